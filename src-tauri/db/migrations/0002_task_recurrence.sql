@@ -0,0 +1,2 @@
+-- Repeating tasks: an RRULE (see recurrence.rs) used to spawn the next instance on completion.
+ALTER TABLE tasks ADD COLUMN recurrence TEXT;
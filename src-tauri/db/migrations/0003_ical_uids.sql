@@ -0,0 +1,8 @@
+-- iCalendar UIDs, so re-importing the same .ics updates rows instead of duplicating them.
+ALTER TABLE calendar_events ADD COLUMN ical_uid TEXT;
+ALTER TABLE tasks ADD COLUMN ical_uid TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_calendar_events_ical_uid
+    ON calendar_events(ical_uid) WHERE ical_uid IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tasks_ical_uid
+    ON tasks(ical_uid) WHERE ical_uid IS NOT NULL;
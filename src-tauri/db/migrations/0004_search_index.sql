@@ -0,0 +1,155 @@
+-- FULL-TEXT SEARCH
+-- One FTS5 table for every searchable entity, so a single MATCH ranks hits across kinds.
+-- kind: note | task | project | inbox
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+    kind UNINDEXED,
+    entity_id UNINDEXED,
+    title,
+    body,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- FTS5 can only look rows up by rowid; filtering on the UNINDEXED columns scans
+-- the whole index. This table hands out each entity's rowid so the triggers can
+-- find its row directly.
+CREATE TABLE IF NOT EXISTS search_index_rows (
+    id INTEGER PRIMARY KEY,
+    kind TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    UNIQUE (kind, entity_id)
+);
+
+INSERT INTO search_index_rows (kind, entity_id)
+    SELECT 'note', id FROM notes
+    UNION ALL SELECT 'task', id FROM tasks
+    UNION ALL SELECT 'project', id FROM projects
+    UNION ALL SELECT 'inbox', id FROM inbox_items;
+
+INSERT INTO search_index (rowid, kind, entity_id, title, body)
+    SELECT r.id, 'note', n.id, n.title, n.content
+    FROM notes n JOIN search_index_rows r ON r.kind = 'note' AND r.entity_id = n.id;
+INSERT INTO search_index (rowid, kind, entity_id, title, body)
+    SELECT r.id, 'task', t.id, t.title, COALESCE(t.notes, '')
+    FROM tasks t JOIN search_index_rows r ON r.kind = 'task' AND r.entity_id = t.id;
+INSERT INTO search_index (rowid, kind, entity_id, title, body)
+    SELECT r.id, 'project', p.id, p.name, COALESCE(p.goal, '')
+    FROM projects p JOIN search_index_rows r ON r.kind = 'project' AND r.entity_id = p.id;
+INSERT INTO search_index (rowid, kind, entity_id, title, body)
+    SELECT r.id, 'inbox', i.id, '', i.content
+    FROM inbox_items i JOIN search_index_rows r ON r.kind = 'inbox' AND r.entity_id = i.id;
+
+-- notes
+CREATE TRIGGER IF NOT EXISTS trg_notes_search_insert
+AFTER INSERT ON notes
+FOR EACH ROW
+BEGIN
+  INSERT INTO search_index_rows (kind, entity_id) VALUES ('note', NEW.id);
+  INSERT INTO search_index (rowid, kind, entity_id, title, body)
+    SELECT id, 'note', NEW.id, NEW.title, NEW.content
+    FROM search_index_rows WHERE kind = 'note' AND entity_id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_notes_search_update
+AFTER UPDATE OF title, content ON notes
+FOR EACH ROW
+BEGIN
+  UPDATE search_index
+    SET title = NEW.title, body = NEW.content
+    WHERE rowid = (SELECT id FROM search_index_rows WHERE kind = 'note' AND entity_id = NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_notes_search_delete
+AFTER DELETE ON notes
+FOR EACH ROW
+BEGIN
+  DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_index_rows WHERE kind = 'note' AND entity_id = OLD.id);
+  DELETE FROM search_index_rows WHERE kind = 'note' AND entity_id = OLD.id;
+END;
+
+-- tasks
+CREATE TRIGGER IF NOT EXISTS trg_tasks_search_insert
+AFTER INSERT ON tasks
+FOR EACH ROW
+BEGIN
+  INSERT INTO search_index_rows (kind, entity_id) VALUES ('task', NEW.id);
+  INSERT INTO search_index (rowid, kind, entity_id, title, body)
+    SELECT id, 'task', NEW.id, NEW.title, COALESCE(NEW.notes, '')
+    FROM search_index_rows WHERE kind = 'task' AND entity_id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_search_update
+AFTER UPDATE OF title, notes ON tasks
+FOR EACH ROW
+BEGIN
+  UPDATE search_index
+    SET title = NEW.title, body = COALESCE(NEW.notes, '')
+    WHERE rowid = (SELECT id FROM search_index_rows WHERE kind = 'task' AND entity_id = NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_search_delete
+AFTER DELETE ON tasks
+FOR EACH ROW
+BEGIN
+  DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_index_rows WHERE kind = 'task' AND entity_id = OLD.id);
+  DELETE FROM search_index_rows WHERE kind = 'task' AND entity_id = OLD.id;
+END;
+
+-- projects
+CREATE TRIGGER IF NOT EXISTS trg_projects_search_insert
+AFTER INSERT ON projects
+FOR EACH ROW
+BEGIN
+  INSERT INTO search_index_rows (kind, entity_id) VALUES ('project', NEW.id);
+  INSERT INTO search_index (rowid, kind, entity_id, title, body)
+    SELECT id, 'project', NEW.id, NEW.name, COALESCE(NEW.goal, '')
+    FROM search_index_rows WHERE kind = 'project' AND entity_id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_projects_search_update
+AFTER UPDATE OF name, goal ON projects
+FOR EACH ROW
+BEGIN
+  UPDATE search_index
+    SET title = NEW.name, body = COALESCE(NEW.goal, '')
+    WHERE rowid = (SELECT id FROM search_index_rows WHERE kind = 'project' AND entity_id = NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_projects_search_delete
+AFTER DELETE ON projects
+FOR EACH ROW
+BEGIN
+  DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_index_rows WHERE kind = 'project' AND entity_id = OLD.id);
+  DELETE FROM search_index_rows WHERE kind = 'project' AND entity_id = OLD.id;
+END;
+
+-- inbox
+CREATE TRIGGER IF NOT EXISTS trg_inbox_search_insert
+AFTER INSERT ON inbox_items
+FOR EACH ROW
+BEGIN
+  INSERT INTO search_index_rows (kind, entity_id) VALUES ('inbox', NEW.id);
+  INSERT INTO search_index (rowid, kind, entity_id, title, body)
+    SELECT id, 'inbox', NEW.id, '', NEW.content
+    FROM search_index_rows WHERE kind = 'inbox' AND entity_id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_inbox_search_update
+AFTER UPDATE OF content ON inbox_items
+FOR EACH ROW
+BEGIN
+  UPDATE search_index
+    SET body = NEW.content
+    WHERE rowid = (SELECT id FROM search_index_rows WHERE kind = 'inbox' AND entity_id = NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_inbox_search_delete
+AFTER DELETE ON inbox_items
+FOR EACH ROW
+BEGIN
+  DELETE FROM search_index
+    WHERE rowid = (SELECT id FROM search_index_rows WHERE kind = 'inbox' AND entity_id = OLD.id);
+  DELETE FROM search_index_rows WHERE kind = 'inbox' AND entity_id = OLD.id;
+END;
@@ -0,0 +1,17 @@
+-- SETTINGS
+-- Per-machine preferences as key/value pairs (e.g. the notes vault directory).
+CREATE TABLE IF NOT EXISTS settings (
+    key   TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+-- VAULT SYNC
+-- What each note and its Markdown file looked like at the last sync (see vault.rs).
+-- Rows deliberately outlive their note, so a deletion on one side can be carried
+-- over to the other.
+CREATE TABLE IF NOT EXISTS vault_files (
+    note_id         TEXT PRIMARY KEY,
+    path            TEXT NOT NULL UNIQUE, -- relative to the vault, '/'-separated
+    note_updated_at TEXT NOT NULL,
+    file_hash       TEXT NOT NULL
+);
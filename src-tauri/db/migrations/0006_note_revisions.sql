@@ -0,0 +1,37 @@
+-- NOTE REVISIONS
+-- The version of a note that an update replaced, or that a delete removed.
+-- No foreign key to notes: the history must outlive the note so a delete can be undone.
+CREATE TABLE IF NOT EXISTS note_revisions (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    note_id         TEXT NOT NULL,
+    title           TEXT NOT NULL,
+    content         TEXT NOT NULL,
+    area_id         TEXT,
+    project_id      TEXT,
+    note_created_at TEXT NOT NULL,
+    edited_at       TEXT NOT NULL, -- when this version was written (the note's updated_at)
+    reason          TEXT NOT NULL CHECK (reason IN ('update', 'delete')),
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_revisions_note ON note_revisions(note_id, id);
+
+CREATE TRIGGER IF NOT EXISTS trg_notes_revision_update
+AFTER UPDATE OF title, content, area_id, project_id ON notes
+FOR EACH ROW
+WHEN OLD.title IS NOT NEW.title
+  OR OLD.content IS NOT NEW.content
+  OR OLD.area_id IS NOT NEW.area_id
+  OR OLD.project_id IS NOT NEW.project_id
+BEGIN
+  INSERT INTO note_revisions (note_id, title, content, area_id, project_id, note_created_at, edited_at, reason)
+    VALUES (OLD.id, OLD.title, OLD.content, OLD.area_id, OLD.project_id, OLD.created_at, OLD.updated_at, 'update');
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_notes_revision_delete
+AFTER DELETE ON notes
+FOR EACH ROW
+BEGIN
+  INSERT INTO note_revisions (note_id, title, content, area_id, project_id, note_created_at, edited_at, reason)
+    VALUES (OLD.id, OLD.title, OLD.content, OLD.area_id, OLD.project_id, OLD.created_at, OLD.updated_at, 'delete');
+END;
@@ -0,0 +1,46 @@
+-- LINKS
+-- [[wiki-links]] found in note content, rebuilt from the text whenever a note is saved.
+-- kind is the prefix as written (note: / task: / project:), NULL when there was none;
+-- target_kind/target_id say what the link resolved to, NULL while nothing matches.
+CREATE TABLE IF NOT EXISTS links (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    source_note_id TEXT NOT NULL,
+    position       INTEGER NOT NULL, -- byte offset of the link in the note's content
+    kind           TEXT CHECK (kind IN ('note', 'task', 'project') OR kind IS NULL),
+    target_text    TEXT NOT NULL,
+    heading        TEXT,
+    label          TEXT,
+    target_kind    TEXT CHECK (target_kind IN ('note', 'task', 'project') OR target_kind IS NULL),
+    target_id      TEXT,
+
+    FOREIGN KEY (source_note_id) REFERENCES notes(id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_links_source ON links(source_note_id, position);
+CREATE INDEX IF NOT EXISTS idx_links_target ON links(target_kind, target_id);
+
+-- A deleted target leaves its links dangling until something else matches them.
+CREATE TRIGGER IF NOT EXISTS trg_notes_links_unresolve
+AFTER DELETE ON notes
+FOR EACH ROW
+BEGIN
+  UPDATE links SET target_kind = NULL, target_id = NULL
+    WHERE target_kind = 'note' AND target_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_links_unresolve
+AFTER DELETE ON tasks
+FOR EACH ROW
+BEGIN
+  UPDATE links SET target_kind = NULL, target_id = NULL
+    WHERE target_kind = 'task' AND target_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_projects_links_unresolve
+AFTER DELETE ON projects
+FOR EACH ROW
+BEGIN
+  UPDATE links SET target_kind = NULL, target_id = NULL
+    WHERE target_kind = 'project' AND target_id = OLD.id;
+END;
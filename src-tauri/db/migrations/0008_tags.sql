@@ -0,0 +1,54 @@
+-- TAGS
+-- Cross-cutting labels (@phone, @errands, waiting) that any task, note, project or
+-- inbox item can carry. Names are unique regardless of case.
+CREATE TABLE IF NOT EXISTS tags (
+    id         TEXT PRIMARY KEY,
+    name       TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+-- entity_kind: task | note | project | inbox. entity_id is not a foreign key since it
+-- points into one of four tables; the triggers below clean up after deletes instead.
+CREATE TABLE IF NOT EXISTS entity_tags (
+    id          TEXT PRIMARY KEY,
+    tag_id      TEXT NOT NULL,
+    entity_kind TEXT NOT NULL CHECK (entity_kind IN ('task', 'note', 'project', 'inbox')),
+    entity_id   TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+
+    UNIQUE (tag_id, entity_kind, entity_id),
+
+    FOREIGN KEY (tag_id) REFERENCES tags(id)
+        ON UPDATE CASCADE
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_entity_tags_entity ON entity_tags(entity_kind, entity_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_tags_delete
+AFTER DELETE ON tasks
+FOR EACH ROW
+BEGIN
+  DELETE FROM entity_tags WHERE entity_kind = 'task' AND entity_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_notes_tags_delete
+AFTER DELETE ON notes
+FOR EACH ROW
+BEGIN
+  DELETE FROM entity_tags WHERE entity_kind = 'note' AND entity_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_projects_tags_delete
+AFTER DELETE ON projects
+FOR EACH ROW
+BEGIN
+  DELETE FROM entity_tags WHERE entity_kind = 'project' AND entity_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_inbox_tags_delete
+AFTER DELETE ON inbox_items
+FOR EACH ROW
+BEGIN
+  DELETE FROM entity_tags WHERE entity_kind = 'inbox' AND entity_id = OLD.id;
+END;
@@ -0,0 +1,17 @@
+-- SMART LISTS
+-- Saved task filters in the query language of `task_query.rs`, e.g.
+-- `status:todo,doing due<+7d sort:due`. The query is stored as typed, so relative
+-- dates are worked out each time the list is run.
+CREATE TABLE IF NOT EXISTS smart_lists (
+    id         TEXT PRIMARY KEY,
+    name       TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    query      TEXT NOT NULL,
+    position   INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+INSERT OR IGNORE INTO smart_lists (id, name, query, position) VALUES
+    ('smart_list_today', 'Today', 'status:todo,doing due<=today sort:due,-priority', 0),
+    ('smart_list_overdue', 'Overdue', 'status:todo,doing due<now sort:due', 1),
+    ('smart_list_waiting', 'Waiting', 'tag:waiting -status:done sort:created', 2);
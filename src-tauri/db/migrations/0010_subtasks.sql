@@ -0,0 +1,39 @@
+-- SUBTASKS
+-- A task can be split into subtasks, a few levels deep (the limit is checked in
+-- store/subtasks.rs, since triggers cannot walk the tree). Subtasks share their
+-- parent's project and go when it is deleted.
+ALTER TABLE tasks ADD COLUMN parent_task_id TEXT
+    REFERENCES tasks(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_parent ON tasks(parent_task_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_prevent_completing_parent_with_open_subtasks
+BEFORE UPDATE OF status ON tasks
+FOR EACH ROW
+WHEN NEW.status = 'done'
+  AND OLD.status != 'done'
+  AND EXISTS (SELECT 1
+              FROM tasks
+              WHERE parent_task_id = OLD.id
+                AND status IN ('todo', 'doing'))
+BEGIN
+  SELECT RAISE(ABORT, 'Cannot complete a task with open subtasks. Complete them first, or complete the task together with its subtasks.');
+END;
+
+-- CHECKLISTS
+-- Lightweight steps inside one task, too small to be tasks of their own.
+CREATE TABLE IF NOT EXISTS checklist_items (
+    id           TEXT PRIMARY KEY,
+    task_id      TEXT NOT NULL,
+    text         TEXT NOT NULL,
+    done         INTEGER NOT NULL DEFAULT 0 CHECK (done IN (0, 1)),
+    position     INTEGER NOT NULL DEFAULT 0,
+    created_at   TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    completed_at TEXT,
+
+    FOREIGN KEY (task_id) REFERENCES tasks(id)
+        ON UPDATE CASCADE
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_checklist_items_task ON checklist_items(task_id, position);
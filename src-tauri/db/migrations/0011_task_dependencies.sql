@@ -0,0 +1,145 @@
+-- TASK DEPENDENCIES
+-- "task_id can't start until depends_on_id is done". A task with an unfinished
+-- dependency is blocked; cycles are refused in store/dependencies.rs.
+CREATE TABLE IF NOT EXISTS task_dependencies (
+    id            TEXT PRIMARY KEY,
+    task_id       TEXT NOT NULL,
+    depends_on_id TEXT NOT NULL,
+    created_at    TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+
+    UNIQUE (task_id, depends_on_id),
+    CHECK (task_id != depends_on_id),
+
+    FOREIGN KEY (task_id) REFERENCES tasks(id)
+        ON UPDATE CASCADE
+        ON DELETE CASCADE,
+    FOREIGN KEY (depends_on_id) REFERENCES tasks(id)
+        ON UPDATE CASCADE
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_dependencies_depends_on
+    ON task_dependencies(depends_on_id);
+
+
+-- NEXT ACTIONS
+-- A blocked task cannot be worked on, so it no longer counts as a project's next
+-- action. The rules from 0001 are rebuilt to count open, unblocked tasks only.
+
+DROP TRIGGER IF EXISTS trg_projects_prevent_active_without_next_action;
+DROP TRIGGER IF EXISTS trg_tasks_prevent_closing_last_open_task_in_active_project;
+DROP TRIGGER IF EXISTS trg_tasks_prevent_deleting_last_open_task_in_active_project;
+
+CREATE TRIGGER IF NOT EXISTS trg_projects_prevent_active_without_next_action
+BEFORE UPDATE OF status ON projects
+FOR EACH ROW
+WHEN NEW.status = 'active'
+  AND NOT EXISTS (SELECT 1
+                  FROM tasks t
+                  WHERE t.project_id = NEW.id
+                    AND t.status IN ('todo', 'doing')
+                    AND NOT EXISTS (SELECT 1
+                                    FROM task_dependencies d
+                                    JOIN tasks b ON b.id = d.depends_on_id
+                                    WHERE d.task_id = t.id
+                                      AND b.status != 'done'))
+BEGIN
+  SELECT RAISE(ABORT, 'Cannot activate project without an unblocked open task (next action required).');
+END;
+
+-- Completing a task unblocks the tasks waiting on it, which then count; deferring
+-- it does not.
+CREATE TRIGGER IF NOT EXISTS trg_tasks_prevent_closing_last_open_task_in_active_project
+BEFORE UPDATE OF status ON tasks
+FOR EACH ROW
+WHEN OLD.project_id IS NOT NULL
+  AND OLD.status IN ('todo', 'doing')
+  AND NEW.status IN ('done', 'deferred')
+  AND (SELECT status FROM projects WHERE id = OLD.project_id) = 'active'
+  AND NOT EXISTS (SELECT 1
+                  FROM tasks t
+                  WHERE t.project_id = OLD.project_id
+                    AND t.status IN ('todo', 'doing')
+                    AND t.id != OLD.id
+                    AND NOT EXISTS (SELECT 1
+                                    FROM task_dependencies d
+                                    JOIN tasks b ON b.id = d.depends_on_id
+                                    WHERE d.task_id = t.id
+                                      AND b.status != 'done'
+                                      AND NOT (b.id = OLD.id AND NEW.status = 'done')))
+BEGIN
+  SELECT RAISE(ABORT, 'Active project must keep at least one unblocked open task. Add a next action or pause/complete the project first.');
+END;
+
+-- Deleting a task drops the dependencies on it, so its dependents count.
+CREATE TRIGGER IF NOT EXISTS trg_tasks_prevent_deleting_last_open_task_in_active_project
+BEFORE DELETE ON tasks
+FOR EACH ROW
+WHEN OLD.project_id IS NOT NULL
+  AND OLD.status IN ('todo', 'doing')
+  AND (SELECT status FROM projects WHERE id = OLD.project_id) = 'active'
+  AND NOT EXISTS (SELECT 1
+                  FROM tasks t
+                  WHERE t.project_id = OLD.project_id
+                    AND t.status IN ('todo', 'doing')
+                    AND t.id != OLD.id
+                    AND NOT EXISTS (SELECT 1
+                                    FROM task_dependencies d
+                                    JOIN tasks b ON b.id = d.depends_on_id
+                                    WHERE d.task_id = t.id
+                                      AND b.status != 'done'
+                                      AND b.id != OLD.id))
+BEGIN
+  SELECT RAISE(ABORT, 'Cannot delete last open task in an active project. Add a next action or pause/complete the project first.');
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_task_dependencies_prevent_blocking_last_next_action
+BEFORE INSERT ON task_dependencies
+FOR EACH ROW
+WHEN (SELECT status FROM tasks WHERE id = NEW.depends_on_id) != 'done'
+  AND (SELECT status FROM tasks WHERE id = NEW.task_id) IN ('todo', 'doing')
+  AND (SELECT p.status
+       FROM projects p JOIN tasks t ON t.project_id = p.id
+       WHERE t.id = NEW.task_id) = 'active'
+  AND NOT EXISTS (SELECT 1
+                  FROM tasks t
+                  WHERE t.project_id = (SELECT project_id FROM tasks WHERE id = NEW.task_id)
+                    AND t.status IN ('todo', 'doing')
+                    AND t.id != NEW.task_id
+                    AND NOT EXISTS (SELECT 1
+                                    FROM task_dependencies d
+                                    JOIN tasks b ON b.id = d.depends_on_id
+                                    WHERE d.task_id = t.id
+                                      AND b.status != 'done'))
+BEGIN
+  SELECT RAISE(ABORT, 'Cannot block the last unblocked open task of an active project. Add another next action or pause the project first.');
+END;
+
+-- Reopening a finished task blocks its dependents again.
+CREATE TRIGGER IF NOT EXISTS trg_tasks_prevent_reopening_into_blocked_active_project
+BEFORE UPDATE OF status ON tasks
+FOR EACH ROW
+WHEN OLD.status = 'done'
+  AND NEW.status != 'done'
+  AND EXISTS (SELECT 1
+              FROM projects p
+              WHERE p.status = 'active'
+                AND EXISTS (SELECT 1
+                            FROM task_dependencies d
+                            JOIN tasks w ON w.id = d.task_id
+                            WHERE d.depends_on_id = OLD.id
+                              AND w.project_id = p.id
+                              AND w.status IN ('todo', 'doing'))
+                AND NOT EXISTS (SELECT 1
+                                FROM tasks t
+                                WHERE t.project_id = p.id
+                                  AND (t.status IN ('todo', 'doing')
+                                       OR (t.id = OLD.id AND NEW.status IN ('todo', 'doing')))
+                                  AND NOT EXISTS (SELECT 1
+                                                  FROM task_dependencies d
+                                                  JOIN tasks b ON b.id = d.depends_on_id
+                                                  WHERE d.task_id = t.id
+                                                    AND (b.status != 'done' OR b.id = OLD.id))))
+BEGIN
+  SELECT RAISE(ABORT, 'Reopening this task would leave an active project without an unblocked open task. Add a next action or pause the project first.');
+END;
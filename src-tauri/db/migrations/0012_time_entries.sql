@@ -0,0 +1,29 @@
+-- TIME ENTRIES
+-- Time actually spent on a task, from a timer or entered by hand. An entry without
+-- ended_at is the running timer, and there is at most one. The running entry is
+-- stamped with last_seen_at while the app is up; if the app went away with a timer
+-- running, the next launch sets interrupted_at to the last stamp so the user can
+-- decide whether the gap counts.
+CREATE TABLE IF NOT EXISTS time_entries (
+    id             TEXT PRIMARY KEY,
+    task_id        TEXT NOT NULL,
+    started_at     TEXT NOT NULL,
+    ended_at       TEXT,
+    note           TEXT,
+    last_seen_at   TEXT,
+    interrupted_at TEXT,
+    created_at     TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+
+    CHECK (ended_at IS NULL OR ended_at >= started_at),
+
+    FOREIGN KEY (task_id) REFERENCES tasks(id)
+        ON UPDATE CASCADE
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_time_entries_task ON time_entries(task_id, started_at);
+CREATE INDEX IF NOT EXISTS idx_time_entries_started ON time_entries(started_at);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_time_entries_one_running
+    ON time_entries((ended_at IS NULL))
+    WHERE ended_at IS NULL;
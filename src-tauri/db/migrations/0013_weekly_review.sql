@@ -0,0 +1,62 @@
+-- TASK TOUCH STAMPS
+-- When a task was last edited, so a review can find tasks nobody has looked at in
+-- a while. Existing tasks start from their completion or creation time.
+ALTER TABLE tasks ADD COLUMN updated_at TEXT;
+
+UPDATE tasks SET updated_at = COALESCE(completed_at, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_tasks_updated_at ON tasks(updated_at);
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_stamp_updated_at
+AFTER INSERT ON tasks
+FOR EACH ROW
+WHEN NEW.updated_at IS NULL
+BEGIN
+  UPDATE tasks
+    SET updated_at = NEW.created_at
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_tasks_touch_updated_at
+AFTER UPDATE OF title, status, priority, due_at, scheduled_at, estimate_minutes, notes,
+                area_id, project_id, recurrence, parent_task_id ON tasks
+FOR EACH ROW
+BEGIN
+  UPDATE tasks
+    SET updated_at = (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+    WHERE id = NEW.id;
+END;
+
+-- WEEKLY REVIEWS
+-- A guided review goes through its steps in order; `step` is where it stands and
+-- becomes 'done' when it is completed. At most one review is in progress.
+CREATE TABLE IF NOT EXISTS weekly_reviews (
+    id           TEXT PRIMARY KEY,
+    step         TEXT NOT NULL DEFAULT 'inbox'
+                 CHECK (step IN ('inbox', 'projects', 'stale_tasks', 'deferred_tasks', 'calendar', 'done')),
+    stale_days   INTEGER NOT NULL DEFAULT 30 CHECK (stale_days > 0),
+    started_at   TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    completed_at TEXT,
+
+    CHECK ((step = 'done') = (completed_at IS NOT NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_weekly_reviews_one_open
+    ON weekly_reviews((completed_at IS NULL))
+    WHERE completed_at IS NULL;
+
+-- What was ticked off in each step. entity_id points at a project, task or
+-- calendar event; there is no foreign key so the history survives deletes.
+CREATE TABLE IF NOT EXISTS weekly_review_items (
+    id          TEXT PRIMARY KEY,
+    review_id   TEXT NOT NULL,
+    step        TEXT NOT NULL,
+    entity_id   TEXT NOT NULL,
+    reviewed_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+
+    UNIQUE (review_id, step, entity_id),
+
+    FOREIGN KEY (review_id) REFERENCES weekly_reviews(id)
+        ON UPDATE CASCADE
+        ON DELETE CASCADE
+);
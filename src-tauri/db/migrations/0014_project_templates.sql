@@ -0,0 +1,29 @@
+-- PROJECT TEMPLATES
+-- Projects that come round again (client onboarding, monthly close, trip prep).
+-- A template holds the tasks to create, with due dates as day offsets from an
+-- anchor date picked when it is used, and an optional starter note.
+CREATE TABLE IF NOT EXISTS project_templates (
+    id          TEXT PRIMARY KEY,
+    name        TEXT NOT NULL UNIQUE,
+    note        TEXT,
+    created_at  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+CREATE TABLE IF NOT EXISTS project_template_tasks (
+    id               TEXT PRIMARY KEY,
+    template_id      TEXT NOT NULL,
+    position         INTEGER NOT NULL DEFAULT 0,
+    title            TEXT NOT NULL,
+    priority         TEXT NOT NULL DEFAULT 'normal' CHECK (priority IN ('low', 'normal', 'high')),
+    due_offset_days  INTEGER, -- negative for tasks due before the anchor date
+    estimate_minutes INTEGER CHECK (estimate_minutes >= 0 OR estimate_minutes IS NULL),
+    notes            TEXT,
+
+    FOREIGN KEY (template_id) REFERENCES project_templates(id)
+        ON UPDATE CASCADE
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_template_tasks_template
+    ON project_template_tasks(template_id, position);
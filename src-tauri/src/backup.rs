@@ -0,0 +1,259 @@
+//! Point-in-time copies of the database, kept in the `backups/` directory next to
+//! it. They are taken with SQLite's online backup API, so a copy is consistent
+//! even while the app keeps writing.
+//!
+//! File names carry the time and the kind, e.g. `auralis-20261018T093000123Z-daily.db`
+//! or `auralis-20261018T093000123Z-pre-note-delete.db`. Each kind keeps only its
+//! most recent copies (see `RETENTION`).
+
+use crate::datetime;
+use crate::error::AuralisError;
+use crate::migrations;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use rusqlite::backup::{Backup, Progress};
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where backups are written. Managed as app state alongside [`crate::db::Db`].
+pub struct BackupDir(pub PathBuf);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupKind {
+    Manual,
+    Daily,
+    Weekly,
+    /// Taken automatically before a migration, a restore or a destructive command.
+    Safety,
+}
+
+const RETENTION: &[(BackupKind, usize)] = &[
+    (BackupKind::Manual, 10),
+    (BackupKind::Daily, 7),
+    (BackupKind::Weekly, 4),
+    (BackupKind::Safety, 10),
+];
+
+#[derive(Debug, Serialize)]
+pub struct BackupInfo {
+    pub id: String, // file name without `.db`
+    pub kind: BackupKind,
+    pub reason: Option<String>, // what a safety backup was taken before, e.g. "note-delete"
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+struct Entry {
+    at: DateTime<Utc>,
+    path: PathBuf,
+    info: BackupInfo,
+}
+
+fn io_error(what: &str, path: &Path, e: std::io::Error) -> AuralisError {
+    AuralisError::Io(format!("Failed to {what} {}: {e}", path.display()))
+}
+
+fn parse_name(stem: &str) -> Option<(DateTime<Utc>, BackupKind, Option<String>)> {
+    let rest = stem.strip_prefix("auralis-")?;
+    let (stamp, tag) = rest.split_once('-')?;
+    let at = NaiveDateTime::parse_from_str(stamp, "%Y%m%dT%H%M%S%3fZ").ok()?;
+    let (kind, reason) = match tag {
+        "manual" => (BackupKind::Manual, None),
+        "daily" => (BackupKind::Daily, None),
+        "weekly" => (BackupKind::Weekly, None),
+        _ => {
+            let reason = tag.strip_prefix("pre")?.trim_start_matches('-');
+            (BackupKind::Safety, (!reason.is_empty()).then(|| reason.to_string()))
+        }
+    };
+    Some((at.and_utc(), kind, reason))
+}
+
+/// All recognised backups in `dir`, newest first. Other files are ignored.
+fn scan(dir: &Path) -> Result<Vec<Entry>, AuralisError> {
+    let read = match fs::read_dir(dir) {
+        Ok(read) => read,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(io_error("read", dir, e)),
+    };
+
+    let mut entries = Vec::new();
+    for item in read {
+        let path = item.map_err(|e| io_error("read", dir, e))?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("db") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Some((at, kind, reason)) = parse_name(stem) else {
+            continue;
+        };
+
+        let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let info = BackupInfo {
+            id: stem.to_string(),
+            kind,
+            reason,
+            created_at: datetime::format_utc(at),
+            size_bytes,
+        };
+        entries.push(Entry { at, path, info });
+    }
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.at));
+    Ok(entries)
+}
+
+pub fn list(dir: &Path) -> Result<Vec<BackupInfo>, AuralisError> {
+    Ok(scan(dir)?.into_iter().map(|e| e.info).collect())
+}
+
+// Copies the live database to a new file in `dir` without pruning, so a caller
+// that still needs an older backup (restore) can prune once it is done.
+fn write(conn: &Connection, dir: &Path, tag: &str) -> Result<BackupInfo, AuralisError> {
+    fs::create_dir_all(dir).map_err(|e| io_error("create", dir, e))?;
+
+    // Names are unique to the millisecond; wait out a clash rather than overwrite.
+    let (stem, path) = loop {
+        let stem = format!("auralis-{}-{tag}", Utc::now().format("%Y%m%dT%H%M%S%3fZ"));
+        let path = dir.join(format!("{stem}.db"));
+        if !path.exists() {
+            break (stem, path);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    };
+    // Written under a temporary name so an interrupted copy never looks like a backup.
+    let part = dir.join(format!("{stem}.db.part"));
+
+    let copy = || -> Result<(), AuralisError> {
+        let mut dst = Connection::open(&part)?;
+        Backup::new(conn, &mut dst)?.run_to_completion(256, std::time::Duration::ZERO, None)?;
+        // The copy inherits WAL mode from the live database; switch it back so the
+        // backup is a single self-contained file.
+        dst.pragma_update(None, "journal_mode", "DELETE")?;
+        Ok(())
+    };
+    if let Err(e) = copy() {
+        let _ = fs::remove_file(&part);
+        return Err(e);
+    }
+    fs::rename(&part, &path).map_err(|e| io_error("write", &path, e))?;
+
+    scan(dir)?
+        .into_iter()
+        .find(|e| e.path == path)
+        .map(|e| e.info)
+        .ok_or_else(|| AuralisError::not_found("Backup"))
+}
+
+/// Deletes the oldest backups of each kind beyond its retention count.
+fn prune(dir: &Path) -> Result<(), AuralisError> {
+    let entries = scan(dir)?;
+    for (kind, keep) in RETENTION {
+        for old in entries.iter().filter(|e| e.info.kind == *kind).skip(*keep) {
+            fs::remove_file(&old.path).map_err(|e| io_error("delete", &old.path, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Takes a backup of the given kind. Safety backups should go through [`before`],
+/// which records what they were taken for.
+pub fn create(conn: &Connection, dir: &Path, kind: BackupKind) -> Result<BackupInfo, AuralisError> {
+    let tag = match kind {
+        BackupKind::Manual => "manual",
+        BackupKind::Daily => "daily",
+        BackupKind::Weekly => "weekly",
+        BackupKind::Safety => "pre",
+    };
+    let info = write(conn, dir, tag)?;
+    prune(dir)?;
+    Ok(info)
+}
+
+/// Takes a safety backup ahead of `operation` (e.g. "note-delete").
+pub fn before(conn: &Connection, dir: &Path, operation: &str) -> Result<BackupInfo, AuralisError> {
+    let info = write(conn, dir, &format!("pre-{operation}"))?;
+    prune(dir)?;
+    Ok(info)
+}
+
+/// Takes the daily and weekly backups when the newest of each kind is more than a
+/// day or a week old. Returns the backups it created.
+pub fn run_scheduled(conn: &Connection, dir: &Path) -> Result<Vec<BackupInfo>, AuralisError> {
+    let now = Utc::now();
+    let existing = scan(dir)?;
+
+    let mut created = Vec::new();
+    for (kind, every) in [(BackupKind::Daily, Duration::days(1)), (BackupKind::Weekly, Duration::weeks(1))] {
+        let newest = existing.iter().find(|e| e.info.kind == kind).map(|e| e.at);
+        if newest.is_none_or(|at| now - at >= every) {
+            created.push(create(conn, dir, kind)?);
+        }
+    }
+
+    Ok(created)
+}
+
+fn path_of(dir: &Path, id: &str) -> Result<PathBuf, AuralisError> {
+    let path = dir.join(format!("{id}.db"));
+    if id.contains(['/', '\\']) || parse_name(id).is_none() || !path.is_file() {
+        return Err(AuralisError::not_found("Backup"));
+    }
+    Ok(path)
+}
+
+/// Checks that a backup is an intact database this version of Auralis can open.
+fn verify(path: &Path, id: &str) -> Result<(), AuralisError> {
+    let unreadable = |e: rusqlite::Error| AuralisError::validation(format!("Backup {id} is not a readable database: {e}"));
+
+    // The file itself is only read. The checks run on an in-memory copy of its
+    // pages, as checking the FTS5 search index needs a writable handle.
+    let file = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(unreadable)?;
+    let mut src = Connection::open_in_memory()?;
+    Backup::new(&file, &mut src)
+        .and_then(|b| b.run_to_completion(256, std::time::Duration::ZERO, None))
+        .map_err(unreadable)?;
+
+    let mut stmt = src.prepare("PRAGMA integrity_check").map_err(unreadable)?;
+    let problems = stmt
+        .query_map([], |r| r.get::<_, String>(0))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(unreadable)?;
+    if problems != ["ok"] {
+        return Err(AuralisError::validation(format!(
+            "Backup {id} failed the integrity check: {}",
+            problems.join("; ")
+        )));
+    }
+
+    let version = migrations::current_version(&src).map_err(AuralisError::Db)?;
+    if version > migrations::latest_version() {
+        return Err(AuralisError::validation(format!(
+            "Backup {id} was written by a newer version of Auralis (schema {version})"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Replaces the live database with backup `id`, after checking it with
+/// `PRAGMA integrity_check`. The current state is saved as a safety backup first,
+/// which is returned so the restore itself can be undone. Backups from older
+/// versions are migrated once restored.
+pub fn restore(conn: &mut Connection, dir: &Path, id: &str) -> Result<BackupInfo, AuralisError> {
+    let path = path_of(dir, id)?;
+    verify(&path, id)?;
+
+    let safety = write(conn, dir, "pre-restore")?;
+
+    conn.restore(DatabaseName::Main, &path, None::<fn(Progress)>)?;
+    migrations::migrate(conn, None).map_err(AuralisError::Db)?;
+    crate::store::links::backfill(conn)?;
+
+    prune(dir)?;
+    Ok(safety)
+}
@@ -0,0 +1,18 @@
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::store::agenda::{self, Agenda};
+use tauri::State;
+
+/// Overdue tasks plus tasks and calendar occurrences for the days `start` through
+/// `end` (YYYY-MM-DD, inclusive), bucketed by day in `time_zone` (IANA name, or the
+/// system time zone when omitted).
+#[tauri::command]
+pub async fn agenda(
+    db: State<'_, Db>,
+    start: String,
+    end: Option<String>,
+    time_zone: Option<String>,
+) -> Result<Agenda, AuralisError> {
+    let conn = db.0.lock().await;
+    agenda::agenda(&conn, &start, end.as_deref(), time_zone.as_deref())
+}
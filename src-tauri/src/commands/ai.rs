@@ -1,22 +1,16 @@
 use crate::ai::ollama;
 use crate::db::Db;
-use rusqlite::params;
+use crate::error::AuralisError;
+use crate::store::notes;
 use tauri::State;
 
 #[tauri::command]
-pub async fn ai_summarise_note(db: State<'_, Db>, note_id: String) -> Result<String, String> {
+pub async fn ai_summarise_note(db: State<'_, Db>, note_id: String) -> Result<String, AuralisError> {
     // 1) Load note content
-    let conn = db.0.lock().await;
-
-    let (title, content): (String, String) = conn
-        .query_row(
-            "SELECT title, content FROM notes WHERE id = ?1",
-            params![note_id],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )
-        .map_err(|e| e.to_string())?;
-
-    drop(conn); // release DB lock before network call
+    let note = {
+        let conn = db.0.lock().await;
+        notes::get(&conn, &note_id)?
+    }; // DB lock released before the network call
 
     // 2) Call Ollama
     let system = r#"You are Auralis, a personal command-center assistant.
@@ -45,12 +39,14 @@ pub async fn ai_summarise_note(db: State<'_, Db>, note_id: String) -> Result<Str
         Do not add anything else."#;
     let user = format!(
         "Summarise this note.\n\nTitle: {}\n\nContent:\n{}",
-        title, content
+        note.title, note.content
     );
 
     // Set your default model here
     let model = "llama3.1:8b";
-    let summary = ollama::chat(model, system, &user).await?;
+    let summary = ollama::chat(model, system, &user)
+        .await
+        .map_err(AuralisError::Ai)?;
 
     Ok(summary)
 }
\ No newline at end of file
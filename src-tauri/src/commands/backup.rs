@@ -0,0 +1,61 @@
+use crate::backup::{self, BackupDir, BackupInfo, BackupKind};
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::vault;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[tauri::command]
+pub async fn backup_create(
+    db: State<'_, Db>,
+    dir: State<'_, BackupDir>,
+) -> Result<BackupInfo, AuralisError> {
+    let conn = db.0.lock().await;
+    backup::create(&conn, &dir.0, BackupKind::Manual)
+}
+
+/// Every backup on disk, newest first.
+#[tauri::command]
+pub async fn backup_list(dir: State<'_, BackupDir>) -> Result<Vec<BackupInfo>, AuralisError> {
+    backup::list(&dir.0)
+}
+
+/// Swaps backup `id` in for the live database. Returns the safety backup of the
+/// state it replaced. The vault is then rewritten from the restored notes; how
+/// that went arrives as `vault:synced` or `vault:error`, as for a sync pass.
+#[tauri::command]
+pub async fn backup_restore(
+    app: AppHandle,
+    db: State<'_, Db>,
+    dir: State<'_, BackupDir>,
+    id: String,
+) -> Result<BackupInfo, AuralisError> {
+    let mut conn = db.0.lock().await;
+    let safety = backup::restore(&mut conn, &dir.0, &id)?;
+    match vault::replace_configured(&mut conn, &dir.0) {
+        Ok(Some(summary)) if !summary.is_empty() => {
+            let _ = app.emit("vault:synced", &summary);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            let _ = app.emit("vault:error", e.to_string());
+        }
+    }
+    Ok(safety)
+}
+
+/// Background task started at launch: takes any due daily/weekly backup, then
+/// checks again every hour.
+pub async fn run_schedule(app: AppHandle) {
+    loop {
+        {
+            let db = app.state::<Db>();
+            let dir = app.state::<BackupDir>();
+            let conn = db.0.lock().await;
+            if let Err(e) = backup::run_scheduled(&conn, &dir.0) {
+                eprintln!("Scheduled backup failed: {e}");
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(60 * 60)).await;
+    }
+}
@@ -0,0 +1,126 @@
+use crate::backup::{self, BackupDir};
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::store::calendar::{self, CalendarEventInput, CalendarEventItem, CalendarOccurrence};
+use tauri::State;
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn calendar_event_add(
+    db: State<'_, Db>,
+    title: String,
+    start_at: String,
+    end_at: String,
+    event_type: Option<String>, // event | block (default: event)
+    task_id: Option<String>,
+    area_id: Option<String>,
+    location: Option<String>,
+    recurrence: Option<String>,
+) -> Result<String, AuralisError> {
+    let input = CalendarEventInput {
+        title,
+        start_at,
+        end_at,
+        event_type,
+        task_id,
+        area_id,
+        location,
+    };
+    let conn = db.0.lock().await;
+    calendar::add(&conn, input, recurrence)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn calendar_event_update(
+    db: State<'_, Db>,
+    id: String,
+    title: String,
+    start_at: String,
+    end_at: String,
+    event_type: Option<String>,
+    task_id: Option<String>,
+    area_id: Option<String>,
+    location: Option<String>,
+    recurrence: Option<String>,
+) -> Result<(), AuralisError> {
+    let input = CalendarEventInput {
+        title,
+        start_at,
+        end_at,
+        event_type,
+        task_id,
+        area_id,
+        location,
+    };
+    let conn = db.0.lock().await;
+    calendar::update(&conn, &id, input, recurrence)
+}
+
+#[tauri::command]
+pub async fn calendar_event_delete(
+    db: State<'_, Db>,
+    backups: State<'_, BackupDir>,
+    id: String,
+) -> Result<(), AuralisError> {
+    let conn = db.0.lock().await;
+    backup::before(&conn, &backups.0, "event-delete")?;
+    calendar::delete(&conn, &id)
+}
+
+#[tauri::command]
+pub async fn calendar_event_get(db: State<'_, Db>, id: String) -> Result<CalendarEventItem, AuralisError> {
+    let conn = db.0.lock().await;
+    calendar::get(&conn, &id)
+}
+
+/// Occurrences overlapping the half-open range [start, end), with recurring
+/// series expanded in local time.
+#[tauri::command]
+pub async fn calendar_list_range(
+    db: State<'_, Db>,
+    start: String,
+    end: String,
+) -> Result<Vec<CalendarOccurrence>, AuralisError> {
+    let conn = db.0.lock().await;
+    calendar::list_range(&conn, &start, &end)
+}
+
+/// Edits one instance of a recurring event; see [`calendar::update_occurrence`].
+#[tauri::command]
+pub async fn calendar_event_update_occurrence(
+    db: State<'_, Db>,
+    id: String,
+    occurrence_start: String,
+    scope: String,
+    changes: CalendarEventInput,
+) -> Result<String, AuralisError> {
+    let mut conn = db.0.lock().await;
+    calendar::update_occurrence(&mut conn, &id, &occurrence_start, &scope, changes)
+}
+
+/// Deletes one instance, this and all following instances, or the whole series.
+#[tauri::command]
+pub async fn calendar_event_delete_occurrence(
+    db: State<'_, Db>,
+    backups: State<'_, BackupDir>,
+    id: String,
+    occurrence_start: String,
+    scope: String,
+) -> Result<(), AuralisError> {
+    let conn = db.0.lock().await;
+    backup::before(&conn, &backups.0, "event-delete")?;
+    calendar::delete_occurrence(&conn, &id, &occurrence_start, &scope)
+}
+
+/// Creates a time block for a task, sized from its estimate when `end_at` is omitted.
+#[tauri::command]
+pub async fn calendar_block_from_task(
+    db: State<'_, Db>,
+    task_id: String,
+    start_at: String,
+    end_at: Option<String>,
+) -> Result<String, AuralisError> {
+    let conn = db.0.lock().await;
+    calendar::block_from_task(&conn, &task_id, &start_at, end_at.as_deref())
+}
@@ -0,0 +1,44 @@
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::store::checklists::{self, ChecklistItem};
+use tauri::State;
+
+#[tauri::command]
+pub async fn checklist_list(db: State<'_, Db>, task_id: String) -> Result<Vec<ChecklistItem>, AuralisError> {
+    let conn = db.0.lock().await;
+    checklists::list(&conn, &task_id)
+}
+
+#[tauri::command]
+pub async fn checklist_add(db: State<'_, Db>, task_id: String, text: String) -> Result<ChecklistItem, AuralisError> {
+    let conn = db.0.lock().await;
+    checklists::add(&conn, &task_id, &text)
+}
+
+/// Rewords and/or ticks an item; omitted fields are left alone.
+#[tauri::command]
+pub async fn checklist_update(
+    db: State<'_, Db>,
+    id: String,
+    text: Option<String>,
+    done: Option<bool>,
+) -> Result<ChecklistItem, AuralisError> {
+    let conn = db.0.lock().await;
+    checklists::update(&conn, &id, text.as_deref(), done)
+}
+
+#[tauri::command]
+pub async fn checklist_delete(db: State<'_, Db>, id: String) -> Result<(), AuralisError> {
+    let conn = db.0.lock().await;
+    checklists::delete(&conn, &id)
+}
+
+#[tauri::command]
+pub async fn checklist_reorder(
+    db: State<'_, Db>,
+    task_id: String,
+    ids: Vec<String>,
+) -> Result<Vec<ChecklistItem>, AuralisError> {
+    let mut conn = db.0.lock().await;
+    checklists::reorder(&mut conn, &task_id, &ids)
+}
@@ -0,0 +1,137 @@
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::focus::{self, FocusConfig, FocusEvent, FocusSession, FocusStatus};
+use crate::store::tasks;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+
+/// Managed state: the focus session in progress, if any. It lives here rather than
+/// in the webview so reloading the window does not lose it.
+#[derive(Default)]
+pub struct Focus(pub Mutex<Option<FocusSession>>);
+
+// Logs the work in `events` and emits the rest to the webview.
+async fn publish(app: &AppHandle, session: &FocusSession, events: Vec<FocusEvent>) -> Result<(), AuralisError> {
+    let logged = {
+        let db = app.state::<Db>();
+        let conn = db.0.lock().await;
+        focus::record(&conn, session, &events)
+    };
+    for event in events {
+        match event {
+            FocusEvent::PhaseChanged(change) => {
+                let _ = app.emit("focus:phase_changed", change);
+            }
+            FocusEvent::Completed(summary) => {
+                let _ = app.emit("focus:completed", summary);
+            }
+            FocusEvent::Worked { .. } => {}
+        }
+    }
+    logged.map(|_| ())
+}
+
+// Applies `change` to the running session and publishes what it caused. A session
+// that is over is cleared, which also ends its clock.
+async fn step(
+    app: &AppHandle,
+    focus: &Focus,
+    change: impl FnOnce(&mut FocusSession, DateTime<Utc>) -> Result<Vec<FocusEvent>, AuralisError>,
+) -> Result<FocusStatus, AuralisError> {
+    let mut current = focus.0.lock().await;
+    let session = current.as_mut().ok_or_else(|| AuralisError::not_found("Focus session"))?;
+    let now = Utc::now();
+    let events = change(session, now)?;
+    publish(app, session, events).await?;
+
+    let status = session.status(now);
+    if session.is_finished() {
+        *current = None;
+    }
+    Ok(status)
+}
+
+/// Starts a focus session on a task; `config` defaults to 4 × 25 minutes.
+#[tauri::command]
+pub async fn focus_start(
+    app: AppHandle,
+    db: State<'_, Db>,
+    focus: State<'_, Focus>,
+    task_id: String,
+    config: Option<FocusConfig>,
+) -> Result<FocusStatus, AuralisError> {
+    let mut current = focus.0.lock().await;
+    if current.is_some() {
+        return Err(AuralisError::validation(
+            "A focus session is already running. Stop it before starting another",
+        ));
+    }
+
+    let task = {
+        let conn = db.0.lock().await;
+        tasks::get(&conn, &task_id)?
+    };
+    let now = Utc::now();
+    let session = FocusSession::new(&task.id, &task.title, config.unwrap_or_default(), now)?;
+    let status = session.status(now);
+    tauri::async_runtime::spawn(run_clock(app, session.id.clone()));
+    *current = Some(session);
+    Ok(status)
+}
+
+/// The session in progress, e.g. for a window that was just reloaded.
+#[tauri::command]
+pub async fn focus_status(focus: State<'_, Focus>) -> Result<Option<FocusStatus>, AuralisError> {
+    let current = focus.0.lock().await;
+    Ok(current.as_ref().map(|s| s.status(Utc::now())))
+}
+
+#[tauri::command]
+pub async fn focus_pause(app: AppHandle, focus: State<'_, Focus>) -> Result<FocusStatus, AuralisError> {
+    step(&app, &focus, |session, now| session.pause(now)).await
+}
+
+#[tauri::command]
+pub async fn focus_resume(app: AppHandle, focus: State<'_, Focus>) -> Result<FocusStatus, AuralisError> {
+    step(&app, &focus, |session, now| session.resume(now).map(|_| Vec::new())).await
+}
+
+/// Ends the current phase now.
+#[tauri::command]
+pub async fn focus_skip(app: AppHandle, focus: State<'_, Focus>) -> Result<FocusStatus, AuralisError> {
+    step(&app, &focus, |session, now| Ok(session.skip(now))).await
+}
+
+/// Ends the session early; work done so far is still logged.
+#[tauri::command]
+pub async fn focus_stop(app: AppHandle, focus: State<'_, Focus>) -> Result<FocusStatus, AuralisError> {
+    step(&app, &focus, |session, now| Ok(session.stop(now))).await
+}
+
+/// The clock of one session, started with it: once a second it moves the session
+/// along and emits `focus:tick`. It ends when that session is over or replaced.
+async fn run_clock(app: AppHandle, session_id: String) {
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        ticker.tick().await;
+
+        let focus = app.state::<Focus>();
+        let mut current = focus.0.lock().await;
+        let Some(session) = current.as_mut().filter(|s| s.id == session_id) else {
+            break;
+        };
+
+        let now = Utc::now();
+        let events = session.advance(now);
+        if let Err(e) = publish(&app, session, events).await {
+            eprintln!("Failed to log focus time: {e}");
+        }
+        if session.is_finished() {
+            *current = None;
+            break;
+        }
+        let _ = app.emit("focus:tick", session.status(now));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::backup::{self, BackupDir};
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::store::ical::{self, IcalImportSummary};
+use serde::Serialize;
+use std::fs;
+use tauri::State;
+
+#[derive(Serialize)]
+pub struct IcalExportSummary {
+    pub path: String,
+    pub events: u32,
+    pub tasks: u32,
+}
+
+/// Imports VEVENTs into `calendar_events` and VTODOs into `tasks`. Items are matched
+/// by UID, so importing the same file again updates rather than duplicates.
+/// Items that fail are reported in `warnings` and do not abort the rest.
+#[tauri::command]
+pub async fn ical_import(
+    db: State<'_, Db>,
+    backups: State<'_, BackupDir>,
+    path: String,
+    area_id: Option<String>, // area for imported tasks (default: Admin / Life)
+) -> Result<IcalImportSummary, AuralisError> {
+    let text = fs::read_to_string(&path)
+        .map_err(|e| AuralisError::Io(format!("Failed to read {path}: {e}")))?;
+
+    let mut conn = db.0.lock().await;
+    // Matching UIDs overwrite existing rows.
+    backup::before(&conn, &backups.0, "ical-import")?;
+    ical::import(&mut conn, &text, area_id.as_deref())
+}
+
+/// Writes all calendar events plus every task with a due date to an .ics file.
+#[tauri::command]
+pub async fn ical_export(db: State<'_, Db>, path: String) -> Result<IcalExportSummary, AuralisError> {
+    let export = {
+        let conn = db.0.lock().await;
+        ical::export(&conn)?
+    };
+
+    fs::write(&path, export.text)
+        .map_err(|e| AuralisError::Io(format!("Failed to write {path}: {e}")))?;
+
+    Ok(IcalExportSummary {
+        path,
+        events: export.events,
+        tasks: export.tasks,
+    })
+}
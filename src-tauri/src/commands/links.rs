@@ -0,0 +1,12 @@
+use crate::db::Db;
+use crate::domain::LinkKind;
+use crate::error::AuralisError;
+use crate::store::links::{self, EntityLinks};
+use tauri::State;
+
+/// `[[links]]` out of a note and backlinks into any note, task or project.
+#[tauri::command]
+pub async fn entity_links(db: State<'_, Db>, kind: LinkKind, id: String) -> Result<EntityLinks, AuralisError> {
+    let conn = db.0.lock().await;
+    links::entity_links(&conn, kind, &id)
+}
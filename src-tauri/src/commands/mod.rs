@@ -1,6 +1,21 @@
+pub mod agenda;
 pub mod ai;
 pub mod areas;
+pub mod backup;
+pub mod calendar;
+pub mod checklists;
+pub mod focus;
+pub mod ical;
 pub mod inbox;
+pub mod links;
 pub mod notes;
+pub mod project_templates;
 pub mod projects;
+pub mod search;
+pub mod smart_lists;
+pub mod tags;
 pub mod tasks;
+pub mod time_entries;
+pub mod weekly_review;
+pub mod workspace;
+pub mod vault;
@@ -0,0 +1,56 @@
+use crate::commands::vault::Vault;
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::store::project_templates::{self, ProjectTemplate, ProjectTemplateInput, TemplateInstance};
+use tauri::State;
+
+#[tauri::command]
+pub async fn project_template_list(db: State<'_, Db>) -> Result<Vec<ProjectTemplate>, AuralisError> {
+    let conn = db.0.lock().await;
+    project_templates::list(&conn)
+}
+
+#[tauri::command]
+pub async fn project_template_get(db: State<'_, Db>, id: String) -> Result<ProjectTemplate, AuralisError> {
+    let conn = db.0.lock().await;
+    project_templates::get(&conn, &id)
+}
+
+/// Creates a template when `id` is omitted, otherwise replaces it (tasks included).
+#[tauri::command]
+pub async fn project_template_save(
+    db: State<'_, Db>,
+    id: Option<String>,
+    template: ProjectTemplateInput,
+) -> Result<ProjectTemplate, AuralisError> {
+    let mut conn = db.0.lock().await;
+    project_templates::save(&mut conn, id.as_deref(), template)
+}
+
+#[tauri::command]
+pub async fn project_template_delete(db: State<'_, Db>, id: String) -> Result<(), AuralisError> {
+    let conn = db.0.lock().await;
+    project_templates::delete(&conn, &id)
+}
+
+/// Creates an active project from a template, due dates counted from `anchor_date`.
+#[tauri::command]
+pub async fn project_from_template(
+    db: State<'_, Db>,
+    vault: State<'_, Vault>,
+    template_id: String,
+    area_id: Option<String>,
+    name: Option<String>,
+    anchor_date: String,
+) -> Result<TemplateInstance, AuralisError> {
+    let mut conn = db.0.lock().await;
+    let instance = project_templates::instantiate(
+        &mut conn,
+        &template_id,
+        area_id.as_deref(),
+        name.as_deref(),
+        &anchor_date,
+    )?;
+    vault.poke();
+    Ok(instance)
+}
@@ -1,156 +1,86 @@
+use crate::commands::vault::Vault;
 use crate::db::Db;
-use rusqlite::params;
-use serde::Serialize;
+use crate::domain::ProjectStatus;
+use crate::error::AuralisError;
+use crate::store::project_stats::{self, ProjectStats};
+use crate::store::projects::{self, ProjectItem, ProjectPatch};
 use tauri::State;
 
-#[derive(Serialize)]
-pub struct ProjectItem {
-    pub id: String,
-    pub area_id: String,
-    pub name: String,
-    pub status: String, // paused | active | completed
-    pub created_at: String,
-}
-
 #[tauri::command]
 pub async fn project_add(
     db: State<'_, Db>,
     name: String,
     area_id: Option<String>,
-) -> Result<String, String> {
-    let name = name.trim().to_string();
-    if name.is_empty() {
-        return Err("Name cannot be empty".into());
-    }
-
-    let id = format!("project_{}", uuid::Uuid::new_v4());
-    let area_id = area_id.unwrap_or_else(|| "area_admin_life".to_string());
-
+) -> Result<String, AuralisError> {
     let conn = db.0.lock().await;
-    conn.execute(
-        "INSERT INTO projects (id, area_id, name, status)
-         VALUES (?1, ?2, ?3, 'paused')",
-        params![id, area_id, name],
-    )
-    .map_err(|e| e.to_string())?;
-
-    Ok(id)
+    projects::add(&conn, &name, area_id.as_deref())
 }
 
 #[tauri::command]
 pub async fn project_list(
     db: State<'_, Db>,
-    status: Option<String>, // paused | active | completed
-) -> Result<Vec<ProjectItem>, String> {
+    status: Option<ProjectStatus>,
+    tag: Option<String>,
+) -> Result<Vec<ProjectItem>, AuralisError> {
     let conn = db.0.lock().await;
-    let mut items: Vec<ProjectItem> = Vec::new();
-
-    match status {
-        Some(s) => {
-            let mut stmt = conn
-                .prepare(
-                    "SELECT id, area_id, name, status, created_at
-                     FROM projects
-                     WHERE status = ?1
-                     ORDER BY created_at DESC",
-                )
-                .map_err(|e| e.to_string())?;
-
-            let rows = stmt
-                .query_map([s], |row| {
-                    Ok(ProjectItem {
-                        id: row.get(0)?,
-                        area_id: row.get(1)?,
-                        name: row.get(2)?,
-                        status: row.get(3)?,
-                        created_at: row.get(4)?,
-                    })
-                })
-                .map_err(|e| e.to_string())?;
-
-            for r in rows {
-                items.push(r.map_err(|e| e.to_string())?);
-            }
-        }
-        None => {
-            let mut stmt = conn
-                .prepare(
-                    "SELECT id, area_id, name, status, created_at
-                     FROM projects
-                     ORDER BY created_at DESC",
-                )
-                .map_err(|e| e.to_string())?;
-
-            let rows = stmt
-                .query_map([], |row| {
-                    Ok(ProjectItem {
-                        id: row.get(0)?,
-                        area_id: row.get(1)?,
-                        name: row.get(2)?,
-                        status: row.get(3)?,
-                        created_at: row.get(4)?,
-                    })
-                })
-                .map_err(|e| e.to_string())?;
-
-            for r in rows {
-                items.push(r.map_err(|e| e.to_string())?);
-            }
-        }
-    }
-
-    Ok(items)
+    projects::list(&conn, status, tag.as_deref())
 }
 
 #[tauri::command]
 pub async fn project_set_status(
     db: State<'_, Db>,
     id: String,
-    status: String, // paused | active | completed
-) -> Result<(), String> {
-    if status != "paused" && status != "active" && status != "completed" {
-        return Err("Invalid status".into());
-    }
-
+    status: ProjectStatus,
+) -> Result<(), AuralisError> {
     let conn = db.0.lock().await;
+    projects::set_status(&conn, &id, status)
+}
 
-    let updated = conn
-        .execute(
-            "UPDATE projects SET status = ?1 WHERE id = ?2",
-            params![status, id],
-        )
-        .map_err(|e| e.to_string())?;
-
-    if updated == 0 {
-        return Err("Project not found".into());
-    }
-
+/// Renames a project and rewrites `[[links]]` to it in notes.
+#[tauri::command]
+pub async fn project_rename(
+    db: State<'_, Db>,
+    vault: State<'_, Vault>,
+    id: String,
+    name: String,
+) -> Result<(), AuralisError> {
+    let mut conn = db.0.lock().await;
+    projects::rename(&mut conn, &id, &name)?;
+    vault.poke();
     Ok(())
 }
 
+/// Edits a project. `move_tasks` takes its tasks along on a move to another area.
 #[tauri::command]
-pub async fn project_get(db: State<'_, Db>, id: String) -> Result<ProjectItem, String> {
-    let conn = db.0.lock().await;
+pub async fn project_update(
+    db: State<'_, Db>,
+    vault: State<'_, Vault>,
+    id: String,
+    patch: ProjectPatch,
+    move_tasks: Option<bool>,
+) -> Result<ProjectItem, AuralisError> {
+    let mut conn = db.0.lock().await;
+    let project = projects::update(&mut conn, &id, patch, move_tasks.unwrap_or(false))?;
+    vault.poke();
+    Ok(project)
+}
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, area_id, name, status, created_at
-             FROM projects
-             WHERE id = ?1",
-        )
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn project_get(db: State<'_, Db>, id: String) -> Result<ProjectItem, AuralisError> {
+    let conn = db.0.lock().await;
+    projects::get(&conn, &id)
+}
 
-    let item = stmt
-        .query_row(params![id], |row| {
-            Ok(ProjectItem {
-                id: row.get(0)?,
-                area_id: row.get(1)?,
-                name: row.get(2)?,
-                status: row.get(3)?,
-                created_at: row.get(4)?,
-            })
-        })
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn project_stats(db: State<'_, Db>, id: String) -> Result<ProjectStats, AuralisError> {
+    let conn = db.0.lock().await;
+    project_stats::get(&conn, &id)
+}
 
-    Ok(item)
-}
\ No newline at end of file
+/// Stats for several projects at once (e.g. everything in `project_list`), in the
+/// order given.
+#[tauri::command]
+pub async fn project_stats_batch(db: State<'_, Db>, ids: Vec<String>) -> Result<Vec<ProjectStats>, AuralisError> {
+    let conn = db.0.lock().await;
+    project_stats::many(&conn, &ids)
+}
@@ -0,0 +1,17 @@
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::store::search::{self, SearchHit};
+use tauri::State;
+
+/// Ranked full-text search across notes, tasks, projects and inbox items.
+/// `kinds` narrows the entity types (default: all); `limit` defaults to 20.
+#[tauri::command]
+pub async fn search(
+    db: State<'_, Db>,
+    query: String,
+    kinds: Option<Vec<String>>,
+    limit: Option<u32>,
+) -> Result<Vec<SearchHit>, AuralisError> {
+    let conn = db.0.lock().await;
+    search::search(&conn, &query, kinds, limit)
+}
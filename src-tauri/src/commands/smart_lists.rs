@@ -0,0 +1,41 @@
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::store::smart_lists::{self, SmartList};
+use crate::store::tasks::TaskItem;
+use tauri::State;
+
+#[tauri::command]
+pub async fn smart_list_list(db: State<'_, Db>) -> Result<Vec<SmartList>, AuralisError> {
+    let conn = db.0.lock().await;
+    smart_lists::list(&conn)
+}
+
+/// Creates a smart list, or updates the one with `id`.
+#[tauri::command]
+pub async fn smart_list_save(
+    db: State<'_, Db>,
+    id: Option<String>,
+    name: String,
+    query: String,
+) -> Result<SmartList, AuralisError> {
+    let conn = db.0.lock().await;
+    smart_lists::save(&conn, id.as_deref(), &name, &query)
+}
+
+#[tauri::command]
+pub async fn smart_list_reorder(db: State<'_, Db>, ids: Vec<String>) -> Result<Vec<SmartList>, AuralisError> {
+    let mut conn = db.0.lock().await;
+    smart_lists::reorder(&mut conn, &ids)
+}
+
+#[tauri::command]
+pub async fn smart_list_delete(db: State<'_, Db>, id: String) -> Result<(), AuralisError> {
+    let conn = db.0.lock().await;
+    smart_lists::delete(&conn, &id)
+}
+
+#[tauri::command]
+pub async fn smart_list_run(db: State<'_, Db>, id: String) -> Result<Vec<TaskItem>, AuralisError> {
+    let conn = db.0.lock().await;
+    smart_lists::run(&conn, &id)
+}
@@ -0,0 +1,72 @@
+use crate::backup::{self, BackupDir};
+use crate::db::Db;
+use crate::domain::TagTarget;
+use crate::error::AuralisError;
+use crate::store::tags::{self, TagItem};
+use tauri::State;
+
+/// Every tag with how many entities carry it.
+#[tauri::command]
+pub async fn tag_list(db: State<'_, Db>) -> Result<Vec<TagItem>, AuralisError> {
+    let conn = db.0.lock().await;
+    tags::list(&conn)
+}
+
+#[tauri::command]
+pub async fn tag_list_for(db: State<'_, Db>, kind: TagTarget, id: String) -> Result<Vec<TagItem>, AuralisError> {
+    let conn = db.0.lock().await;
+    tags::for_entity(&conn, kind, &id)
+}
+
+/// Tags a task, note, project or inbox item by name, creating the tag if needed.
+#[tauri::command]
+pub async fn tag_add(
+    db: State<'_, Db>,
+    kind: TagTarget,
+    entity_id: String,
+    name: String,
+) -> Result<TagItem, AuralisError> {
+    let conn = db.0.lock().await;
+    tags::add(&conn, kind, &entity_id, &name)
+}
+
+#[tauri::command]
+pub async fn tag_remove(
+    db: State<'_, Db>,
+    kind: TagTarget,
+    entity_id: String,
+    tag_id: String,
+) -> Result<(), AuralisError> {
+    let conn = db.0.lock().await;
+    tags::remove(&conn, kind, &entity_id, &tag_id)
+}
+
+#[tauri::command]
+pub async fn tag_rename(db: State<'_, Db>, id: String, name: String) -> Result<TagItem, AuralisError> {
+    let conn = db.0.lock().await;
+    tags::rename(&conn, &id, &name)
+}
+
+/// Folds `source_id` into `target_id` and deletes the source tag.
+#[tauri::command]
+pub async fn tag_merge(
+    db: State<'_, Db>,
+    backups: State<'_, BackupDir>,
+    source_id: String,
+    target_id: String,
+) -> Result<TagItem, AuralisError> {
+    let conn = db.0.lock().await;
+    backup::before(&conn, &backups.0, "tag-merge")?;
+    tags::merge(&conn, &source_id, &target_id)
+}
+
+#[tauri::command]
+pub async fn tag_delete(
+    db: State<'_, Db>,
+    backups: State<'_, BackupDir>,
+    id: String,
+) -> Result<(), AuralisError> {
+    let conn = db.0.lock().await;
+    backup::before(&conn, &backups.0, "tag-delete")?;
+    tags::delete(&conn, &id)
+}
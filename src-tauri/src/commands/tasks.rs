@@ -1,208 +1,116 @@
+use crate::commands::vault::Vault;
 use crate::db::Db;
-use rusqlite::params;
-use serde::Serialize;
+use crate::domain::TaskStatus;
+use crate::error::AuralisError;
+use crate::quick_add::QuickAdd;
+use crate::store::dependencies::{self, Dependencies};
+use crate::store::subtasks::{self, TaskNode};
+use crate::store::tasks::{self, QuickAddResult, StatusChange, TaskItem, TaskPatch};
 use tauri::State;
 
-#[derive(Serialize)]
-pub struct TaskItem {
-    pub id: String,
-    pub area_id: String,
-    pub project_id: Option<String>,
-    pub title: String,
-    pub status: String,   // todo | doing | done | deferred
-    pub priority: String, // low | normal | high
-    pub due_at: Option<String>,
-    pub scheduled_at: Option<String>,
-    pub created_at: String,
-    pub completed_at: Option<String>,
-}
-
 #[tauri::command]
 pub async fn task_add(
     db: State<'_, Db>,
     title: String,
     area_id: Option<String>,
     project_id: Option<String>,
-) -> Result<String, String> {
-    let title = title.trim().to_string();
-    if title.is_empty() {
-        return Err("Title cannot be empty".into());
-    }
-
-    // Default to your seeded fallback area
-    let area_id = area_id.unwrap_or_else(|| "area_admin_life".to_string());
-
-    let id = format!("task_{}", uuid::Uuid::new_v4());
-
+) -> Result<String, AuralisError> {
     let conn = db.0.lock().await;
-    conn.execute(
-        "INSERT INTO tasks (id, area_id, project_id, title)
-         VALUES (?1, ?2, ?3, ?4)",
-        params![id, area_id, project_id, title],
-    )
-    .map_err(|e| e.to_string())?;
-
-    Ok(id)
+    tasks::add(&conn, &title, area_id.as_deref(), project_id.as_deref())
 }
 
 #[tauri::command]
 pub async fn task_list(
     db: State<'_, Db>,
-    status: Option<String>,
-) -> Result<Vec<TaskItem>, String> {
+    status: Option<TaskStatus>,
+    tag: Option<String>,
+) -> Result<Vec<TaskItem>, AuralisError> {
     let conn = db.0.lock().await;
+    tasks::list(&conn, status, tag.as_deref())
+}
 
-    let mut items: Vec<TaskItem> = Vec::new();
-
-    match status {
-        Some(s) => {
-            let mut stmt = conn
-                .prepare(
-                    "SELECT id, area_id, project_id, title, status, priority,
-                            due_at, scheduled_at, created_at, completed_at
-                     FROM tasks
-                     WHERE status = ?1
-                     ORDER BY created_at DESC",
-                )
-                .map_err(|e| e.to_string())?;
-
-            let rows = stmt
-                .query_map([s], |row| {
-                    Ok(TaskItem {
-                        id: row.get(0)?,
-                        area_id: row.get(1)?,
-                        project_id: row.get(2)?,
-                        title: row.get(3)?,
-                        status: row.get(4)?,
-                        priority: row.get(5)?,
-                        due_at: row.get(6)?,
-                        scheduled_at: row.get(7)?,
-                        created_at: row.get(8)?,
-                        completed_at: row.get(9)?,
-                    })
-                })
-                .map_err(|e| e.to_string())?;
-
-            for r in rows {
-                items.push(r.map_err(|e| e.to_string())?);
-            }
-        }
-        None => {
-            let mut stmt = conn
-                .prepare(
-                    "SELECT id, area_id, project_id, title, status, priority,
-                            due_at, scheduled_at, created_at, completed_at
-                     FROM tasks
-                     ORDER BY created_at DESC",
-                )
-                .map_err(|e| e.to_string())?;
-
-            let rows = stmt
-                .query_map([], |row| {
-                    Ok(TaskItem {
-                        id: row.get(0)?,
-                        area_id: row.get(1)?,
-                        project_id: row.get(2)?,
-                        title: row.get(3)?,
-                        status: row.get(4)?,
-                        priority: row.get(5)?,
-                        due_at: row.get(6)?,
-                        scheduled_at: row.get(7)?,
-                        created_at: row.get(8)?,
-                        completed_at: row.get(9)?,
-                    })
-                })
-                .map_err(|e| e.to_string())?;
-
-            for r in rows {
-                items.push(r.map_err(|e| e.to_string())?);
-            }
-        }
-    }
-
-    Ok(items)
+/// Tasks matching a query such as `status:todo,doing due<+7d tag:@phone sort:due`.
+#[tauri::command]
+pub async fn task_query(db: State<'_, Db>, query: String) -> Result<Vec<TaskItem>, AuralisError> {
+    let conn = db.0.lock().await;
+    tasks::query(&conn, &query)
 }
 
+/// Sets a task's status. Completing a task reports the tasks it unblocked and the
+/// next instance of a repeating task. A task with open subtasks is only completed
+/// when `include_subtasks` is set, which completes them too.
 #[tauri::command]
 pub async fn task_set_status(
     db: State<'_, Db>,
     id: String,
-    status: String, // todo | doing | done | deferred
-) -> Result<(), String> {
-    if status != "todo" && status != "doing" && status != "done" && status != "deferred" {
-        return Err("Invalid status".into());
+    status: TaskStatus,
+    include_subtasks: Option<bool>,
+) -> Result<StatusChange, AuralisError> {
+    let mut conn = db.0.lock().await;
+    if status == TaskStatus::Done && include_subtasks.unwrap_or(false) {
+        return subtasks::complete(&mut conn, &id);
     }
+    tasks::set_status(&mut conn, &id, status)
+}
 
+#[tauri::command]
+pub async fn task_add_subtask(db: State<'_, Db>, parent_id: String, title: String) -> Result<String, AuralisError> {
+    let mut conn = db.0.lock().await;
+    subtasks::add(&mut conn, &parent_id, &title)
+}
+
+/// Moves a task under another one, or back to the top level when `parent_id` is null.
+#[tauri::command]
+pub async fn task_set_parent(
+    db: State<'_, Db>,
+    id: String,
+    parent_id: Option<String>,
+) -> Result<TaskItem, AuralisError> {
+    let mut conn = db.0.lock().await;
+    subtasks::set_parent(&mut conn, &id, parent_id.as_deref())
+}
+
+/// A project's tasks (or those in no project) as a tree with rolled-up progress.
+#[tauri::command]
+pub async fn task_tree(db: State<'_, Db>, project_id: Option<String>) -> Result<Vec<TaskNode>, AuralisError> {
     let conn = db.0.lock().await;
+    subtasks::tree(&conn, project_id.as_deref())
+}
 
-    // When marking done, set completed_at; otherwise clear it.
-    let updated = if status == "done" {
-        conn.execute(
-            "UPDATE tasks
-             SET status = ?1,
-                 completed_at = (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
-             WHERE id = ?2",
-            params![status, id],
-        )
-    } else {
-        conn.execute(
-            "UPDATE tasks
-             SET status = ?1,
-                 completed_at = NULL
-             WHERE id = ?2",
-            params![status, id],
-        )
-    }
-    .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn task_dependencies(db: State<'_, Db>, id: String) -> Result<Dependencies, AuralisError> {
+    let conn = db.0.lock().await;
+    dependencies::list(&conn, &id)
+}
 
-    if updated == 0 {
-        return Err("Task not found".into());
-    }
+/// Makes `id` wait until `depends_on_id` is done.
+#[tauri::command]
+pub async fn task_add_dependency(
+    db: State<'_, Db>,
+    id: String,
+    depends_on_id: String,
+) -> Result<Dependencies, AuralisError> {
+    let conn = db.0.lock().await;
+    dependencies::add(&conn, &id, &depends_on_id)
+}
 
-    Ok(())
+#[tauri::command]
+pub async fn task_remove_dependency(
+    db: State<'_, Db>,
+    id: String,
+    depends_on_id: String,
+) -> Result<Dependencies, AuralisError> {
+    let conn = db.0.lock().await;
+    dependencies::remove(&conn, &id, &depends_on_id)
 }
 
 #[tauri::command]
 pub async fn task_list_by_project(
     db: State<'_, Db>,
     project_id: String,
-) -> Result<Vec<TaskItem>, String> {
+) -> Result<Vec<TaskItem>, AuralisError> {
     let conn = db.0.lock().await;
-
-    let mut items: Vec<TaskItem> = Vec::new();
-
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, area_id, project_id, title, status, priority,
-                    due_at, scheduled_at, created_at, completed_at
-             FROM tasks
-             WHERE project_id = ?1
-             ORDER BY created_at DESC",
-        )
-        .map_err(|e| e.to_string())?;
-
-    let rows = stmt
-        .query_map([project_id], |row| {
-            Ok(TaskItem {
-                id: row.get(0)?,
-                area_id: row.get(1)?,
-                project_id: row.get(2)?,
-                title: row.get(3)?,
-                status: row.get(4)?,
-                priority: row.get(5)?,
-                due_at: row.get(6)?,
-                scheduled_at: row.get(7)?,
-                created_at: row.get(8)?,
-                completed_at: row.get(9)?,
-            })
-        })
-        .map_err(|e| e.to_string())?;
-
-    for r in rows {
-        items.push(r.map_err(|e| e.to_string())?);
-    }
-
-    Ok(items)
+    tasks::list_by_project(&conn, &project_id)
 }
 
 #[tauri::command]
@@ -210,19 +118,50 @@ pub async fn task_set_project(
     db: State<'_, Db>,
     id: String,
     project_id: Option<String>, // null to unassign
-) -> Result<(), String> {
+) -> Result<(), AuralisError> {
     let conn = db.0.lock().await;
+    tasks::set_project(&conn, &id, project_id.as_deref())
+}
 
-    let updated = conn
-        .execute(
-            "UPDATE tasks SET project_id = ?1 WHERE id = ?2",
-            params![project_id, id],
-        )
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn task_set_recurrence(
+    db: State<'_, Db>,
+    id: String,
+    recurrence: Option<String>, // RRULE text, null to stop repeating
+) -> Result<(), AuralisError> {
+    let conn = db.0.lock().await;
+    tasks::set_recurrence(&conn, &id, recurrence.as_deref())
+}
 
-    if updated == 0 {
-        return Err("Task not found".into());
+/// Applies a partial update to a task and returns the stored result. A new title
+/// is carried into `[[task:...]]` links in notes.
+#[tauri::command]
+pub async fn task_update(
+    db: State<'_, Db>,
+    vault: State<'_, Vault>,
+    id: String,
+    patch: TaskPatch,
+) -> Result<TaskItem, AuralisError> {
+    let mut conn = db.0.lock().await;
+    let renamed = patch.title.is_some();
+    let task = tasks::update(&mut conn, &id, patch)?;
+    if renamed {
+        vault.poke();
     }
+    Ok(task)
+}
 
-    Ok(())
-}
\ No newline at end of file
+/// Shows how `task_quick_add` would read `text`, without creating anything.
+#[tauri::command]
+pub async fn task_quick_add_preview(db: State<'_, Db>, text: String) -> Result<QuickAdd, AuralisError> {
+    let conn = db.0.lock().await;
+    tasks::quick_add_parse(&conn, &text)
+}
+
+/// Creates a task from inline syntax such as
+/// `Call dentist tomorrow 3pm !high #health +Q4Launch ~30m`.
+#[tauri::command]
+pub async fn task_quick_add(db: State<'_, Db>, text: String) -> Result<QuickAddResult, AuralisError> {
+    let conn = db.0.lock().await;
+    tasks::quick_add(&conn, &text)
+}
@@ -0,0 +1,103 @@
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::store::time_entries::{self, ReportGroup, TimeEntry, TimeEntryInput, TimeEntryPatch, TimeReport};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+/// Starts the timer on a task, stopping one running on another task.
+#[tauri::command]
+pub async fn task_start_timer(db: State<'_, Db>, task_id: String) -> Result<TimeEntry, AuralisError> {
+    let mut conn = db.0.lock().await;
+    time_entries::start(&mut conn, &task_id)
+}
+
+/// Stops the running timer, now or at `ended_at`.
+#[tauri::command]
+pub async fn task_stop_timer(db: State<'_, Db>, ended_at: Option<String>) -> Result<TimeEntry, AuralisError> {
+    let conn = db.0.lock().await;
+    time_entries::stop(&conn, ended_at.as_deref())
+}
+
+/// The running timer, if any. `interrupted_at` is set when it was left running by a
+/// session that ended without stopping it.
+#[tauri::command]
+pub async fn task_running_timer(db: State<'_, Db>) -> Result<Option<TimeEntry>, AuralisError> {
+    let conn = db.0.lock().await;
+    time_entries::running(&conn)
+}
+
+/// Keeps an interrupted timer running, counting the time the app was away.
+#[tauri::command]
+pub async fn task_resume_timer(db: State<'_, Db>) -> Result<TimeEntry, AuralisError> {
+    let conn = db.0.lock().await;
+    time_entries::resume(&conn)
+}
+
+#[tauri::command]
+pub async fn time_entry_list(
+    db: State<'_, Db>,
+    task_id: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<TimeEntry>, AuralisError> {
+    let conn = db.0.lock().await;
+    time_entries::list(&conn, task_id.as_deref(), from.as_deref(), to.as_deref())
+}
+
+#[tauri::command]
+pub async fn time_entry_add(db: State<'_, Db>, input: TimeEntryInput) -> Result<TimeEntry, AuralisError> {
+    let conn = db.0.lock().await;
+    time_entries::add(&conn, input)
+}
+
+#[tauri::command]
+pub async fn time_entry_update(
+    db: State<'_, Db>,
+    id: String,
+    patch: TimeEntryPatch,
+) -> Result<TimeEntry, AuralisError> {
+    let conn = db.0.lock().await;
+    time_entries::update(&conn, &id, patch)
+}
+
+#[tauri::command]
+pub async fn time_entry_delete(db: State<'_, Db>, id: String) -> Result<(), AuralisError> {
+    let conn = db.0.lock().await;
+    time_entries::delete(&conn, &id)
+}
+
+/// Actual against estimated time for the days `start` through `end`.
+#[tauri::command]
+pub async fn time_report(
+    db: State<'_, Db>,
+    start: String,
+    end: Option<String>,
+    group_by: ReportGroup,
+    time_zone: Option<String>,
+) -> Result<TimeReport, AuralisError> {
+    let conn = db.0.lock().await;
+    time_entries::report(&conn, &start, end.as_deref(), group_by, time_zone.as_deref())
+}
+
+/// Background task started at launch: flags a timer left running by the previous
+/// session, then stamps the running timer every minute so the next launch can tell
+/// how long the app was away.
+pub async fn run_heartbeat(app: AppHandle) {
+    {
+        let db = app.state::<Db>();
+        let conn = db.0.lock().await;
+        if let Err(e) = time_entries::recover(&conn) {
+            eprintln!("Timer recovery failed: {e}");
+        }
+    }
+    loop {
+        {
+            let db = app.state::<Db>();
+            let conn = db.0.lock().await;
+            if let Err(e) = time_entries::heartbeat(&conn) {
+                eprintln!("Timer heartbeat failed: {e}");
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(time_entries::HEARTBEAT_SECS)).await;
+    }
+}
@@ -0,0 +1,139 @@
+use crate::backup::BackupDir;
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::vault::{self, VaultSyncSummary};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+/// Managed state for vault sync: the file watcher and a channel that asks the
+/// background worker (see [`run_worker`]) for a sync pass.
+pub struct Vault {
+    tx: UnboundedSender<()>,
+    watcher: std::sync::Mutex<Option<RecommendedWatcher>>,
+}
+
+impl Vault {
+    pub fn new(tx: UnboundedSender<()>) -> Self {
+        Vault {
+            tx,
+            watcher: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Queues a sync pass. Called after anything that changes notes.
+    pub fn poke(&self) {
+        let _ = self.tx.send(());
+    }
+
+    /// Watches `dir` for edits made outside the app, replacing any previous watch.
+    fn watch(&self, dir: Option<&Path>) -> Result<(), AuralisError> {
+        let mut current = self.watcher.lock().unwrap_or_else(|e| e.into_inner());
+        *current = None;
+        let Some(dir) = dir else {
+            return Ok(());
+        };
+
+        let tx = self.tx.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let touches_md = event.is_ok_and(|e| {
+                e.paths
+                    .iter()
+                    .any(|p| p.extension().and_then(|x| x.to_str()) == Some("md"))
+            });
+            if touches_md {
+                let _ = tx.send(());
+            }
+        })
+        .map_err(|e| AuralisError::Io(format!("Failed to start watching the vault: {e}")))?;
+        watcher
+            .watch(dir, RecursiveMode::Recursive)
+            .map_err(|e| AuralisError::Io(format!("Failed to watch {}: {e}", dir.display())))?;
+
+        *current = Some(watcher);
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub async fn vault_get_dir(db: State<'_, Db>) -> Result<Option<String>, AuralisError> {
+    let conn = db.0.lock().await;
+    Ok(vault::dir(&conn)?.map(|d| d.to_string_lossy().into_owned()))
+}
+
+/// Sets the vault folder (or, with `None`, turns sync off) and runs a first sync
+/// straight away, so existing notes and files are paired up before returning.
+#[tauri::command]
+pub async fn vault_set_dir(
+    db: State<'_, Db>,
+    backups: State<'_, BackupDir>,
+    state: State<'_, Vault>,
+    dir: Option<String>,
+) -> Result<Option<VaultSyncSummary>, AuralisError> {
+    let dir = dir.map(PathBuf::from);
+    let mut conn = db.0.lock().await;
+    vault::set_dir(&conn, dir.as_deref())?;
+
+    let Some(dir) = dir else {
+        state.watch(None)?;
+        return Ok(None);
+    };
+    let summary = vault::sync(&mut conn, &dir, &backups.0)?;
+    state.watch(Some(&dir))?;
+    Ok(Some(summary))
+}
+
+/// Runs a sync pass now. Returns `None` when no vault is configured.
+#[tauri::command]
+pub async fn vault_sync(
+    db: State<'_, Db>,
+    backups: State<'_, BackupDir>,
+) -> Result<Option<VaultSyncSummary>, AuralisError> {
+    let mut conn = db.0.lock().await;
+    vault::sync_configured(&mut conn, &backups.0)
+}
+
+/// Background task started at launch: watches the configured vault and runs a
+/// sync pass whenever it is poked, once changes settle for half a second.
+/// Emits `vault:synced` with the summary when a pass changed anything, and
+/// `vault:error` with the message when one fails.
+pub async fn run_worker(app: AppHandle, mut rx: UnboundedReceiver<()>) {
+    let dir = {
+        let db = app.state::<Db>();
+        let conn = db.0.lock().await;
+        vault::dir(&conn)
+    };
+    match dir {
+        Ok(dir) => {
+            if let Err(e) = app.state::<Vault>().watch(dir.as_deref()) {
+                eprintln!("Vault watch failed: {e}");
+            }
+        }
+        Err(e) => eprintln!("Failed to read vault settings: {e}"),
+    }
+    app.state::<Vault>().poke();
+
+    while rx.recv().await.is_some() {
+        // Editors save in bursts (temp file, rename, metadata); let them settle.
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        while rx.try_recv().is_ok() {}
+
+        let result = {
+            let db = app.state::<Db>();
+            let backups = app.state::<BackupDir>();
+            let mut conn = db.0.lock().await;
+            vault::sync_configured(&mut conn, &backups.0)
+        };
+        match result {
+            Ok(Some(summary)) if !summary.is_empty() => {
+                let _ = app.emit("vault:synced", &summary);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let _ = app.emit("vault:error", e.to_string());
+            }
+        }
+    }
+}
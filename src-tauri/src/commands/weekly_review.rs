@@ -0,0 +1,45 @@
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::store::weekly_review::{self, ReviewState, WeeklyReview};
+use tauri::State;
+
+/// Starts a weekly review, or resumes the one in progress.
+#[tauri::command]
+pub async fn weekly_review_start(db: State<'_, Db>, stale_days: Option<i64>) -> Result<ReviewState, AuralisError> {
+    let conn = db.0.lock().await;
+    weekly_review::start(&conn, stale_days)
+}
+
+#[tauri::command]
+pub async fn weekly_review_current(db: State<'_, Db>) -> Result<Option<ReviewState>, AuralisError> {
+    let conn = db.0.lock().await;
+    weekly_review::current(&conn)
+}
+
+#[tauri::command]
+pub async fn weekly_review_mark(
+    db: State<'_, Db>,
+    entity_id: String,
+    reviewed: bool,
+) -> Result<ReviewState, AuralisError> {
+    let conn = db.0.lock().await;
+    weekly_review::mark(&conn, &entity_id, reviewed)
+}
+
+#[tauri::command]
+pub async fn weekly_review_advance(db: State<'_, Db>) -> Result<ReviewState, AuralisError> {
+    let conn = db.0.lock().await;
+    weekly_review::advance(&conn)
+}
+
+#[tauri::command]
+pub async fn weekly_review_abandon(db: State<'_, Db>) -> Result<(), AuralisError> {
+    let conn = db.0.lock().await;
+    weekly_review::abandon(&conn)
+}
+
+#[tauri::command]
+pub async fn weekly_review_history(db: State<'_, Db>, limit: Option<i64>) -> Result<Vec<WeeklyReview>, AuralisError> {
+    let conn = db.0.lock().await;
+    weekly_review::history(&conn, limit)
+}
@@ -0,0 +1,54 @@
+use crate::backup::{self, BackupDir};
+use crate::commands::vault::Vault;
+use crate::db::Db;
+use crate::error::AuralisError;
+use crate::store::workspace::{self, ImportMode, Workspace, WorkspaceImportSummary};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use tauri::State;
+
+#[derive(Serialize)]
+pub struct WorkspaceExportSummary {
+    pub path: String,
+    pub tables: BTreeMap<String, usize>, // rows written per table
+}
+
+/// Writes every area, project, task, inbox item, note and calendar event to a
+/// versioned JSON file.
+#[tauri::command]
+pub async fn workspace_export(db: State<'_, Db>, path: String) -> Result<WorkspaceExportSummary, AuralisError> {
+    let doc = {
+        let conn = db.0.lock().await;
+        workspace::export(&conn)?
+    };
+
+    let json = serde_json::to_string_pretty(&doc)
+        .map_err(|e| AuralisError::Io(format!("Failed to encode workspace: {e}")))?;
+    fs::write(&path, json).map_err(|e| AuralisError::Io(format!("Failed to write {path}: {e}")))?;
+
+    let tables = doc.tables.iter().map(|(t, rows)| (t.clone(), rows.len())).collect();
+    Ok(WorkspaceExportSummary { path, tables })
+}
+
+/// Loads a file written by `workspace_export`, either replacing the current
+/// workspace or merging into it by id. A safety backup is taken first.
+#[tauri::command]
+pub async fn workspace_import(
+    db: State<'_, Db>,
+    backups: State<'_, BackupDir>,
+    vault: State<'_, Vault>,
+    path: String,
+    mode: ImportMode, // replace | merge
+) -> Result<WorkspaceImportSummary, AuralisError> {
+    let text = fs::read_to_string(&path)
+        .map_err(|e| AuralisError::Io(format!("Failed to read {path}: {e}")))?;
+    let doc: Workspace = serde_json::from_str(&text)
+        .map_err(|e| AuralisError::validation(format!("{path} is not a workspace export: {e}")))?;
+
+    let mut conn = db.0.lock().await;
+    backup::before(&conn, &backups.0, "workspace-import")?;
+    let summary = workspace::import(&mut conn, &doc, mode)?;
+    vault.poke();
+    Ok(summary)
+}
@@ -0,0 +1,50 @@
+use crate::error::ParseError;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Formats a timestamp the way SQLite's `strftime('%Y-%m-%dT%H:%M:%fZ', 'now')` does,
+/// so stored values sort and compare correctly as text.
+pub fn format_utc(dt: DateTime<Utc>) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+/// Parses an ISO-8601 date or date-time. Explicit offsets are respected;
+/// values without one (including bare dates) are taken as local time.
+pub fn parse(input: &str) -> Result<DateTime<Utc>, ParseError> {
+    let s = input.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    for fmt in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, fmt) {
+            return local_to_utc(naive);
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return local_to_utc(date.and_hms_opt(0, 0, 0).unwrap_or_default());
+    }
+
+    Err(format!(
+        "Invalid date '{s}' (expected ISO-8601, e.g. 2024-05-01 or 2024-05-01T09:30:00Z)"
+    ).into())
+}
+
+pub fn local_to_utc(naive: NaiveDateTime) -> Result<DateTime<Utc>, ParseError> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| ParseError(format!("Local time {naive} does not exist")))
+}
+
+/// Parses and re-formats a user-supplied timestamp into the storage format.
+pub fn normalize(input: &str) -> Result<String, ParseError> {
+    parse(input).map(format_utc)
+}
@@ -1,3 +1,4 @@
+use crate::{migrations, store};
 use rusqlite::Connection;
 use std::{fs, path::PathBuf};
 use tauri::Manager;
@@ -16,23 +17,42 @@ fn db_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(app_data_dir.join("auralis.db"))
 }
 
+fn enable_foreign_keys(conn: &Connection) -> Result<(), String> {
+    conn.pragma_update(None, "foreign_keys", "ON")
+        .map_err(|e| format!("Failed to enable foreign_keys: {e}"))
+}
+
+/// The rotating `backups/` directory next to the database file.
+pub fn backup_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(db_path(app)?.with_file_name("backups"))
+}
+
 pub fn init_db(app: &tauri::AppHandle) -> Result<Db, String> {
     let path = db_path(app)?;
 
-    let conn =
+    let mut conn =
         Connection::open(&path).map_err(|e| format!("Failed to open DB at {:?}: {e}", path))?;
 
-    conn.pragma_update(None, "foreign_keys", "ON")
-        .map_err(|e| format!("Failed to enable foreign_keys: {e}"))?;
+    enable_foreign_keys(&conn)?;
     conn.pragma_update(None, "journal_mode", "WAL")
         .map_err(|e| format!("Failed to set journal_mode=WAL: {e}"))?;
     conn.pragma_update(None, "synchronous", "NORMAL")
         .map_err(|e| format!("Failed to set synchronous=NORMAL: {e}"))?;
 
-    let schema_sql = include_str!("../db/schema.sql");
-
-    conn.execute_batch(schema_sql)
-        .map_err(|e| format!("Failed to execute schema.sql: {e}"))?;
+    migrations::migrate(&mut conn, Some(&backup_dir(app)?))?;
+    store::links::backfill(&conn).map_err(|e| format!("Failed to index note links: {e}"))?;
 
     Ok(Db(Mutex::new(conn)))
 }
+
+/// A fresh, fully migrated database that lives only as long as the connection.
+/// Used by the integration tests to exercise `store` without a Tauri app.
+pub fn open_in_memory() -> Result<Connection, String> {
+    let mut conn =
+        Connection::open_in_memory().map_err(|e| format!("Failed to open in-memory DB: {e}"))?;
+
+    enable_foreign_keys(&conn)?;
+    migrations::migrate(&mut conn, None)?;
+
+    Ok(conn)
+}
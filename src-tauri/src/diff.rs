@@ -0,0 +1,89 @@
+//! Line-level diff between two texts, as the shortest list of kept, removed and
+//! added lines (a longest-common-subsequence diff).
+
+use serde::Serialize;
+
+// Past this many cells the LCS table costs more than the diff is worth; the
+// differing middle is then shown as one removed block and one added block.
+const MAX_TABLE: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+fn line(op: DiffOp, text: &str) -> DiffLine {
+    DiffLine {
+        op,
+        text: text.to_string(),
+    }
+}
+
+/// The edits that turn `old` into `new`, in order. Within a changed block,
+/// removed lines come before added ones.
+pub fn lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut out: Vec<DiffLine> = a[..prefix].iter().map(|l| line(DiffOp::Equal, l)).collect();
+
+    if (a_mid.len() + 1) * (b_mid.len() + 1) > MAX_TABLE {
+        out.extend(a_mid.iter().map(|l| line(DiffOp::Delete, l)));
+        out.extend(b_mid.iter().map(|l| line(DiffOp::Insert, l)));
+    } else {
+        middle(a_mid, b_mid, &mut out);
+    }
+
+    out.extend(a[a.len() - suffix..].iter().map(|l| line(DiffOp::Equal, l)));
+    out
+}
+
+fn middle(a: &[&str], b: &[&str], out: &mut Vec<DiffLine>) {
+    let (n, m) = (a.len(), b.len());
+    // lcs[i][j]: length of the longest common subsequence of a[i..] and b[j..].
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            out.push(line(DiffOp::Equal, a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(line(DiffOp::Delete, a[i]));
+            i += 1;
+        } else {
+            out.push(line(DiffOp::Insert, b[j]));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|l| line(DiffOp::Delete, l)));
+    out.extend(b[j..].iter().map(|l| line(DiffOp::Insert, l)));
+}
@@ -0,0 +1,139 @@
+//! Enumerated column values shared by commands and the schema's CHECK constraints.
+//! Each one (de)serializes, and is stored, as its lowercase text.
+
+use crate::error::AuralisError;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+macro_rules! text_enum {
+    ($(#[$meta:meta])* $name:ident ($what:literal) { $($variant:ident => $text:literal),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        pub enum $name {
+            $(#[serde(rename = $text)] $variant),+
+        }
+
+        impl $name {
+            pub const ALL: &'static [$name] = &[$($name::$variant),+];
+
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $($name::$variant => $text),+
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = AuralisError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($text => Ok($name::$variant),)+
+                    _ => {
+                        let allowed: Vec<&str> = $name::ALL.iter().map(|v| v.as_str()).collect();
+                        Err(AuralisError::Validation(format!(
+                            "Invalid {} '{s}' (must be one of: {})",
+                            $what,
+                            allowed.join(", ")
+                        )))
+                    }
+                }
+            }
+        }
+
+        impl ToSql for $name {
+            fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::from(self.as_str()))
+            }
+        }
+
+        impl FromSql for $name {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                value
+                    .as_str()?
+                    .parse()
+                    .map_err(|e| FromSqlError::Other(Box::new(e)))
+            }
+        }
+    };
+}
+
+text_enum! {
+    TaskStatus ("status") {
+        Todo => "todo",
+        Doing => "doing",
+        Done => "done",
+        Deferred => "deferred",
+    }
+}
+
+text_enum! {
+    /// Used by tasks and projects.
+    Priority ("priority") {
+        Low => "low",
+        Normal => "normal",
+        High => "high",
+    }
+}
+
+text_enum! {
+    ProjectStatus ("status") {
+        Active => "active",
+        Paused => "paused",
+        Completed => "completed",
+    }
+}
+
+text_enum! {
+    InboxState ("state") {
+        Unprocessed => "unprocessed",
+        Processed => "processed",
+        Archived => "archived",
+    }
+}
+
+text_enum! {
+    InboxSource ("source") {
+        Text => "text",
+        Voice => "voice",
+    }
+}
+
+text_enum! {
+    /// What a `[[wiki-link]]` can point at.
+    LinkKind ("link kind") {
+        Note => "note",
+        Task => "task",
+        Project => "project",
+    }
+}
+
+text_enum! {
+    /// Entities that can carry tags.
+    TagTarget ("entity kind") {
+        Task => "task",
+        Note => "note",
+        Project => "project",
+        Inbox => "inbox",
+    }
+}
+
+text_enum! {
+    /// The steps of a weekly review, in order.
+    ReviewStep ("review step") {
+        Inbox => "inbox",
+        Projects => "projects",
+        StaleTasks => "stale_tasks",
+        DeferredTasks => "deferred_tasks",
+        Calendar => "calendar",
+        Done => "done",
+    }
+}
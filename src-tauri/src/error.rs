@@ -0,0 +1,152 @@
+//! The error type every command returns. It reaches the frontend as
+//! `{ code, message, rule? }`, so the UI can react to `code` / `rule` instead of
+//! matching on message text.
+
+use rusqlite::ffi;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum AuralisError {
+    NotFound(String),
+    Validation(String),
+    /// A schema trigger refused the write. `rule` says which one, when known.
+    RuleViolation {
+        rule: Option<Rule>,
+        message: String,
+    },
+    Db(String),
+    Ai(String),
+    Io(String),
+}
+
+/// Domain rules enforced by `RAISE(ABORT, ...)` triggers in the schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rule {
+    /// An active project must always keep at least one open task.
+    NextActionRequired,
+    /// A task cannot be completed while it has open subtasks.
+    OpenSubtasks,
+}
+
+// SQLite only hands back the RAISE message, so rules are recognised by their text.
+const TRIGGER_RULES: &[(&str, Rule)] = &[
+    ("open task", Rule::NextActionRequired),
+    ("open subtasks", Rule::OpenSubtasks),
+];
+
+impl AuralisError {
+    /// `AuralisError::not_found("Task")` reads "Task not found".
+    pub fn not_found(what: &str) -> Self {
+        AuralisError::NotFound(format!("{what} not found"))
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        AuralisError::Validation(message.into())
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AuralisError::NotFound(_) => "NotFound",
+            AuralisError::Validation(_) => "Validation",
+            AuralisError::RuleViolation { .. } => "RuleViolation",
+            AuralisError::Db(_) => "Db",
+            AuralisError::Ai(_) => "Ai",
+            AuralisError::Io(_) => "Io",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AuralisError::NotFound(m)
+            | AuralisError::Validation(m)
+            | AuralisError::RuleViolation { message: m, .. }
+            | AuralisError::Db(m)
+            | AuralisError::Ai(m)
+            | AuralisError::Io(m) => m,
+        }
+    }
+
+    pub fn rule(&self) -> Option<Rule> {
+        match self {
+            AuralisError::RuleViolation { rule, .. } => *rule,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AuralisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AuralisError {}
+
+impl Serialize for AuralisError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let rule = self.rule();
+        let mut s = serializer.serialize_struct("AuralisError", 2 + rule.is_some() as usize)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", self.message())?;
+        if let Some(rule) = rule {
+            s.serialize_field("rule", &rule)?;
+        }
+        s.end()
+    }
+}
+
+impl From<rusqlite::Error> for AuralisError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e {
+            rusqlite::Error::QueryReturnedNoRows => AuralisError::NotFound("Not found".into()),
+            rusqlite::Error::SqliteFailure(err, Some(message))
+                if err.extended_code == ffi::SQLITE_CONSTRAINT_TRIGGER =>
+            {
+                let rule = TRIGGER_RULES
+                    .iter()
+                    .find(|(needle, _)| message.contains(needle))
+                    .map(|(_, rule)| *rule);
+                AuralisError::RuleViolation { rule, message }
+            }
+            rusqlite::Error::SqliteFailure(err, message)
+                if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                AuralisError::Validation(message.unwrap_or_else(|| err.to_string()))
+            }
+            other => AuralisError::Db(other.to_string()),
+        }
+    }
+}
+
+/// Bad input as reported by the parsers in this crate (dates, RRULEs, iCalendar,
+/// ...). It surfaces as a validation error; other failures are mapped explicitly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError(pub String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<String> for ParseError {
+    fn from(message: String) -> Self {
+        ParseError(message)
+    }
+}
+
+impl From<&str> for ParseError {
+    fn from(message: &str) -> Self {
+        ParseError(message.to_string())
+    }
+}
+
+impl From<ParseError> for AuralisError {
+    fn from(e: ParseError) -> Self {
+        AuralisError::Validation(e.0)
+    }
+}
@@ -0,0 +1,320 @@
+//! Focus sessions (pomodoro): rounds of work on one task separated by breaks, with
+//! a long break every few rounds. [`FocusSession`] is the state machine; it is
+//! driven by the clock in `commands::focus` and never reads the time itself.
+//!
+//! Work time is reported as [`FocusEvent::Worked`] spans, one per stretch without
+//! a pause, which [`record`] stores as time entries on the task.
+
+use crate::datetime;
+use crate::error::AuralisError;
+use crate::store::time_entries::{self, TimeEntry, TimeEntryInput};
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct FocusConfig {
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    /// A long break follows every this many rounds.
+    pub long_break_every: u32,
+    /// The session completes after this many rounds of work.
+    pub rounds: u32,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        FocusConfig {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 4,
+            rounds: 4,
+        }
+    }
+}
+
+impl FocusConfig {
+    fn validate(&self) -> Result<(), AuralisError> {
+        let ranges = [
+            ("Work", self.work_minutes, 1, 180),
+            ("Short break", self.short_break_minutes, 1, 60),
+            ("Long break", self.long_break_minutes, 1, 120),
+        ];
+        for (what, minutes, min, max) in ranges {
+            if !(min..=max).contains(&minutes) {
+                return Err(AuralisError::validation(format!(
+                    "{what} must be between {min} and {max} minutes"
+                )));
+            }
+        }
+        if !(1..=12).contains(&self.rounds) || !(1..=12).contains(&self.long_break_every) {
+            return Err(AuralisError::validation("Rounds and the long-break interval must be between 1 and 12"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusPhase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+/// What the webview shows; also the `focus:tick` payload.
+#[derive(Serialize, Clone, Debug)]
+pub struct FocusStatus {
+    pub session_id: String,
+    pub task_id: String,
+    pub task_title: String,
+    pub config: FocusConfig,
+    pub phase: FocusPhase,
+    pub round: u32, // 1-based; during a break, the round just finished
+    pub phase_started_at: String,
+    pub phase_ends_at: String, // pushed back by pauses
+    pub remaining_seconds: i64,
+    pub paused: bool,
+    pub rounds_completed: u32,
+    pub focused_seconds: i64,
+}
+
+/// The `focus:completed` payload.
+#[derive(Serialize, Clone, Debug)]
+pub struct FocusSummary {
+    pub session_id: String,
+    pub task_id: String,
+    pub rounds_completed: u32,
+    pub focused_seconds: i64,
+    pub stopped_early: bool,
+}
+
+/// The `focus:phase_changed` payload.
+#[derive(Serialize, Clone, Debug)]
+pub struct PhaseChange {
+    pub session_id: String,
+    pub from: FocusPhase,
+    pub to: FocusPhase,
+    pub round: u32,
+    pub at: String,
+}
+
+#[derive(Clone, Debug)]
+pub enum FocusEvent {
+    PhaseChanged(PhaseChange),
+    /// Work done without a pause, to be logged on the task.
+    Worked {
+        round: u32,
+        started_at: DateTime<Utc>,
+        ended_at: DateTime<Utc>,
+    },
+    Completed(FocusSummary),
+}
+
+pub struct FocusSession {
+    pub id: String,
+    pub task_id: String,
+    pub task_title: String,
+    config: FocusConfig,
+    phase: FocusPhase,
+    round: u32,
+    phase_started: DateTime<Utc>,
+    phase_ends: DateTime<Utc>,
+    paused_at: Option<DateTime<Utc>>,
+    work_since: Option<DateTime<Utc>>, // start of the running stretch of work
+    rounds_completed: u32,
+    focused_seconds: i64,
+    finished: bool,
+}
+
+impl FocusSession {
+    /// Starts the first round of work at `now`.
+    pub fn new(task_id: &str, task_title: &str, config: FocusConfig, now: DateTime<Utc>) -> Result<Self, AuralisError> {
+        config.validate()?;
+        Ok(FocusSession {
+            id: format!("focus_{}", uuid::Uuid::new_v4()),
+            task_id: task_id.to_string(),
+            task_title: task_title.to_string(),
+            config,
+            phase: FocusPhase::Work,
+            round: 1,
+            phase_started: now,
+            phase_ends: now + minutes(config.work_minutes),
+            paused_at: None,
+            work_since: Some(now),
+            rounds_completed: 0,
+            focused_seconds: 0,
+            finished: false,
+        })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> FocusStatus {
+        let clock = self.paused_at.unwrap_or(now);
+        FocusStatus {
+            session_id: self.id.clone(),
+            task_id: self.task_id.clone(),
+            task_title: self.task_title.clone(),
+            config: self.config,
+            phase: self.phase,
+            round: self.round,
+            phase_started_at: datetime::format_utc(self.phase_started),
+            phase_ends_at: datetime::format_utc(self.phase_ends),
+            remaining_seconds: (self.phase_ends - clock).num_seconds().max(0),
+            paused: self.paused_at.is_some(),
+            rounds_completed: self.rounds_completed,
+            focused_seconds: self.focused_seconds,
+        }
+    }
+
+    /// Moves through every phase that has run out by `now`; after a long sleep that
+    /// can be several.
+    pub fn advance(&mut self, now: DateTime<Utc>) -> Vec<FocusEvent> {
+        let mut events = Vec::new();
+        while !self.finished && self.paused_at.is_none() && self.phase_ends <= now {
+            self.end_phase(self.phase_ends, &mut events);
+        }
+        events
+    }
+
+    pub fn pause(&mut self, now: DateTime<Utc>) -> Result<Vec<FocusEvent>, AuralisError> {
+        if self.paused_at.is_some() {
+            return Err(AuralisError::validation("The focus session is already paused"));
+        }
+        let mut events = self.advance(now);
+        if !self.finished {
+            self.paused_at = Some(now);
+            self.close_work(now, &mut events);
+        }
+        Ok(events)
+    }
+
+    pub fn resume(&mut self, now: DateTime<Utc>) -> Result<(), AuralisError> {
+        let paused_at = self
+            .paused_at
+            .take()
+            .ok_or_else(|| AuralisError::validation("The focus session is not paused"))?;
+        self.phase_ends += now - paused_at;
+        if self.phase == FocusPhase::Work {
+            self.work_since = Some(now);
+        }
+        Ok(())
+    }
+
+    /// Ends the current phase now. A skipped round of work still counts as done.
+    pub fn skip(&mut self, now: DateTime<Utc>) -> Vec<FocusEvent> {
+        if self.paused_at.is_some() {
+            let _ = self.resume(now);
+        }
+        let mut events = self.advance(now);
+        if !self.finished {
+            self.end_phase(now, &mut events);
+        }
+        events
+    }
+
+    /// Ends the session early, keeping the work done so far.
+    pub fn stop(&mut self, now: DateTime<Utc>) -> Vec<FocusEvent> {
+        let mut events = if self.paused_at.is_some() { Vec::new() } else { self.advance(now) };
+        if !self.finished {
+            self.close_work(now, &mut events);
+            self.finish(true, &mut events);
+        }
+        events
+    }
+
+    fn duration(&self, phase: FocusPhase) -> Duration {
+        minutes(match phase {
+            FocusPhase::Work => self.config.work_minutes,
+            FocusPhase::ShortBreak => self.config.short_break_minutes,
+            FocusPhase::LongBreak => self.config.long_break_minutes,
+        })
+    }
+
+    fn close_work(&mut self, at: DateTime<Utc>, events: &mut Vec<FocusEvent>) {
+        if let Some(started_at) = self.work_since.take() {
+            if at > started_at {
+                self.focused_seconds += (at - started_at).num_seconds();
+                events.push(FocusEvent::Worked {
+                    round: self.round,
+                    started_at,
+                    ended_at: at,
+                });
+            }
+        }
+    }
+
+    fn finish(&mut self, stopped_early: bool, events: &mut Vec<FocusEvent>) {
+        self.finished = true;
+        events.push(FocusEvent::Completed(FocusSummary {
+            session_id: self.id.clone(),
+            task_id: self.task_id.clone(),
+            rounds_completed: self.rounds_completed,
+            focused_seconds: self.focused_seconds,
+            stopped_early,
+        }));
+    }
+
+    fn end_phase(&mut self, at: DateTime<Utc>, events: &mut Vec<FocusEvent>) {
+        let next = match self.phase {
+            FocusPhase::Work => {
+                self.close_work(at, events);
+                self.rounds_completed += 1;
+                if self.round >= self.config.rounds {
+                    self.finish(false, events);
+                    return;
+                }
+                if self.round.is_multiple_of(self.config.long_break_every) {
+                    FocusPhase::LongBreak
+                } else {
+                    FocusPhase::ShortBreak
+                }
+            }
+            FocusPhase::ShortBreak | FocusPhase::LongBreak => {
+                self.round += 1;
+                self.work_since = Some(at);
+                FocusPhase::Work
+            }
+        };
+
+        events.push(FocusEvent::PhaseChanged(PhaseChange {
+            session_id: self.id.clone(),
+            from: self.phase,
+            to: next,
+            round: self.round,
+            at: datetime::format_utc(at),
+        }));
+        self.phase = next;
+        self.phase_started = at;
+        self.phase_ends = at + self.duration(next);
+    }
+}
+
+fn minutes(n: u32) -> Duration {
+    Duration::minutes(i64::from(n))
+}
+
+/// Logs the work in `events` as time entries on the session's task.
+pub fn record(conn: &Connection, session: &FocusSession, events: &[FocusEvent]) -> Result<Vec<TimeEntry>, AuralisError> {
+    let mut logged = Vec::new();
+    for event in events {
+        if let FocusEvent::Worked { round, started_at, ended_at } = event {
+            logged.push(time_entries::add(
+                conn,
+                TimeEntryInput {
+                    task_id: session.task_id.clone(),
+                    started_at: datetime::format_utc(*started_at),
+                    ended_at: datetime::format_utc(*ended_at),
+                    note: Some(format!("Focus round {round}")),
+                },
+            )?);
+        }
+    }
+    Ok(logged)
+}
@@ -0,0 +1,173 @@
+//! Minimal YAML front matter for Markdown files: a leading `---` block of flat
+//! `key: value` lines. Anything else in the block (lists, nested maps, comments)
+//! is kept verbatim, so rewriting a file never drops what another tool put there.
+
+#[derive(Debug, Clone)]
+enum Line {
+    Field { key: String, value: String, raw: String },
+    Raw(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    lines: Vec<Line>,
+    pub body: String,
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        let mut out = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        }
+        return out;
+    }
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return inner.replace("''", "'");
+    }
+    // Plain scalars end at a comment.
+    match value.find(" #") {
+        Some(i) => value[..i].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+fn quote(value: &str) -> String {
+    const SPECIAL_START: &[char] = &[
+        '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`',
+    ];
+    let reserved = ["true", "false", "yes", "no", "on", "off", "null", "~"]
+        .iter()
+        .any(|w| value.eq_ignore_ascii_case(w));
+
+    let needs_quotes = value.is_empty()
+        || reserved
+        || value.starts_with(SPECIAL_START)
+        || value.trim() != value
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.ends_with(':')
+        || value.contains(['\n', '\t', '"', '\\']);
+
+    if !needs_quotes {
+        return value.to_string();
+    }
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+    format!("\"{escaped}\"")
+}
+
+impl Document {
+    /// Splits `text` into front matter and body. Text without a front matter block
+    /// is all body.
+    pub fn parse(text: &str) -> Document {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+            return Document {
+                lines: Vec::new(),
+                body: text.to_string(),
+            };
+        };
+
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for raw in rest.split_inclusive('\n') {
+            offset += raw.len();
+            let line = raw.trim_end_matches(['\n', '\r']);
+            if line == "---" || line == "..." {
+                return Document {
+                    lines,
+                    body: rest[offset..].to_string(),
+                };
+            }
+
+            let field = (!line.starts_with([' ', '\t', '-', '#']))
+                .then(|| line.split_once(':'))
+                .flatten()
+                .filter(|(key, value)| !key.trim().is_empty() && (value.is_empty() || value.starts_with(' ')));
+            lines.push(match field {
+                Some((key, value)) => Line::Field {
+                    key: key.trim().to_string(),
+                    value: unquote(value),
+                    raw: line.to_string(),
+                },
+                None => Line::Raw(line.to_string()),
+            });
+        }
+
+        // No closing delimiter: not front matter after all.
+        Document {
+            lines: Vec::new(),
+            body: text.to_string(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().find_map(|line| match line {
+            Line::Field { key: k, value, .. } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Sets or (with `None`) removes a field. New fields go after the existing ones.
+    pub fn set(&mut self, key: &str, value: Option<&str>) {
+        let at = self
+            .lines
+            .iter()
+            .position(|line| matches!(line, Line::Field { key: k, .. } if k == key));
+
+        match (at, value) {
+            (Some(i), Some(value)) => {
+                if let Line::Field { value: old, .. } = &self.lines[i] {
+                    if old == value {
+                        return;
+                    }
+                }
+                self.lines[i] = Line::Field {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    raw: format!("{key}: {}", quote(value)),
+                };
+            }
+            (None, Some(value)) => self.lines.push(Line::Field {
+                key: key.to_string(),
+                value: value.to_string(),
+                raw: format!("{key}: {}", quote(value)),
+            }),
+            (Some(i), None) => {
+                self.lines.remove(i);
+            }
+            (None, None) => {}
+        }
+    }
+
+    pub fn render(&self) -> String {
+        if self.lines.is_empty() {
+            return self.body.clone();
+        }
+        let mut out = String::from("---\n");
+        for line in &self.lines {
+            match line {
+                Line::Field { raw, .. } | Line::Raw(raw) => out.push_str(raw),
+            }
+            out.push('\n');
+        }
+        out.push_str("---\n");
+        out.push_str(&self.body);
+        out
+    }
+}
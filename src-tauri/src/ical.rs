@@ -0,0 +1,324 @@
+//! Minimal RFC 5545 reader/writer: content-line (un)folding, escaping, components,
+//! and the date/duration value types Auralis maps onto its tables.
+
+use crate::datetime;
+use crate::error::ParseError;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+#[derive(Debug, Clone)]
+pub struct Property {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Component {
+    pub name: String,
+    pub properties: Vec<Property>,
+    pub children: Vec<Component>,
+}
+
+impl Component {
+    pub fn new(name: &str) -> Self {
+        Component {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Property> + 'a {
+        self.properties
+            .iter()
+            .filter(move |p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Unescaped TEXT value of a property, if present and non-empty.
+    pub fn text(&self, name: &str) -> Option<String> {
+        self.get(name)
+            .map(|p| unescape_text(&p.value))
+            .filter(|v| !v.trim().is_empty())
+    }
+
+    /// Adds a property whose value is already in wire format.
+    pub fn push_raw(&mut self, name: &str, value: impl Into<String>) {
+        self.properties.push(Property {
+            name: name.to_string(),
+            params: Vec::new(),
+            value: value.into(),
+        });
+    }
+
+    /// Adds a property from a full content line such as `EXDATE;VALUE=DATE:20240101`.
+    pub fn push_line(&mut self, line: &str) {
+        if let Some(prop) = parse_line(line) {
+            self.properties.push(prop);
+        }
+    }
+
+    /// Adds a TEXT property, escaping it.
+    pub fn push_text(&mut self, name: &str, value: &str) {
+        self.push_raw(name, escape_text(value));
+    }
+
+    /// Serialises the component with folded CRLF-terminated lines.
+    pub fn write(&self, out: &mut String) {
+        write_line(out, &format!("BEGIN:{}", self.name));
+        for p in &self.properties {
+            let mut line = p.name.clone();
+            for (k, v) in &p.params {
+                if v.contains([':', ';', ',']) {
+                    line.push_str(&format!(";{k}=\"{v}\""));
+                } else {
+                    line.push_str(&format!(";{k}={v}"));
+                }
+            }
+            line.push(':');
+            line.push_str(&p.value);
+            write_line(out, &line);
+        }
+        for child in &self.children {
+            child.write(out);
+        }
+        write_line(out, &format!("END:{}", self.name));
+    }
+}
+
+/// Parses an iCalendar stream into its top-level components (usually one VCALENDAR).
+pub fn parse(input: &str) -> Result<Vec<Component>, ParseError> {
+    let mut stack: Vec<Component> = Vec::new();
+    let mut roots = Vec::new();
+
+    for (n, line) in unfold(input).into_iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let prop = parse_line(&line).ok_or_else(|| format!("Malformed line {}: {line}", n + 1))?;
+
+        if prop.name.eq_ignore_ascii_case("BEGIN") {
+            stack.push(Component::new(&prop.value.to_ascii_uppercase()));
+        } else if prop.name.eq_ignore_ascii_case("END") {
+            let done = stack
+                .pop()
+                .ok_or_else(|| format!("Unexpected END:{} on line {}", prop.value, n + 1))?;
+            if !done.name.eq_ignore_ascii_case(&prop.value) {
+                return Err(format!(
+                    "Mismatched END:{} for BEGIN:{} on line {}",
+                    prop.value,
+                    done.name,
+                    n + 1
+                ).into());
+            }
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(done),
+                None => roots.push(done),
+            }
+        } else {
+            match stack.last_mut() {
+                Some(current) => current.properties.push(prop),
+                None => return Err(format!("Property outside of a component on line {}", n + 1).into()),
+            }
+        }
+    }
+
+    if let Some(open) = stack.last() {
+        return Err(format!("Missing END:{}", open.name).into());
+    }
+
+    Ok(roots)
+}
+
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in input.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match (raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')), lines.last_mut()) {
+            (Some(cont), Some(prev)) => prev.push_str(cont),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+fn parse_line(line: &str) -> Option<Property> {
+    // The value starts at the first colon that is not inside a quoted parameter value.
+    let mut in_quotes = false;
+    let mut split = None;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => {
+                split = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let split = split?;
+    let (head, value) = (&line[..split], &line[split + 1..]);
+
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.trim().to_ascii_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+// Lines are folded at 75 octets without splitting a UTF-8 sequence.
+fn write_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += len;
+    }
+    out.push_str("\r\n");
+}
+
+pub fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+pub fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// A DATE or DATE-TIME value resolved to UTC. `all_day` marks VALUE=DATE.
+#[derive(Debug, Clone, Copy)]
+pub struct IcalTime {
+    pub at: DateTime<Utc>,
+    pub all_day: bool,
+}
+
+/// Resolves a DTSTART/DTEND/DUE-style property. UTC values are taken as-is,
+/// TZID values use the IANA zone when known, and everything else is local time.
+pub fn parse_time(prop: &Property) -> Result<IcalTime, ParseError> {
+    let value = prop.value.trim();
+    let is_date = prop
+        .param("VALUE")
+        .is_some_and(|v| v.eq_ignore_ascii_case("DATE"))
+        || (value.len() == 8 && value.chars().all(|c| c.is_ascii_digit()));
+
+    if is_date {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map_err(|_| format!("Invalid date '{value}' in {}", prop.name))?;
+        let at = datetime::local_to_utc(date.and_hms_opt(0, 0, 0).unwrap_or_default())?;
+        return Ok(IcalTime { at, all_day: true });
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .map_err(|_| format!("Invalid date-time '{value}' in {}", prop.name))?;
+        return Ok(IcalTime {
+            at: naive.and_utc(),
+            all_day: false,
+        });
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .map_err(|_| format!("Invalid date-time '{value}' in {}", prop.name))?;
+
+    let zoned = prop
+        .param("TZID")
+        .and_then(|tzid| tzid.parse::<chrono_tz::Tz>().ok())
+        .and_then(|tz| tz.from_local_datetime(&naive).earliest())
+        .map(|dt| dt.with_timezone(&Utc));
+
+    let at = match zoned {
+        Some(at) => at,
+        None => datetime::local_to_utc(naive)?,
+    };
+
+    Ok(IcalTime { at, all_day: false })
+}
+
+/// Parses a DURATION value such as `PT1H30M`, `P1D` or `-P1W`.
+pub fn parse_duration(value: &str) -> Result<Duration, ParseError> {
+    let err = || ParseError(format!("Invalid duration '{value}'"));
+    let v = value.trim();
+    let (negative, v) = match v.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, v.strip_prefix('+').unwrap_or(v)),
+    };
+    let v = v.strip_prefix('P').ok_or_else(err)?;
+
+    let mut total = Duration::zero();
+    let mut num = String::new();
+    let mut in_time = false;
+    for c in v.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => num.push(c),
+            _ => {
+                let n: i64 = num.parse().map_err(|_| err())?;
+                num.clear();
+                let part = match (c, in_time) {
+                    ('W', false) => Duration::try_weeks(n),
+                    ('D', false) => Duration::try_days(n),
+                    ('H', true) => Duration::try_hours(n),
+                    ('M', true) => Duration::try_minutes(n),
+                    ('S', true) => Duration::try_seconds(n),
+                    _ => return Err(err()),
+                };
+                total = part.and_then(|part| total.checked_add(&part)).ok_or_else(err)?;
+            }
+        }
+    }
+    if !num.is_empty() {
+        return Err(err());
+    }
+
+    Ok(if negative { -total } else { total })
+}
+
+pub fn format_utc(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
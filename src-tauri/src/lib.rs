@@ -1,6 +1,22 @@
 mod ai;
+pub mod backup;
 mod commands;
-mod db;
+mod datetime;
+mod diff;
+pub mod db;
+pub mod domain;
+pub mod error;
+pub mod focus;
+mod frontmatter;
+mod ical;
+pub mod migrations;
+mod patch;
+pub mod quick_add;
+pub mod recurrence;
+pub mod store;
+mod task_query;
+pub mod vault;
+mod wikilink;
 
 use tauri::Manager;
 
@@ -15,11 +31,24 @@ pub fn run() {
         .setup(|app| {
             let handle = app.handle().clone();
 
-            // Initialise SQLite DB (creates auralis.db + runs schema.sql)
+            // Initialise SQLite DB (creates auralis.db + applies pending migrations)
             let db = db::init_db(&handle)?;
 
             // Make DB available to all commands via app state
             app.manage(db);
+            app.manage(backup::BackupDir(db::backup_dir(&handle)?));
+            app.manage(commands::focus::Focus::default());
+
+            // Daily/weekly backups: checked now, then every hour while the app runs
+            tauri::async_runtime::spawn(commands::backup::run_schedule(handle.clone()));
+
+            // Time tracking: recovers a timer left running, then keeps it stamped
+            tauri::async_runtime::spawn(commands::time_entries::run_heartbeat(handle.clone()));
+
+            // Markdown vault sync: runs whenever notes or vault files change
+            let (vault_tx, vault_rx) = tokio::sync::mpsc::unbounded_channel();
+            app.manage(commands::vault::Vault::new(vault_tx));
+            tauri::async_runtime::spawn(commands::vault::run_worker(handle, vault_rx));
 
             Ok(())
         })
@@ -31,7 +60,40 @@ pub fn run() {
             commands::inbox::inbox_set_state,
             commands::tasks::task_add,
             commands::tasks::task_list,
+            commands::tasks::task_query,
             commands::tasks::task_set_status,
+            commands::tasks::task_add_subtask,
+            commands::tasks::task_set_parent,
+            commands::tasks::task_tree,
+            commands::tasks::task_dependencies,
+            commands::tasks::task_add_dependency,
+            commands::tasks::task_remove_dependency,
+            commands::time_entries::task_start_timer,
+            commands::time_entries::task_stop_timer,
+            commands::time_entries::task_running_timer,
+            commands::time_entries::task_resume_timer,
+            commands::time_entries::time_entry_list,
+            commands::time_entries::time_entry_add,
+            commands::time_entries::time_entry_update,
+            commands::time_entries::time_entry_delete,
+            commands::time_entries::time_report,
+            commands::weekly_review::weekly_review_start,
+            commands::weekly_review::weekly_review_current,
+            commands::weekly_review::weekly_review_mark,
+            commands::weekly_review::weekly_review_advance,
+            commands::weekly_review::weekly_review_abandon,
+            commands::weekly_review::weekly_review_history,
+            commands::focus::focus_start,
+            commands::focus::focus_status,
+            commands::focus::focus_pause,
+            commands::focus::focus_resume,
+            commands::focus::focus_skip,
+            commands::focus::focus_stop,
+            commands::checklists::checklist_list,
+            commands::checklists::checklist_add,
+            commands::checklists::checklist_update,
+            commands::checklists::checklist_delete,
+            commands::checklists::checklist_reorder,
             commands::inbox::inbox_convert_to_task,
             commands::areas::area_add,
             commands::areas::area_list,
@@ -40,14 +102,65 @@ pub fn run() {
             commands::projects::project_list,
             commands::projects::project_set_status,
             commands::projects::project_get,
+            commands::projects::project_rename,
+            commands::projects::project_update,
+            commands::projects::project_stats,
+            commands::projects::project_stats_batch,
+            commands::project_templates::project_template_list,
+            commands::project_templates::project_template_get,
+            commands::project_templates::project_template_save,
+            commands::project_templates::project_template_delete,
+            commands::project_templates::project_from_template,
             commands::tasks::task_list_by_project,
             commands::tasks::task_set_project,
+            commands::tasks::task_set_recurrence,
+            commands::tasks::task_update,
+            commands::tasks::task_quick_add_preview,
+            commands::tasks::task_quick_add,
             commands::notes::note_add,
             commands::notes::note_list,
             commands::notes::note_update,
             commands::notes::note_delete,
             commands::notes::note_get,
+            commands::notes::note_history,
+            commands::notes::note_deleted_list,
+            commands::notes::note_revision_diff,
+            commands::notes::note_restore,
+            commands::notes::note_backlinks,
+            commands::links::entity_links,
+            commands::tags::tag_list,
+            commands::tags::tag_list_for,
+            commands::tags::tag_add,
+            commands::tags::tag_remove,
+            commands::tags::tag_rename,
+            commands::tags::tag_merge,
+            commands::tags::tag_delete,
+            commands::smart_lists::smart_list_list,
+            commands::smart_lists::smart_list_save,
+            commands::smart_lists::smart_list_reorder,
+            commands::smart_lists::smart_list_delete,
+            commands::smart_lists::smart_list_run,
             commands::ai::ai_summarise_note,
+            commands::calendar::calendar_event_add,
+            commands::calendar::calendar_event_update,
+            commands::calendar::calendar_event_delete,
+            commands::calendar::calendar_event_get,
+            commands::calendar::calendar_list_range,
+            commands::agenda::agenda,
+            commands::calendar::calendar_event_update_occurrence,
+            commands::calendar::calendar_event_delete_occurrence,
+            commands::calendar::calendar_block_from_task,
+            commands::ical::ical_import,
+            commands::ical::ical_export,
+            commands::search::search,
+            commands::backup::backup_create,
+            commands::backup::backup_list,
+            commands::backup::backup_restore,
+            commands::workspace::workspace_export,
+            commands::workspace::workspace_import,
+            commands::vault::vault_get_dir,
+            commands::vault::vault_set_dir,
+            commands::vault::vault_sync,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,182 @@
+use crate::backup;
+use rusqlite::Connection;
+use std::path::Path;
+
+#[derive(Clone, Copy)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// Ordered schema steps, tracked via PRAGMA user_version.
+///
+/// Append new steps at the end; never edit a migration that has already shipped.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: include_str!("../db/migrations/0001_initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "task_recurrence",
+        sql: include_str!("../db/migrations/0002_task_recurrence.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "ical_uids",
+        sql: include_str!("../db/migrations/0003_ical_uids.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "search_index",
+        sql: include_str!("../db/migrations/0004_search_index.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "vault_sync",
+        sql: include_str!("../db/migrations/0005_vault_sync.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "note_revisions",
+        sql: include_str!("../db/migrations/0006_note_revisions.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "links",
+        sql: include_str!("../db/migrations/0007_links.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "tags",
+        sql: include_str!("../db/migrations/0008_tags.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "smart_lists",
+        sql: include_str!("../db/migrations/0009_smart_lists.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "subtasks",
+        sql: include_str!("../db/migrations/0010_subtasks.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "task_dependencies",
+        sql: include_str!("../db/migrations/0011_task_dependencies.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "time_entries",
+        sql: include_str!("../db/migrations/0012_time_entries.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "weekly_review",
+        sql: include_str!("../db/migrations/0013_weekly_review.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "project_templates",
+        sql: include_str!("../db/migrations/0014_project_templates.sql"),
+    },
+];
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+pub fn current_version(conn: &Connection) -> Result<i64, String> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read schema version: {e}"))
+}
+
+/// Brings the database up to the latest schema version.
+///
+/// If the database already holds data and there is work to do, a copy is written to
+/// `backup_dir` first. Each migration runs in its own transaction.
+pub fn migrate(conn: &mut Connection, backup_dir: Option<&Path>) -> Result<(), String> {
+    migrate_with(conn, MIGRATIONS, backup_dir)
+}
+
+/// [`migrate`] against `steps` instead of the app's own list, so the runner can be
+/// tested with a partial or broken schema history.
+pub fn migrate_with(conn: &mut Connection, steps: &[Migration], backup_dir: Option<&Path>) -> Result<(), String> {
+    let current = current_version(conn)?;
+    let latest = steps.last().map(|m| m.version).unwrap_or(0);
+
+    if current > latest {
+        return Err(format!(
+            "Database schema version {current} is newer than this version of Auralis supports ({latest}). Please update the app."
+        ));
+    }
+
+    let pending: Vec<&Migration> = steps.iter().filter(|m| m.version > current).collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    if let Some(dir) = backup_dir {
+        if has_user_tables(conn)? {
+            backup::before(conn, dir, &format!("migration-v{current}"))
+                .map_err(|e| format!("Failed to back up database before migration: {e}"))?;
+        }
+    }
+
+    // Rebuilding a table (the usual way to change a CHECK constraint) needs FK enforcement off,
+    // and the pragma is a no-op inside a transaction, so toggle it around the whole run.
+    conn.pragma_update(None, "foreign_keys", "OFF")
+        .map_err(|e| format!("Failed to disable foreign_keys for migration: {e}"))?;
+
+    let result = apply(conn, &pending);
+
+    conn.pragma_update(None, "foreign_keys", "ON")
+        .map_err(|e| format!("Failed to re-enable foreign_keys: {e}"))?;
+
+    result
+}
+
+fn apply(conn: &mut Connection, pending: &[&Migration]) -> Result<(), String> {
+    for m in pending {
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start migration {}: {e}", m.version))?;
+
+        tx.execute_batch(m.sql)
+            .map_err(|e| format!("Migration {} ({}) failed: {e}", m.version, m.name))?;
+
+        let violations: i64 = tx
+            .query_row("SELECT COUNT(1) FROM pragma_foreign_key_check", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to check foreign keys after migration {}: {e}", m.version))?;
+        if violations > 0 {
+            return Err(format!(
+                "Migration {} ({}) left {violations} foreign key violation(s)",
+                m.version, m.name
+            ));
+        }
+
+        tx.pragma_update(None, "user_version", m.version)
+            .map_err(|e| format!("Failed to record schema version {}: {e}", m.version))?;
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit migration {}: {e}", m.version))?;
+    }
+
+    Ok(())
+}
+
+fn has_user_tables(conn: &Connection) -> Result<bool, String> {
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(1) FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(count > 0)
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Deserializer};
+
+/// Deserializes a nullable patch field so that a missing key (`None`) can be told
+/// apart from an explicit `null` (`Some(None)`, i.e. "clear this column").
+///
+/// Use as `#[serde(default, deserialize_with = "crate::patch::nullable")]`.
+pub fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,471 @@
+//! Inline syntax for fast task entry, e.g.
+//! `Call dentist tomorrow 3pm !high #health +Q4Launch ~30m`.
+//!
+//! - dates: `today`, `tomorrow`, weekdays, `next friday`, `next week`, `next month`,
+//!   `this weekend`, `in 3 days`, `may 14`, `2024-05-14`, optionally with a time
+//!   (`3pm`, `15:30`, `at 9:15am`, `noon`). `due`/`by`/`on` before a date are dropped;
+//!   `start`/`scheduled`/`from` route it to `scheduled_at` instead of `due_at`.
+//! - `!high` / `!normal` / `!low` (or `!h`, `!n`, `!l`) set the priority.
+//! - `#Area` and `+Project` pick an area / project by name. Case and punctuation are
+//!   ignored, so `#adminlife` finds "Admin / Life".
+//! - `~30m`, `~1h30m`, `~1.5h` or `~45` set the estimate.
+//!
+//! Whatever is not recognised stays in the title, including `#` / `+` words that don't
+//! name an existing area or project.
+
+use crate::datetime;
+use crate::domain::Priority;
+use crate::store::tasks::MAX_ESTIMATE_MINUTES;
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Default)]
+pub struct QuickAdd {
+    pub title: String,
+    pub due_at: Option<String>,       // UTC, storage format
+    pub scheduled_at: Option<String>, // UTC, storage format
+    pub priority: Option<Priority>,
+    pub area_id: Option<String>,
+    pub project_id: Option<String>,
+    pub estimate_minutes: Option<i64>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sigil {
+    Area,
+    Project,
+}
+
+#[derive(Clone, Copy)]
+enum Slot {
+    Due,
+    Scheduled,
+}
+
+// A date-only due date means "by the end of that day"; a date-only start means
+// "from the beginning of it".
+const END_OF_DAY: (u32, u32, u32) = (23, 59, 59);
+
+/// Parses `input` relative to the local time `now`. `lookup` resolves `#area` and
+/// `+project` words to ids.
+pub fn parse(
+    input: &str,
+    now: NaiveDateTime,
+    mut lookup: impl FnMut(Sigil, &str) -> Option<String>,
+) -> QuickAdd {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let words: Vec<String> = tokens.iter().map(|t| normalize_word(t)).collect();
+
+    let mut out = QuickAdd::default();
+    let mut title: Vec<&str> = Vec::new();
+    let mut due: Option<NaiveDateTime> = None;
+    let mut scheduled: Option<NaiveDateTime> = None;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+
+        if let Some(p) = token.strip_prefix('!').and_then(parse_priority) {
+            if out.priority.is_none() {
+                out.priority = Some(p);
+                i += 1;
+                continue;
+            }
+        }
+
+        if let Some(m) = token.strip_prefix('~').and_then(parse_estimate) {
+            if out.estimate_minutes.is_none() {
+                out.estimate_minutes = Some(m);
+                i += 1;
+                continue;
+            }
+        }
+
+        let sigil = match token.chars().next() {
+            Some('#') => Some((Sigil::Area, &mut out.area_id)),
+            Some('+') => Some((Sigil::Project, &mut out.project_id)),
+            _ => None,
+        };
+        if let Some((kind, target)) = sigil {
+            let name = token[1..].trim_end_matches([',', '.', ';']);
+            if target.is_none() && !name.is_empty() {
+                match lookup(kind, name) {
+                    Some(id) => {
+                        *target = Some(id);
+                        i += 1;
+                        continue;
+                    }
+                    None => out.warnings.push(match kind {
+                        Sigil::Area => format!("No area matches '{name}'"),
+                        Sigil::Project => format!("No project matches '{name}'"),
+                    }),
+                }
+            }
+        }
+
+        if let Some((slot, at, used)) = date_phrase(&words, i, now) {
+            let target = match slot {
+                Slot::Due => &mut due,
+                Slot::Scheduled => &mut scheduled,
+            };
+            if target.is_none() {
+                *target = Some(at);
+                i += used;
+                continue;
+            }
+        }
+
+        title.push(token);
+        i += 1;
+    }
+
+    out.title = title.join(" ");
+
+    for (at, target) in [(due, &mut out.due_at), (scheduled, &mut out.scheduled_at)] {
+        if let Some(at) = at {
+            match datetime::local_to_utc(at) {
+                Ok(utc) => *target = Some(datetime::format_utc(utc)),
+                Err(e) => out.warnings.push(e.0),
+            }
+        }
+    }
+
+    out
+}
+
+/// Picks the candidate whose name matches `word`, ignoring case and punctuation.
+/// An exact match wins; otherwise a unique prefix match is accepted.
+pub fn match_name<'a>(word: &str, candidates: &'a [(String, String)]) -> Option<&'a str> {
+    let wanted = name_key(word);
+    if wanted.is_empty() {
+        return None;
+    }
+
+    if let Some((id, _)) = candidates.iter().find(|(_, name)| name_key(name) == wanted) {
+        return Some(id);
+    }
+
+    let mut prefixed = candidates
+        .iter()
+        .filter(|(_, name)| name_key(name).starts_with(&wanted));
+    match (prefixed.next(), prefixed.next()) {
+        (Some((id, _)), None) => Some(id),
+        _ => None,
+    }
+}
+
+fn name_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn normalize_word(token: &str) -> String {
+    token
+        .trim_end_matches([',', '.', ';', '!', '?'])
+        .to_lowercase()
+}
+
+fn parse_priority(word: &str) -> Option<Priority> {
+    match word.to_lowercase().as_str() {
+        "high" | "h" => Some(Priority::High),
+        "normal" | "n" | "medium" | "med" => Some(Priority::Normal),
+        "low" | "l" => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+fn parse_estimate(text: &str) -> Option<i64> {
+    let text = text.to_lowercase();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(minutes) = text.parse::<i64>() {
+        return (0..=MAX_ESTIMATE_MINUTES).contains(&minutes).then_some(minutes);
+    }
+
+    let mut total = 0.0;
+    let mut rest = text.as_str();
+    while !rest.is_empty() {
+        let num_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let value: f64 = rest[..num_len].parse().ok()?;
+        rest = &rest[num_len..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        total += match &rest[..unit_len] {
+            "h" | "hr" | "hrs" | "hour" | "hours" => value * 60.0,
+            "m" | "min" | "mins" => value,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+    }
+
+    // Checked before the cast, which would saturate rather than fail.
+    (total <= MAX_ESTIMATE_MINUTES as f64).then(|| total.round() as i64)
+}
+
+// Tries to read a date/time phrase starting at `i`, including a leading keyword.
+// Returns the slot it belongs in, the local time, and how many words it used.
+fn date_phrase(
+    words: &[String],
+    i: usize,
+    now: NaiveDateTime,
+) -> Option<(Slot, NaiveDateTime, usize)> {
+    let (slot, skip) = match words[i].as_str() {
+        "due" | "by" | "on" => (Slot::Due, 1),
+        "start" | "starts" | "starting" | "scheduled" | "from" => {
+            let on = matches!(words.get(i + 1).map(String::as_str), Some("on" | "at"));
+            (Slot::Scheduled, if on { 2 } else { 1 })
+        }
+        _ => (Slot::Due, 0),
+    };
+
+    // Weekday abbreviations ("sun", "sat", "wed") and "weekend" are ordinary words
+    // too, so they only count after a keyword.
+    let strict = skip == 0;
+    let (at, used) = moment(words, i + skip, now, strict, slot)?;
+    Some((slot, at, skip + used))
+}
+
+fn moment(
+    words: &[String],
+    i: usize,
+    now: NaiveDateTime,
+    strict: bool,
+    slot: Slot,
+) -> Option<(NaiveDateTime, usize)> {
+    let today = now.date();
+
+    if let Some((date, n)) = date_words(words, i, today, strict) {
+        return Some(match time_words(words, i + n) {
+            Some((time, m)) => (date.and_time(time), n + m),
+            None => (date.and_time(default_time(slot)), n),
+        });
+    }
+
+    let (time, n) = time_words(words, i)?;
+    if let Some((date, m)) = date_words(words, i + n, today, strict) {
+        return Some((date.and_time(time), n + m));
+    }
+
+    // A bare time means the next time the clock reads it.
+    let date = if time > now.time() {
+        today
+    } else {
+        today.succ_opt()?
+    };
+    Some((date.and_time(time), n))
+}
+
+fn default_time(slot: Slot) -> NaiveTime {
+    match slot {
+        Slot::Due => {
+            let (h, m, s) = END_OF_DAY;
+            NaiveTime::from_hms_opt(h, m, s).unwrap_or_default()
+        }
+        Slot::Scheduled => NaiveTime::MIN,
+    }
+}
+
+fn date_words(
+    words: &[String],
+    i: usize,
+    today: NaiveDate,
+    strict: bool,
+) -> Option<(NaiveDate, usize)> {
+    let word = words.get(i)?.as_str();
+    let next = words.get(i + 1).map(String::as_str);
+
+    match word {
+        "today" | "tonight" => return Some((today, 1)),
+        "tomorrow" | "tmrw" | "tmr" => return Some((today.succ_opt()?, 1)),
+        "weekend" if !strict => return Some((this_weekday(today, Weekday::Sat), 1)),
+        "next" => {
+            let next = next?;
+            if let Some(wd) = weekday(next, false) {
+                // "next friday" is the friday of next week.
+                let monday = today.week(Weekday::Mon).first_day() + Days::new(7);
+                return Some((monday + Days::new(wd.num_days_from_monday() as u64), 2));
+            }
+            return match next {
+                "week" => Some((today.week(Weekday::Mon).first_day() + Days::new(7), 2)),
+                "month" => Some((today.with_day(1)?.checked_add_months(Months::new(1))?, 2)),
+                "weekend" => Some((this_weekday(today, Weekday::Sat) + Days::new(7), 2)),
+                _ => None,
+            };
+        }
+        "this" => {
+            let next = next?;
+            if let Some(wd) = weekday(next, false) {
+                return Some((this_weekday(today, wd), 2));
+            }
+            return match next {
+                "weekend" => Some((this_weekday(today, Weekday::Sat), 2)),
+                _ => None,
+            };
+        }
+        "in" => {
+            let (count, unit, n) = match (next, words.get(i + 2).map(String::as_str)) {
+                (Some(a), Some(b)) if a.parse::<u32>().is_ok() => (a.parse::<u32>().ok()?, b, 3),
+                (Some(a), _) => {
+                    let split = a.find(|c: char| !c.is_ascii_digit())?;
+                    (a[..split].parse::<u32>().ok()?, &a[split..], 2)
+                }
+                _ => return None,
+            };
+            let date = match unit {
+                "d" | "day" | "days" => today.checked_add_days(Days::new(count as u64))?,
+                "w" | "week" | "weeks" => today.checked_add_days(Days::new(7 * count as u64))?,
+                "month" | "months" => today.checked_add_months(Months::new(count))?,
+                _ => return None,
+            };
+            return Some((date, n));
+        }
+        _ => {}
+    }
+
+    if let Some(wd) = weekday(word, strict) {
+        let ahead = (wd.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        let ahead = if ahead == 0 { 7 } else { ahead };
+        return Some((today + Days::new(ahead as u64), 1));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+        return Some((date, 1));
+    }
+
+    // "may 14" / "14 may", rolled into next year once the date has passed.
+    let (month, day) = match (month(word), next.and_then(day_of_month)) {
+        (Some(m), Some(d)) => (m, d),
+        _ => match (day_of_month(word), next.and_then(month)) {
+            (Some(d), Some(m)) => (m, d),
+            _ => return None,
+        },
+    };
+    let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if date < today {
+        return Some((NaiveDate::from_ymd_opt(today.year() + 1, month, day)?, 2));
+    }
+    Some((date, 2))
+}
+
+fn time_words(words: &[String], i: usize) -> Option<(NaiveTime, usize)> {
+    let (skip, word) = match words.get(i)?.as_str() {
+        "at" | "@" => (1, words.get(i + 1)?.as_str()),
+        w => (0, w),
+    };
+
+    if word == "noon" {
+        return Some((NaiveTime::from_hms_opt(12, 0, 0)?, skip + 1));
+    }
+
+    if let Some(t) = clock(word) {
+        return Some((t, skip + 1));
+    }
+
+    // "3 pm" / "3:30 pm"
+    let suffix = words.get(i + skip + 1).map(String::as_str)?;
+    if suffix == "am" || suffix == "pm" {
+        return Some((clock(&format!("{word}{suffix}"))?, skip + 2));
+    }
+
+    None
+}
+
+// 3pm, 3:30pm, 11am, 15:30. A bare number is not a time.
+fn clock(word: &str) -> Option<NaiveTime> {
+    let (digits, meridiem) = match word.strip_suffix("am") {
+        Some(d) => (d, Some(false)),
+        None => match word.strip_suffix("pm") {
+            Some(d) => (d, Some(true)),
+            None => (word, None),
+        },
+    };
+
+    let (hour, minute) = match digits.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None if meridiem.is_some() => (digits.parse::<u32>().ok()?, 0),
+        _ => return None,
+    };
+
+    let hour = match meridiem {
+        Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn this_weekday(today: NaiveDate, wd: Weekday) -> NaiveDate {
+    let ahead = (wd.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    today + Days::new(ahead as u64)
+}
+
+fn weekday(word: &str, strict: bool) -> Option<Weekday> {
+    let full = match word {
+        "monday" => Some(Weekday::Mon),
+        "tuesday" => Some(Weekday::Tue),
+        "wednesday" => Some(Weekday::Wed),
+        "thursday" => Some(Weekday::Thu),
+        "friday" => Some(Weekday::Fri),
+        "saturday" => Some(Weekday::Sat),
+        "sunday" => Some(Weekday::Sun),
+        _ => None,
+    };
+    if full.is_some() || strict {
+        return full;
+    }
+
+    match word {
+        "mon" => Some(Weekday::Mon),
+        "tue" | "tues" => Some(Weekday::Tue),
+        "wed" | "weds" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" => Some(Weekday::Thu),
+        "fri" => Some(Weekday::Fri),
+        "sat" => Some(Weekday::Sat),
+        "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    if word.len() < 3 {
+        return None;
+    }
+    // Full names and any prefix of three or more letters ("sep", "sept").
+    MONTHS
+        .iter()
+        .position(|m| m.starts_with(word))
+        .map(|p| p as u32 + 1)
+}
+
+fn day_of_month(word: &str) -> Option<u32> {
+    let digits = word
+        .strip_suffix("st")
+        .or_else(|| word.strip_suffix("nd"))
+        .or_else(|| word.strip_suffix("rd"))
+        .or_else(|| word.strip_suffix("th"))
+        .unwrap_or(word);
+    digits.parse::<u32>().ok().filter(|d| (1..=31).contains(d))
+}
@@ -0,0 +1,700 @@
+//! RFC 5545 recurrence rules (the subset Auralis supports) and their expansion.
+//!
+//! A recurrence is stored as text in content-line form, e.g.
+//!
+//! ```text
+//! RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE
+//! EXDATE:20240110T090000Z
+//! ```
+//!
+//! Supported rule parts: FREQ (DAILY/WEEKLY/MONTHLY/YEARLY), INTERVAL, BYDAY
+//! (with ordinals for MONTHLY/YEARLY), BYMONTHDAY, BYMONTH, COUNT, UNTIL and WKST
+//! (accepted, weeks always start on Monday). Expansion happens in wall-clock time
+//! of the given timezone, so "every Monday at 09:00" stays at 09:00 across DST changes.
+
+use crate::error::ParseError;
+use chrono::{DateTime, Datelike, Days, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use std::collections::VecDeque;
+use std::fmt;
+
+// Upper bounds that keep a malformed or unbounded rule from spinning forever.
+const MAX_EXPANSION: usize = 5000;
+const MAX_IDLE_PERIODS: u32 = 1200;
+const MAX_INTERVAL: u32 = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeekdayNum {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Until {
+    Utc(DateTime<Utc>),
+    /// Local wall-clock time; a DATE value becomes the last second of that day.
+    Floating(NaiveDateTime),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RRule {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<WeekdayNum>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub count: Option<u32>,
+    pub until: Option<Until>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExDate {
+    Utc(DateTime<Utc>),
+    Floating(NaiveDateTime),
+    Date(NaiveDate),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recurrence {
+    pub rule: RRule,
+    pub exdates: Vec<ExDate>,
+}
+
+impl Recurrence {
+    /// Parses `RRULE:`/`EXDATE` content lines. A bare `FREQ=...` string is accepted as the rule.
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut rule: Option<RRule> = None;
+        let mut exdates = Vec::new();
+
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (name, params, value) = split_content_line(line);
+
+            match name.to_ascii_uppercase().as_str() {
+                "RRULE" => {
+                    if rule.is_some() {
+                        return Err("Only one RRULE is supported".into());
+                    }
+                    rule = Some(parse_rrule(value)?);
+                }
+                "EXDATE" => {
+                    let is_date = params.to_ascii_uppercase().contains("VALUE=DATE")
+                        && !params.to_ascii_uppercase().contains("VALUE=DATE-TIME");
+                    for v in value.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+                        exdates.push(parse_exdate(v, is_date)?);
+                    }
+                }
+                _ if line.to_ascii_uppercase().starts_with("FREQ=") => {
+                    if rule.is_some() {
+                        return Err("Only one RRULE is supported".into());
+                    }
+                    rule = Some(parse_rrule(line)?);
+                }
+                other => return Err(format!("Unsupported recurrence property '{other}'").into()),
+            }
+        }
+
+        let rule = rule.ok_or_else(|| "Recurrence is missing an RRULE".to_string())?;
+        Ok(Recurrence { rule, exdates })
+    }
+
+    /// Occurrence start times for a series that begins at `dtstart`, in order.
+    pub fn occurrences<Tz: TimeZone>(&self, dtstart: DateTime<Utc>, tz: Tz) -> Occurrences<'_, Tz> {
+        let start_local = dtstart.with_timezone(&tz).naive_local();
+        Occurrences {
+            rec: self,
+            tz,
+            start_local,
+            period: 0,
+            buffer: VecDeque::new(),
+            generated: 0,
+            emitted_start: false,
+            idle_periods: 0,
+            done: false,
+        }
+    }
+
+    /// Starts of occurrences lasting `duration` that overlap the half-open range [from, to).
+    pub fn between<Tz: TimeZone>(
+        &self,
+        dtstart: DateTime<Utc>,
+        duration: Duration,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        tz: Tz,
+    ) -> Vec<DateTime<Utc>> {
+        self.occurrences(dtstart, tz)
+            .take(MAX_EXPANSION * 10)
+            .take_while(|start| *start < to)
+            .filter(|start| *start + duration > from)
+            .take(MAX_EXPANSION)
+            .collect()
+    }
+
+    /// First occurrence strictly after `after`.
+    pub fn next_after<Tz: TimeZone>(
+        &self,
+        dtstart: DateTime<Utc>,
+        after: DateTime<Utc>,
+        tz: Tz,
+    ) -> Option<DateTime<Utc>> {
+        self.occurrences(dtstart, tz)
+            .take(MAX_EXPANSION * 10)
+            .find(|start| *start > after)
+    }
+
+    /// Whether `at` is one of the (non-excluded) occurrences of the series.
+    pub fn includes<Tz: TimeZone>(&self, dtstart: DateTime<Utc>, at: DateTime<Utc>, tz: Tz) -> bool {
+        self.occurrences(dtstart, tz)
+            .take(MAX_EXPANSION * 10)
+            .take_while(|start| *start <= at)
+            .any(|start| start == at)
+    }
+
+    pub fn add_exdate(&mut self, at: DateTime<Utc>) {
+        let ex = ExDate::Utc(at);
+        if !self.exdates.contains(&ex) {
+            self.exdates.push(ex);
+        }
+    }
+
+    /// Splits a series at `at` (an occurrence start): the head keeps everything before it,
+    /// the tail is the same rule re-anchored at `at` with COUNT reduced accordingly.
+    /// Returns `None` for the head when `at` is the first occurrence, and `None` for
+    /// the tail when COUNT is already used up.
+    pub fn split_at<Tz: TimeZone>(
+        &self,
+        dtstart: DateTime<Utc>,
+        at: DateTime<Utc>,
+        tz: Tz,
+    ) -> (Option<Recurrence>, Option<Recurrence>) {
+        let mut iter = self.occurrences(dtstart, tz);
+        let mut before = 0u32;
+        while let Some(start) = iter.next_raw() {
+            if start >= at {
+                break;
+            }
+            before += 1;
+            if before as usize > MAX_EXPANSION * 10 {
+                break;
+            }
+        }
+
+        // Each part keeps only the exclusions that fall on its side of `at`.
+        let at_local = at.with_timezone(&iter.tz).naive_local();
+        let before_at = |ex: &ExDate| match ex {
+            ExDate::Utc(dt) => *dt < at,
+            ExDate::Floating(dt) => *dt < at_local,
+            ExDate::Date(d) => *d < at_local.date(),
+        };
+
+        let head = if before == 0 {
+            None
+        } else {
+            let mut head = self.clone();
+            head.rule.count = None;
+            head.rule.until = Some(Until::Utc(at - Duration::seconds(1)));
+            head.exdates.retain(before_at);
+            Some(head)
+        };
+
+        let tail = match self.rule.count {
+            Some(count) if count <= before => None,
+            _ => {
+                let mut tail = self.clone();
+                tail.rule.count = self.rule.count.map(|count| count - before);
+                tail.exdates.retain(|ex| !before_at(ex));
+                Some(tail)
+            }
+        };
+
+        (head, tail)
+    }
+
+    /// The same series with a floating UNTIL and floating EXDATEs pinned to UTC,
+    /// reading them as wall-clock times in `tz`. RFC 5545 requires this form
+    /// alongside a UTC DTSTART.
+    pub fn in_utc<Tz: TimeZone>(&self, tz: Tz) -> Recurrence {
+        let mut rec = self.clone();
+        if let Some(Until::Floating(until)) = rec.rule.until {
+            if let Some(utc) = local_to_utc(&tz, until) {
+                rec.rule.until = Some(Until::Utc(utc));
+            }
+        }
+        for ex in &mut rec.exdates {
+            if let ExDate::Floating(dt) = *ex {
+                if let Some(utc) = local_to_utc(&tz, dt) {
+                    *ex = ExDate::Utc(utc);
+                }
+            }
+        }
+        rec
+    }
+
+    /// Moves UNTIL and the excluded dates along with a series whose start was
+    /// shifted by `delta`.
+    pub fn shift(&mut self, delta: Duration) {
+        match &mut self.rule.until {
+            Some(Until::Utc(dt)) => *dt += delta,
+            Some(Until::Floating(dt)) => *dt += delta,
+            None => {}
+        }
+        for ex in &mut self.exdates {
+            match ex {
+                ExDate::Utc(dt) => *dt += delta,
+                ExDate::Floating(dt) => *dt += delta,
+                ExDate::Date(_) => {}
+            }
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RRULE:{}", self.rule)?;
+        for ex in &self.exdates {
+            match ex {
+                ExDate::Utc(dt) => write!(f, "\nEXDATE:{}", dt.format("%Y%m%dT%H%M%SZ"))?,
+                ExDate::Floating(dt) => write!(f, "\nEXDATE:{}", dt.format("%Y%m%dT%H%M%S"))?,
+                ExDate::Date(d) => write!(f, "\nEXDATE;VALUE=DATE:{}", d.format("%Y%m%d"))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let freq = match self.freq {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={freq}")?;
+
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_month.is_empty() {
+            let months: Vec<String> = self.by_month.iter().map(|m| m.to_string()).collect();
+            write!(f, ";BYMONTH={}", months.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(|d| d.to_string()).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self
+                .by_day
+                .iter()
+                .map(|d| match d.ordinal {
+                    Some(n) => format!("{n}{}", weekday_code(d.weekday)),
+                    None => weekday_code(d.weekday).to_string(),
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        match self.until {
+            Some(Until::Utc(dt)) => write!(f, ";UNTIL={}", dt.format("%Y%m%dT%H%M%SZ"))?,
+            Some(Until::Floating(dt)) => write!(f, ";UNTIL={}", dt.format("%Y%m%dT%H%M%S"))?,
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+/// Splits `NAME;PARAMS:VALUE` into its three parts (params may be empty).
+fn split_content_line(line: &str) -> (&str, &str, &str) {
+    let (head, value) = line.split_once(':').unwrap_or((line, ""));
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    (name, params, value)
+}
+
+fn parse_rrule(value: &str) -> Result<RRule, ParseError> {
+    let mut freq = None;
+    let mut rule = RRule {
+        freq: Frequency::Daily,
+        interval: 1,
+        by_day: Vec::new(),
+        by_month_day: Vec::new(),
+        by_month: Vec::new(),
+        count: None,
+        until: None,
+    };
+
+    for part in value.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        let (key, val) = part
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid RRULE part '{part}'"))?;
+
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                freq = Some(match val.to_ascii_uppercase().as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    other => return Err(format!("Unsupported FREQ '{other}'").into()),
+                })
+            }
+            "INTERVAL" => {
+                rule.interval = val
+                    .parse()
+                    .ok()
+                    .filter(|n: &u32| (1..=MAX_INTERVAL).contains(n))
+                    .ok_or_else(|| format!("Invalid INTERVAL '{val}'"))?;
+            }
+            "COUNT" => {
+                rule.count = Some(
+                    val.parse()
+                        .ok()
+                        .filter(|n: &u32| *n > 0)
+                        .ok_or_else(|| format!("Invalid COUNT '{val}'"))?,
+                );
+            }
+            "UNTIL" => rule.until = Some(parse_until(val)?),
+            "BYDAY" => {
+                for d in val.split(',') {
+                    rule.by_day.push(parse_weekday_num(d.trim())?);
+                }
+            }
+            "BYMONTHDAY" => {
+                for d in val.split(',') {
+                    let n: i32 = d
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("Invalid BYMONTHDAY '{d}'"))?;
+                    if n == 0 || !(-31..=31).contains(&n) {
+                        return Err(format!("Invalid BYMONTHDAY '{d}'").into());
+                    }
+                    rule.by_month_day.push(n);
+                }
+            }
+            "BYMONTH" => {
+                for m in val.split(',') {
+                    let n: u32 = m
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("Invalid BYMONTH '{m}'"))?;
+                    if !(1..=12).contains(&n) {
+                        return Err(format!("Invalid BYMONTH '{m}'").into());
+                    }
+                    rule.by_month.push(n);
+                }
+            }
+            "WKST" => {}
+            other => return Err(format!("Unsupported RRULE part '{other}'").into()),
+        }
+    }
+
+    if rule.count.is_some() && rule.until.is_some() {
+        return Err("RRULE cannot have both COUNT and UNTIL".into());
+    }
+
+    rule.freq = freq.ok_or_else(|| "RRULE is missing FREQ".to_string())?;
+    Ok(rule)
+}
+
+fn parse_weekday_num(s: &str) -> Result<WeekdayNum, ParseError> {
+    let upper = s.to_ascii_uppercase();
+    let split = upper.len().saturating_sub(2);
+    if upper.len() < 2 || !upper.is_char_boundary(split) {
+        return Err(format!("Invalid BYDAY '{s}'").into());
+    }
+    let (num, code) = upper.split_at(split);
+
+    let weekday = match code {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(format!("Invalid BYDAY '{s}'").into()),
+    };
+
+    let ordinal = if num.is_empty() {
+        None
+    } else {
+        let n: i32 = num
+            .trim_start_matches('+')
+            .parse()
+            .map_err(|_| format!("Invalid BYDAY '{s}'"))?;
+        if n == 0 || !(-53..=53).contains(&n) {
+            return Err(format!("Invalid BYDAY '{s}'").into());
+        }
+        Some(n)
+    };
+
+    Ok(WeekdayNum { ordinal, weekday })
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_until(val: &str) -> Result<Until, ParseError> {
+    if let Some(stripped) = val.strip_suffix('Z') {
+        let dt = NaiveDateTime::parse_from_str(stripped, "%Y%m%dT%H%M%S")
+            .map_err(|_| format!("Invalid UNTIL '{val}'"))?;
+        return Ok(Until::Utc(dt.and_utc()));
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(val, "%Y%m%dT%H%M%S") {
+        return Ok(Until::Floating(dt));
+    }
+    let date =
+        NaiveDate::parse_from_str(val, "%Y%m%d").map_err(|_| format!("Invalid UNTIL '{val}'"))?;
+    Ok(Until::Floating(date.and_hms_opt(23, 59, 59).unwrap_or_default()))
+}
+
+fn parse_exdate(val: &str, is_date: bool) -> Result<ExDate, ParseError> {
+    if is_date || (val.len() == 8 && val.chars().all(|c| c.is_ascii_digit())) {
+        let date = NaiveDate::parse_from_str(val, "%Y%m%d")
+            .map_err(|_| format!("Invalid EXDATE '{val}'"))?;
+        return Ok(ExDate::Date(date));
+    }
+    if let Some(stripped) = val.strip_suffix('Z') {
+        let dt = NaiveDateTime::parse_from_str(stripped, "%Y%m%dT%H%M%S")
+            .map_err(|_| format!("Invalid EXDATE '{val}'"))?;
+        return Ok(ExDate::Utc(dt.and_utc()));
+    }
+    NaiveDateTime::parse_from_str(val, "%Y%m%dT%H%M%S")
+        .map(ExDate::Floating)
+        .map_err(|_| ParseError(format!("Invalid EXDATE '{val}'")))
+}
+
+fn local_to_utc<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        // Wall-clock times skipped by a DST jump move forward by an hour.
+        .or_else(|| tz.from_local_datetime(&(local + Duration::hours(1))).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+pub struct Occurrences<'a, Tz: TimeZone> {
+    rec: &'a Recurrence,
+    tz: Tz,
+    start_local: NaiveDateTime,
+    period: i64,
+    buffer: VecDeque<NaiveDateTime>,
+    generated: u32,
+    emitted_start: bool,
+    idle_periods: u32,
+    done: bool,
+}
+
+impl<Tz: TimeZone> Occurrences<'_, Tz> {
+    /// Next candidate in the rule's set, honouring COUNT and UNTIL but not EXDATE.
+    fn next_raw(&mut self) -> Option<DateTime<Utc>> {
+        if self.done {
+            return None;
+        }
+
+        let rule = &self.rec.rule;
+        let next = self.next_local().and_then(|local| {
+            let utc = self.to_utc(local)?;
+            let past_until = match rule.until {
+                Some(Until::Utc(until)) => utc > until,
+                Some(Until::Floating(until)) => local > until,
+                None => false,
+            };
+            let past_count = rule.count.is_some_and(|count| self.generated >= count);
+            (!past_until && !past_count).then_some(utc)
+        });
+
+        match next {
+            Some(utc) => {
+                self.generated += 1;
+                Some(utc)
+            }
+            None => {
+                self.done = true;
+                None
+            }
+        }
+    }
+
+    fn next_local(&mut self) -> Option<NaiveDateTime> {
+        // DTSTART is always the first instance of the series.
+        if !self.emitted_start {
+            self.emitted_start = true;
+            return Some(self.start_local);
+        }
+
+        loop {
+            if let Some(next) = self.buffer.pop_front() {
+                return Some(next);
+            }
+
+            if self.idle_periods > MAX_IDLE_PERIODS {
+                return None;
+            }
+
+            let time = self.start_local.time();
+            let start = self.start_local;
+            let dates = self.period_dates(self.period)?;
+            let candidates: VecDeque<NaiveDateTime> = dates
+                .into_iter()
+                .map(|d| d.and_time(time))
+                .filter(|dt| *dt > start)
+                .collect();
+            self.period += 1;
+
+            if candidates.is_empty() {
+                self.idle_periods += 1;
+            } else {
+                self.idle_periods = 0;
+                self.buffer = candidates;
+            }
+        }
+    }
+
+    fn to_utc(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        local_to_utc(&self.tz, local)
+    }
+
+    /// Candidate dates of the `k`th period after the start, or `None` once that
+    /// period lies outside the dates chrono can represent.
+    fn period_dates(&self, k: i64) -> Option<Vec<NaiveDate>> {
+        let rule = &self.rec.rule;
+        let step = k.checked_mul(rule.interval as i64)?;
+        let start = self.start_local.date();
+        let has_by_day = !rule.by_day.is_empty();
+        let has_by_month_day = !rule.by_month_day.is_empty();
+
+        let days: Vec<NaiveDate> = match rule.freq {
+            Frequency::Daily => vec![start.checked_add_signed(Duration::try_days(step)?)?],
+            Frequency::Weekly => {
+                let week_start = start
+                    .checked_sub_days(Days::new(start.weekday().num_days_from_monday() as u64))?
+                    .checked_add_signed(Duration::try_weeks(step)?)?;
+                (0..7)
+                    .filter_map(|i| week_start.checked_add_days(Days::new(i)))
+                    .filter(|d| has_by_day || has_by_month_day || d.weekday() == start.weekday())
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let (y, m) = add_months(start.year(), start.month(), step)?;
+                month_days(y, m)
+                    .filter(|d| has_by_day || has_by_month_day || d.day() == start.day())
+                    .collect()
+            }
+            Frequency::Yearly => {
+                let y = start.year().checked_add(i32::try_from(step).ok()?)?;
+                if rule.by_month.is_empty() && !has_by_day && !has_by_month_day {
+                    NaiveDate::from_ymd_opt(y, start.month(), start.day())
+                        .into_iter()
+                        .collect()
+                } else {
+                    (1..=12)
+                        .filter(|m| rule.by_month.is_empty() || rule.by_month.contains(m))
+                        .flat_map(|m| month_days(y, m))
+                        .filter(|d| has_by_day || has_by_month_day || d.day() == start.day())
+                        .collect()
+                }
+            }
+        };
+
+        Some(
+            days.into_iter()
+                .filter(|d| rule.by_month.is_empty() || rule.by_month.contains(&d.month()))
+                .filter(|d| !has_by_month_day || rule.by_month_day.iter().any(|n| month_day_matches(*d, *n)))
+                .filter(|d| !has_by_day || rule.by_day.iter().any(|wd| self.weekday_matches(*d, wd)))
+                .collect(),
+        )
+    }
+
+    fn weekday_matches(&self, d: NaiveDate, wd: &WeekdayNum) -> bool {
+        if d.weekday() != wd.weekday {
+            return false;
+        }
+
+        let rule = &self.rec.rule;
+        let ordinal = match (wd.ordinal, rule.freq) {
+            (Some(n), Frequency::Monthly | Frequency::Yearly) => n,
+            _ => return true,
+        };
+
+        // Ordinals count within the month, or within the year for YEARLY rules without BYMONTH.
+        let (scope_start, scope_len) = if rule.freq == Frequency::Yearly && rule.by_month.is_empty() {
+            let first = NaiveDate::from_ymd_opt(d.year(), 1, 1).unwrap_or(d);
+            let next = NaiveDate::from_ymd_opt(d.year() + 1, 1, 1).unwrap_or(d);
+            (first, (next - first).num_days())
+        } else {
+            let first = d.with_day(1).unwrap_or(d);
+            (first, days_in_month(d.year(), d.month()) as i64)
+        };
+
+        let offset = (d - scope_start).num_days();
+        let forward = offset / 7 + 1;
+        let backward = -((scope_len - 1 - offset) / 7 + 1);
+
+        ordinal as i64 == forward || ordinal as i64 == backward
+    }
+}
+
+impl<Tz: TimeZone> Iterator for Occurrences<'_, Tz> {
+    type Item = DateTime<Utc>;
+
+    fn next(&mut self) -> Option<DateTime<Utc>> {
+        loop {
+            let utc = self.next_raw()?;
+            let local = utc.with_timezone(&self.tz).naive_local();
+
+            let excluded = self.rec.exdates.iter().any(|ex| match ex {
+                ExDate::Utc(x) => *x == utc,
+                ExDate::Floating(x) => *x == local,
+                ExDate::Date(d) => *d == local.date(),
+            });
+
+            if !excluded {
+                return Some(utc);
+            }
+        }
+    }
+}
+
+fn add_months(year: i32, month: u32, delta: i64) -> Option<(i32, u32)> {
+    let total = (year as i64 * 12 + (month as i64 - 1)).checked_add(delta)?;
+    Some((i32::try_from(total.div_euclid(12)).ok()?, (total.rem_euclid(12) + 1) as u32))
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let next = add_months(year, month, 1).and_then(|(ny, nm)| NaiveDate::from_ymd_opt(ny, nm, 1));
+    match (NaiveDate::from_ymd_opt(year, month, 1), next) {
+        (Some(a), Some(b)) => (b - a).num_days() as u32,
+        _ => 0,
+    }
+}
+
+fn month_days(year: i32, month: u32) -> impl Iterator<Item = NaiveDate> {
+    (1..=days_in_month(year, month)).filter_map(move |d| NaiveDate::from_ymd_opt(year, month, d))
+}
+
+fn month_day_matches(d: NaiveDate, n: i32) -> bool {
+    if n > 0 {
+        d.day() as i32 == n
+    } else {
+        let len = days_in_month(d.year(), d.month()) as i32;
+        d.day() as i32 == len + 1 + n
+    }
+}
@@ -0,0 +1,193 @@
+//! The daily planning view: open (to do or in progress) tasks due or scheduled in a
+//! range of days, calendar occurrences in it, and open tasks already overdue before
+//! it. Days are bucketed in a given time zone (or the system one), so "today" starts
+//! at local midnight, and repeating events keep their wall-clock time in that zone.
+
+use crate::datetime;
+use crate::error::AuralisError;
+use crate::store::calendar::{self, CalendarOccurrence};
+use crate::store::tasks::{self, TaskItem};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use rusqlite::params;
+use rusqlite::Connection;
+use serde::Serialize;
+
+// Longest range one call may ask for.
+const MAX_DAYS: i64 = 92;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AgendaReason {
+    Due,
+    Scheduled,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AgendaItem {
+    /// A task on the day it is due or scheduled. A task due and scheduled on the
+    /// same day is listed once, as due.
+    Task {
+        at: String,
+        reason: AgendaReason,
+        task: TaskItem,
+    },
+    /// A calendar occurrence; one spanning several days is listed on each, with
+    /// `continued` set after the first and `at` clamped to the start of the day.
+    Event {
+        at: String,
+        continued: bool,
+        occurrence: CalendarOccurrence,
+    },
+}
+
+impl AgendaItem {
+    pub fn at(&self) -> &str {
+        match self {
+            AgendaItem::Task { at, .. } | AgendaItem::Event { at, .. } => at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AgendaDay {
+    pub date: String,     // YYYY-MM-DD in the agenda's time zone
+    pub start_at: String, // UTC bounds of that day, half-open
+    pub end_at: String,
+    pub items: Vec<AgendaItem>, // by time, events before tasks at the same time
+}
+
+#[derive(Serialize)]
+pub struct Agenda {
+    pub time_zone: Option<String>, // None for the system time zone
+    pub overdue: Vec<TaskItem>,    // open and due before the first day, oldest first
+    pub days: Vec<AgendaDay>,
+}
+
+pub(crate) fn parse_date(text: &str) -> Result<NaiveDate, AuralisError> {
+    NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d")
+        .map_err(|_| AuralisError::validation(format!("Invalid date '{text}' (expected YYYY-MM-DD)")))
+}
+
+/// The agenda for the days `start` through `end` (inclusive; just `start` when
+/// omitted). `time_zone` is an IANA name such as `Europe/Berlin`.
+pub fn agenda(
+    conn: &Connection,
+    start: &str,
+    end: Option<&str>,
+    time_zone: Option<&str>,
+) -> Result<Agenda, AuralisError> {
+    let first = parse_date(start)?;
+    let last = end.map(parse_date).transpose()?.unwrap_or(first);
+    if last < first {
+        return Err(AuralisError::validation("End date cannot be before the start date"));
+    }
+    if (last - first).num_days() >= MAX_DAYS {
+        return Err(AuralisError::validation(format!("An agenda can cover at most {MAX_DAYS} days")));
+    }
+
+    match time_zone.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(name) => {
+            let tz: chrono_tz::Tz = name
+                .parse()
+                .map_err(|_| AuralisError::validation(format!("Unknown time zone '{name}'")))?;
+            build(conn, first, last, tz, Some(name.to_string()))
+        }
+        None => build(conn, first, last, Local, None),
+    }
+}
+
+// When `date` begins in `tz`. If midnight falls in a DST gap, the day starts at the
+// first local time after it that exists.
+pub(crate) fn day_start<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    (0..=4)
+        .map(|half_hours| midnight + Duration::minutes(30 * half_hours))
+        .find_map(|local| tz.from_local_datetime(&local).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+fn build<Tz: TimeZone>(
+    conn: &Connection,
+    first: NaiveDate,
+    last: NaiveDate,
+    tz: Tz,
+    time_zone: Option<String>,
+) -> Result<Agenda, AuralisError> {
+    let mut days: Vec<AgendaDay> = Vec::new();
+    for date in first.iter_days().take_while(|d| *d <= last) {
+        let next = date.succ_opt().unwrap_or(date);
+        days.push(AgendaDay {
+            date: date.format("%Y-%m-%d").to_string(),
+            start_at: datetime::format_utc(day_start(date, &tz)),
+            end_at: datetime::format_utc(day_start(next, &tz)),
+            items: Vec::new(),
+        });
+    }
+    let range_start = days[0].start_at.clone();
+    let range_end = days[days.len() - 1].end_at.clone();
+
+    // Stored timestamps share one format, so they compare as text.
+    let day_of = |at: &str| days.iter().position(|d| d.start_at.as_str() <= at && at < d.end_at.as_str());
+
+    let open = tasks::select(
+        conn,
+        "status IN ('todo', 'doing')
+           AND ((due_at >= ?1 AND due_at < ?2) OR (scheduled_at >= ?1 AND scheduled_at < ?2))
+         ORDER BY created_at",
+        params![range_start, range_end],
+    )?;
+    let mut placed: Vec<(usize, AgendaItem)> = Vec::new();
+    for task in open {
+        let due = task.due_at.clone().and_then(|at| Some((day_of(&at)?, at)));
+        let scheduled = task.scheduled_at.clone().and_then(|at| Some((day_of(&at)?, at)));
+
+        if let Some((day, at)) = scheduled {
+            if due.as_ref().map(|(d, _)| *d) != Some(day) {
+                let reason = AgendaReason::Scheduled;
+                placed.push((day, AgendaItem::Task { at, reason, task: task.clone() }));
+            }
+        }
+        if let Some((day, at)) = due {
+            placed.push((day, AgendaItem::Task { at, reason: AgendaReason::Due, task }));
+        }
+    }
+
+    for occurrence in calendar::list_range_in_zone(conn, &range_start, &range_end, tz)? {
+        for (i, day) in days.iter().enumerate() {
+            if occurrence.occurrence_start >= day.end_at || occurrence.occurrence_end <= day.start_at {
+                continue;
+            }
+            let continued = occurrence.occurrence_start < day.start_at;
+            let at = match continued {
+                true => day.start_at.clone(),
+                false => occurrence.occurrence_start.clone(),
+            };
+            let occurrence = occurrence.clone();
+            placed.push((i, AgendaItem::Event { at, continued, occurrence }));
+        }
+    }
+
+    for (day, item) in placed {
+        days[day].items.push(item);
+    }
+    for day in &mut days {
+        day.items.sort_by(|a, b| {
+            let rank = |item: &AgendaItem| matches!(item, AgendaItem::Task { .. });
+            a.at().cmp(b.at()).then(rank(a).cmp(&rank(b)))
+        });
+    }
+
+    let overdue = tasks::select(
+        conn,
+        "status IN ('todo', 'doing') AND due_at < ?1 ORDER BY due_at, created_at",
+        params![range_start],
+    )?;
+
+    Ok(Agenda {
+        time_zone,
+        overdue,
+        days,
+    })
+}
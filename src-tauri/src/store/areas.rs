@@ -0,0 +1,72 @@
+use crate::error::AuralisError;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct AreaItem {
+    pub id: String,
+    pub name: String,
+    pub active: i64, // sqlite integer 0/1
+    pub created_at: String,
+}
+
+pub fn add(conn: &Connection, name: &str) -> Result<String, AuralisError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AuralisError::validation("Name cannot be empty"));
+    }
+
+    let id = format!("area_{}", uuid::Uuid::new_v4());
+
+    conn.execute(
+        "INSERT INTO areas (id, name, active) VALUES (?1, ?2, 1)",
+        params![id, name],
+    )?;
+
+    Ok(id)
+}
+
+pub fn list(conn: &Connection, only_active: bool) -> Result<Vec<AreaItem>, AuralisError> {
+    let mut items: Vec<AreaItem> = Vec::new();
+
+    let sql = if only_active {
+        "SELECT id, name, active, created_at
+         FROM areas
+         WHERE active = 1
+         ORDER BY name ASC"
+    } else {
+        "SELECT id, name, active, created_at
+         FROM areas
+         ORDER BY name ASC"
+    };
+
+    let mut stmt = conn.prepare(sql)?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(AreaItem {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            active: row.get(2)?,
+            created_at: row.get(3)?,
+        })
+    })?;
+
+    for r in rows {
+        items.push(r?);
+    }
+
+    Ok(items)
+}
+
+pub fn set_active(conn: &Connection, id: &str, active: bool) -> Result<(), AuralisError> {
+    let updated = conn.execute(
+        "UPDATE areas SET active = ?1 WHERE id = ?2",
+        params![if active { 1 } else { 0 }, id],
+    )?;
+
+    if updated == 0 {
+        return Err(AuralisError::not_found("Area"));
+    }
+
+    Ok(())
+}
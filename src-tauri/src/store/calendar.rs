@@ -0,0 +1,467 @@
+use crate::datetime;
+use crate::error::AuralisError;
+use crate::recurrence::Recurrence;
+use chrono::{DateTime, Local, TimeDelta, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Clone)]
+pub struct CalendarEventItem {
+    pub id: String,
+    pub title: String,
+    pub start_at: String,
+    pub end_at: String,
+    #[serde(rename = "type")]
+    pub event_type: String, // event | block
+    pub task_id: Option<String>,
+    pub area_id: Option<String>,
+    pub location: Option<String>,
+    pub recurrence: Option<String>,
+    pub created_at: String,
+}
+
+/// One concrete instance of an event. For recurring series, `event` carries the
+/// series itself and `occurrence_start`/`occurrence_end` the expanded instance.
+#[derive(Serialize, Clone)]
+pub struct CalendarOccurrence {
+    #[serde(flatten)]
+    pub event: CalendarEventItem,
+    pub occurrence_start: String,
+    pub occurrence_end: String,
+    pub recurring: bool,
+}
+
+/// Field values for a new event, or for the occurrence (or part of a series) being edited.
+#[derive(Deserialize, Default)]
+pub struct CalendarEventInput {
+    pub title: String,
+    pub start_at: String,
+    pub end_at: String,
+    pub event_type: Option<String>,
+    pub task_id: Option<String>,
+    pub area_id: Option<String>,
+    pub location: Option<String>,
+}
+
+fn event_from_row(row: &Row) -> rusqlite::Result<CalendarEventItem> {
+    Ok(CalendarEventItem {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        start_at: row.get(2)?,
+        end_at: row.get(3)?,
+        event_type: row.get(4)?,
+        task_id: row.get(5)?,
+        area_id: row.get(6)?,
+        location: row.get(7)?,
+        recurrence: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+// Normalises both ends to UTC storage format and checks the span up front,
+// so callers get a readable error instead of the table's raw CHECK failure.
+fn validate_span(start_at: &str, end_at: &str) -> Result<(String, String), AuralisError> {
+    let start = datetime::parse(start_at)?;
+    let end = datetime::parse(end_at)?;
+
+    if end <= start {
+        return Err(AuralisError::validation("End time must be after start time"));
+    }
+
+    Ok((datetime::format_utc(start), datetime::format_utc(end)))
+}
+
+fn validate_type(event_type: Option<String>) -> Result<String, AuralisError> {
+    let t = event_type.unwrap_or_else(|| "event".to_string());
+    if t != "event" && t != "block" {
+        return Err(AuralisError::validation("Invalid type (must be 'event' or 'block')"));
+    }
+    Ok(t)
+}
+
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+// Parses and re-serialises the rule so only well-formed recurrences are stored.
+fn validate_recurrence(recurrence: Option<String>) -> Result<Option<String>, AuralisError> {
+    match clean(recurrence) {
+        Some(r) => Ok(Some(Recurrence::parse(&r)?.to_string())),
+        None => Ok(None),
+    }
+}
+
+enum Scope {
+    This,
+    Following,
+    All,
+}
+
+fn parse_scope(scope: &str) -> Result<Scope, AuralisError> {
+    match scope {
+        "this" => Ok(Scope::This),
+        "following" => Ok(Scope::Following),
+        "all" => Ok(Scope::All),
+        _ => Err(AuralisError::validation("Invalid scope (must be 'this', 'following' or 'all')")),
+    }
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<CalendarEventItem, AuralisError> {
+    conn.query_row(
+        "SELECT id, title, start_at, end_at, type, task_id, area_id, location,
+                recurrence, created_at
+         FROM calendar_events
+         WHERE id = ?1",
+        params![id],
+        event_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AuralisError::not_found("Event"))
+}
+
+// Loads a recurring event and checks that `occurrence_start` is one of its instances.
+fn load_series(
+    conn: &Connection,
+    id: &str,
+    occurrence_start: &str,
+) -> Result<(CalendarEventItem, Recurrence, DateTime<Utc>, DateTime<Utc>), AuralisError> {
+    let event = get(conn, id)?;
+
+    let rule = match &event.recurrence {
+        Some(r) => Recurrence::parse(r)?,
+        None => return Err(AuralisError::validation("Event is not recurring")),
+    };
+
+    let series_start = datetime::parse(&event.start_at)?;
+    let occurrence = datetime::parse(occurrence_start)?;
+
+    if !rule.includes(series_start, occurrence, Local) {
+        return Err(AuralisError::not_found("Occurrence"));
+    }
+
+    Ok((event, rule, series_start, occurrence))
+}
+
+fn set_recurrence(conn: &Connection, id: &str, rule: &Recurrence) -> Result<(), AuralisError> {
+    conn.execute(
+        "UPDATE calendar_events SET recurrence = ?1 WHERE id = ?2",
+        params![rule.to_string(), id],
+    )?;
+    Ok(())
+}
+
+fn insert_from_input(
+    conn: &Connection,
+    input: CalendarEventInput,
+    start_at: &str,
+    end_at: &str,
+    recurrence: Option<String>,
+) -> Result<String, AuralisError> {
+    let title = input.title.trim().to_string();
+    if title.is_empty() {
+        return Err(AuralisError::validation("Title cannot be empty"));
+    }
+    let event_type = validate_type(input.event_type)?;
+
+    let id = format!("event_{}", uuid::Uuid::new_v4());
+
+    conn.execute(
+        "INSERT INTO calendar_events
+            (id, title, start_at, end_at, type, task_id, area_id, location, recurrence)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            id,
+            title,
+            start_at,
+            end_at,
+            event_type,
+            input.task_id,
+            input.area_id,
+            clean(input.location),
+            recurrence
+        ],
+    )?;
+
+    Ok(id)
+}
+
+pub fn add(
+    conn: &Connection,
+    input: CalendarEventInput,
+    recurrence: Option<String>,
+) -> Result<String, AuralisError> {
+    let (start_at, end_at) = validate_span(&input.start_at, &input.end_at)?;
+    let recurrence = validate_recurrence(recurrence)?;
+    insert_from_input(conn, input, &start_at, &end_at, recurrence)
+}
+
+pub fn update(
+    conn: &Connection,
+    id: &str,
+    input: CalendarEventInput,
+    recurrence: Option<String>,
+) -> Result<(), AuralisError> {
+    let title = input.title.trim().to_string();
+    if title.is_empty() {
+        return Err(AuralisError::validation("Title cannot be empty"));
+    }
+
+    let (start_at, end_at) = validate_span(&input.start_at, &input.end_at)?;
+    let event_type = validate_type(input.event_type)?;
+    let recurrence = validate_recurrence(recurrence)?;
+
+    let updated = conn.execute(
+        "UPDATE calendar_events
+         SET title = ?1, start_at = ?2, end_at = ?3, type = ?4,
+             task_id = ?5, area_id = ?6, location = ?7, recurrence = ?8
+         WHERE id = ?9",
+        params![
+            title,
+            start_at,
+            end_at,
+            event_type,
+            input.task_id,
+            input.area_id,
+            clean(input.location),
+            recurrence,
+            id
+        ],
+    )?;
+
+    if updated == 0 {
+        return Err(AuralisError::not_found("Event"));
+    }
+
+    Ok(())
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<(), AuralisError> {
+    let deleted = conn.execute("DELETE FROM calendar_events WHERE id = ?1", params![id])?;
+
+    if deleted == 0 {
+        return Err(AuralisError::not_found("Event"));
+    }
+
+    Ok(())
+}
+
+/// Occurrences overlapping the half-open range [start, end), with recurring
+/// series expanded in local time.
+pub fn list_range(conn: &Connection, start: &str, end: &str) -> Result<Vec<CalendarOccurrence>, AuralisError> {
+    list_range_in_zone(conn, start, end, Local)
+}
+
+/// [`list_range`] with recurring series expanded in the wall-clock time of `tz`.
+pub(crate) fn list_range_in_zone<Tz: TimeZone>(
+    conn: &Connection,
+    start: &str,
+    end: &str,
+    tz: Tz,
+) -> Result<Vec<CalendarOccurrence>, AuralisError> {
+    let range_start = datetime::parse(start)?;
+    let range_end = datetime::parse(end)?;
+    if range_end <= range_start {
+        return Err(AuralisError::validation("End time must be after start time"));
+    }
+
+    // Single events must overlap the range; a series only has to start before it ends.
+    let mut stmt = conn.prepare(
+        "SELECT id, title, start_at, end_at, type, task_id, area_id, location,
+                recurrence, created_at
+         FROM calendar_events
+         WHERE start_at < ?2
+           AND (end_at > ?1 OR recurrence IS NOT NULL)
+         ORDER BY start_at ASC",
+    )?;
+
+    let rows = stmt.query_map(
+        params![datetime::format_utc(range_start), datetime::format_utc(range_end)],
+        event_from_row,
+    )?;
+
+    let mut items: Vec<CalendarOccurrence> = Vec::new();
+    for r in rows {
+        let event = r?;
+
+        let rule = match &event.recurrence {
+            Some(text) => Recurrence::parse(text)?,
+            None => {
+                items.push(CalendarOccurrence {
+                    occurrence_start: event.start_at.clone(),
+                    occurrence_end: event.end_at.clone(),
+                    recurring: false,
+                    event,
+                });
+                continue;
+            }
+        };
+
+        let series_start = datetime::parse(&event.start_at)?;
+        let duration = datetime::parse(&event.end_at)? - series_start;
+
+        for occ in rule.between(series_start, duration, range_start, range_end, tz.clone()) {
+            items.push(CalendarOccurrence {
+                event: event.clone(),
+                occurrence_start: datetime::format_utc(occ),
+                occurrence_end: datetime::format_utc(occ + duration),
+                recurring: true,
+            });
+        }
+    }
+
+    items.sort_by(|a, b| a.occurrence_start.cmp(&b.occurrence_start));
+
+    Ok(items)
+}
+
+/// Edits one instance of a recurring event. `scope` is "this" (detach just this
+/// occurrence), "following" (split the series here) or "all" (shift the whole series
+/// by the same amount the occurrence moved).
+pub fn update_occurrence(
+    conn: &mut Connection,
+    id: &str,
+    occurrence_start: &str,
+    scope: &str,
+    changes: CalendarEventInput,
+) -> Result<String, AuralisError> {
+    let scope = parse_scope(scope)?;
+    let (new_start, new_end) = validate_span(&changes.start_at, &changes.end_at)?;
+
+    let (event, mut rule, series_start, occurrence) = load_series(conn, id, occurrence_start)?;
+
+    let tx = conn.transaction()?;
+
+    let result_id = match scope {
+        Scope::This => {
+            rule.add_exdate(occurrence);
+            set_recurrence(&tx, id, &rule)?;
+            insert_from_input(&tx, changes, &new_start, &new_end, None)?
+        }
+        Scope::Following if occurrence > series_start => {
+            let (head, tail) = rule.split_at(series_start, occurrence, Local);
+            if let Some(head) = head {
+                set_recurrence(&tx, id, &head)?;
+            }
+            // The new series starts where the occurrence was moved to.
+            let delta = datetime::parse(&new_start)? - occurrence;
+            let tail = tail.map(|mut tail| {
+                tail.shift(delta);
+                tail.to_string()
+            });
+            insert_from_input(&tx, changes, &new_start, &new_end, tail)?
+        }
+        Scope::Following | Scope::All => {
+            let title = changes.title.trim().to_string();
+            if title.is_empty() {
+                return Err(AuralisError::validation("Title cannot be empty"));
+            }
+            let event_type = validate_type(changes.event_type)?;
+
+            let moved_start = datetime::parse(&new_start)?;
+            let duration = datetime::parse(&new_end)? - moved_start;
+            let delta = moved_start - occurrence;
+            let start = series_start + delta;
+            rule.shift(delta);
+
+            tx.execute(
+                "UPDATE calendar_events
+                 SET title = ?1, start_at = ?2, end_at = ?3, type = ?4,
+                     task_id = ?5, area_id = ?6, location = ?7, recurrence = ?8
+                 WHERE id = ?9",
+                params![
+                    title,
+                    datetime::format_utc(start),
+                    datetime::format_utc(start + duration),
+                    event_type,
+                    changes.task_id,
+                    changes.area_id,
+                    clean(changes.location),
+                    rule.to_string(),
+                    event.id
+                ],
+            )?;
+
+            event.id
+        }
+    };
+
+    tx.commit()?;
+
+    Ok(result_id)
+}
+
+/// Deletes one instance, this and all following instances, or the whole series.
+pub fn delete_occurrence(
+    conn: &Connection,
+    id: &str,
+    occurrence_start: &str,
+    scope: &str,
+) -> Result<(), AuralisError> {
+    let scope = parse_scope(scope)?;
+
+    let (_, mut rule, series_start, occurrence) = load_series(conn, id, occurrence_start)?;
+
+    let head = match scope {
+        Scope::This => {
+            rule.add_exdate(occurrence);
+            Some(rule)
+        }
+        Scope::Following => rule.split_at(series_start, occurrence, Local).0,
+        Scope::All => None,
+    };
+
+    match head {
+        Some(rule) => set_recurrence(conn, id, &rule)?,
+        None => {
+            conn.execute("DELETE FROM calendar_events WHERE id = ?1", params![id])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates a time block for a task. Without `end_at`, the block lasts for the
+/// task's estimate (or 30 minutes when it has none).
+pub fn block_from_task(
+    conn: &Connection,
+    task_id: &str,
+    start_at: &str,
+    end_at: Option<&str>,
+) -> Result<String, AuralisError> {
+    let task: Option<(String, String, Option<i64>)> = conn
+        .query_row(
+            "SELECT title, area_id, estimate_minutes FROM tasks WHERE id = ?1",
+            params![task_id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .optional()?;
+
+    let (title, area_id, estimate_minutes) = match task {
+        Some(v) => v,
+        None => return Err(AuralisError::not_found("Task")),
+    };
+
+    let (start_at, end_at) = match end_at {
+        Some(end) => validate_span(start_at, end)?,
+        None => {
+            let start = datetime::parse(start_at)?;
+            let minutes = estimate_minutes.filter(|m| *m > 0).unwrap_or(30);
+            let end = TimeDelta::try_minutes(minutes)
+                .and_then(|d| start.checked_add_signed(d))
+                .ok_or_else(|| AuralisError::validation("The task's estimate is too long to block out"))?;
+            (datetime::format_utc(start), datetime::format_utc(end))
+        }
+    };
+
+    let id = format!("event_{}", uuid::Uuid::new_v4());
+
+    conn.execute(
+        "INSERT INTO calendar_events (id, title, start_at, end_at, type, task_id, area_id)
+         VALUES (?1, ?2, ?3, ?4, 'block', ?5, ?6)",
+        params![id, title, start_at, end_at, task_id, area_id],
+    )?;
+
+    Ok(id)
+}
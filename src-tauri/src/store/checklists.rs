@@ -0,0 +1,152 @@
+//! Checklist items: small steps inside a single task, ticked off in place.
+
+use crate::error::AuralisError;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+
+#[derive(Serialize, Debug)]
+pub struct ChecklistItem {
+    pub id: String,
+    pub task_id: String,
+    pub text: String,
+    pub done: bool,
+    pub position: i64,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+fn item_from_row(row: &Row) -> rusqlite::Result<ChecklistItem> {
+    Ok(ChecklistItem {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        text: row.get(2)?,
+        done: row.get(3)?,
+        position: row.get(4)?,
+        created_at: row.get(5)?,
+        completed_at: row.get(6)?,
+    })
+}
+
+fn clean_text(text: &str) -> Result<String, AuralisError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AuralisError::validation("Checklist item cannot be empty"));
+    }
+    Ok(text.to_string())
+}
+
+fn ensure_task(conn: &Connection, task_id: &str) -> Result<(), AuralisError> {
+    conn.query_row("SELECT 1 FROM tasks WHERE id = ?1", params![task_id], |_| Ok(()))
+        .optional()?
+        .ok_or_else(|| AuralisError::not_found("Task"))
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<ChecklistItem, AuralisError> {
+    conn.query_row(
+        "SELECT id, task_id, text, done, position, created_at, completed_at
+         FROM checklist_items
+         WHERE id = ?1",
+        params![id],
+        item_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AuralisError::not_found("Checklist item"))
+}
+
+/// A task's checklist, in order.
+pub fn list(conn: &Connection, task_id: &str) -> Result<Vec<ChecklistItem>, AuralisError> {
+    ensure_task(conn, task_id)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, task_id, text, done, position, created_at, completed_at
+         FROM checklist_items
+         WHERE task_id = ?1
+         ORDER BY position, created_at",
+    )?;
+    let items = stmt.query_map(params![task_id], item_from_row)?;
+    Ok(items.collect::<Result<_, _>>()?)
+}
+
+/// Appends an item to a task's checklist.
+pub fn add(conn: &Connection, task_id: &str, text: &str) -> Result<ChecklistItem, AuralisError> {
+    let text = clean_text(text)?;
+    ensure_task(conn, task_id)?;
+
+    let id = format!("checklist_{}", uuid::Uuid::new_v4());
+    conn.execute(
+        "INSERT INTO checklist_items (id, task_id, text, position)
+         VALUES (?1, ?2, ?3,
+                 (SELECT COALESCE(MAX(position) + 1, 0) FROM checklist_items WHERE task_id = ?2))",
+        params![id, task_id, text],
+    )?;
+    get(conn, &id)
+}
+
+/// Rewords and/or ticks an item; omitted fields are left alone.
+pub fn update(conn: &Connection, id: &str, text: Option<&str>, done: Option<bool>) -> Result<ChecklistItem, AuralisError> {
+    let item = get(conn, id)?;
+
+    if let Some(text) = text {
+        let text = clean_text(text)?;
+        conn.execute("UPDATE checklist_items SET text = ?1 WHERE id = ?2", params![text, id])?;
+    }
+    if let Some(done) = done.filter(|d| *d != item.done) {
+        conn.execute(
+            "UPDATE checklist_items
+             SET done = ?1,
+                 completed_at = CASE WHEN ?1 THEN (strftime('%Y-%m-%dT%H:%M:%fZ','now')) END
+             WHERE id = ?2",
+            params![done, id],
+        )?;
+    }
+
+    get(conn, id)
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<(), AuralisError> {
+    let deleted = conn.execute("DELETE FROM checklist_items WHERE id = ?1", params![id])?;
+    if deleted == 0 {
+        return Err(AuralisError::not_found("Checklist item"));
+    }
+    Ok(())
+}
+
+/// Puts a task's items in the given order; items not named keep their order after them.
+pub fn reorder(conn: &mut Connection, task_id: &str, ids: &[String]) -> Result<Vec<ChecklistItem>, AuralisError> {
+    let tx = conn.transaction()?;
+    ensure_task(&tx, task_id)?;
+    for id in ids {
+        if get(&tx, id)?.task_id != task_id {
+            return Err(AuralisError::validation("Checklist item belongs to another task"));
+        }
+    }
+
+    tx.execute(
+        "UPDATE checklist_items SET position = position + ?1 WHERE task_id = ?2",
+        params![ids.len() as i64, task_id],
+    )?;
+    for (position, id) in ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE checklist_items SET position = ?1 WHERE id = ?2",
+            params![position as i64, id],
+        )?;
+    }
+    tx.commit()?;
+
+    list(conn, task_id)
+}
+
+/// Gives task `to` a fresh, unticked copy of task `from`'s checklist.
+pub(crate) fn copy(conn: &Connection, from: &str, to: &str) -> Result<(), AuralisError> {
+    let mut stmt = conn.prepare("SELECT text, position FROM checklist_items WHERE task_id = ?1")?;
+    let items: Vec<(String, i64)> = stmt
+        .query_map(params![from], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    for (text, position) in items {
+        conn.execute(
+            "INSERT INTO checklist_items (id, task_id, text, position) VALUES (?1, ?2, ?3, ?4)",
+            params![format!("checklist_{}", uuid::Uuid::new_v4()), to, text, position],
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! Task dependencies: "this task can't start until that one is done". A task is
+//! blocked while any task it depends on is unfinished. Blocked tasks do not count
+//! as a project's next action; the schema triggers keep that rule.
+
+use crate::error::AuralisError;
+use crate::store::subtasks;
+use crate::store::tasks::{self, TaskItem};
+use rusqlite::{params, params_from_iter, Connection};
+use serde::Serialize;
+
+/// SQL for "the task in the current `tasks` row is blocked".
+pub(crate) const BLOCKED_SQL: &str = "EXISTS (SELECT 1
+                FROM task_dependencies dep
+                JOIN tasks blocker ON blocker.id = dep.depends_on_id
+                WHERE dep.task_id = tasks.id AND blocker.status != 'done')";
+
+#[derive(Serialize, Debug)]
+pub struct Dependencies {
+    /// What the task waits on.
+    pub depends_on: Vec<TaskItem>,
+    /// What waits on the task.
+    pub dependents: Vec<TaskItem>,
+}
+
+// Whether `from` depends on `to`, directly or through other tasks.
+fn reaches(conn: &Connection, from: &str, to: &str) -> Result<bool, AuralisError> {
+    conn.query_row(
+        "WITH RECURSIVE up(id) AS (
+             SELECT depends_on_id FROM task_dependencies WHERE task_id = ?1
+             UNION
+             SELECT d.depends_on_id FROM task_dependencies d JOIN up ON d.task_id = up.id
+         )
+         SELECT EXISTS(SELECT 1 FROM up WHERE id = ?2)",
+        params![from, to],
+        |r| r.get(0),
+    )
+    .map_err(AuralisError::from)
+}
+
+/// Makes `task_id` wait for `depends_on_id`. Adding a dependency twice is a no-op.
+pub fn add(conn: &Connection, task_id: &str, depends_on_id: &str) -> Result<Dependencies, AuralisError> {
+    tasks::get(conn, task_id)?;
+    tasks::get(conn, depends_on_id)?;
+
+    if task_id == depends_on_id {
+        return Err(AuralisError::validation("A task cannot depend on itself"));
+    }
+    if reaches(conn, depends_on_id, task_id)? {
+        return Err(AuralisError::validation(
+            "That would make the tasks wait on each other in a circle",
+        ));
+    }
+    // A parent only closes after its subtasks, so a subtask waiting on it never could.
+    if subtasks::ancestors(conn, task_id)?.iter().any(|a| a == depends_on_id) {
+        return Err(AuralisError::validation("A subtask cannot depend on a task it belongs to"));
+    }
+
+    conn.execute(
+        "INSERT INTO task_dependencies (id, task_id, depends_on_id) VALUES (?1, ?2, ?3)
+         ON CONFLICT (task_id, depends_on_id) DO NOTHING",
+        params![format!("dependency_{}", uuid::Uuid::new_v4()), task_id, depends_on_id],
+    )?;
+
+    list(conn, task_id)
+}
+
+pub fn remove(conn: &Connection, task_id: &str, depends_on_id: &str) -> Result<Dependencies, AuralisError> {
+    let removed = conn.execute(
+        "DELETE FROM task_dependencies WHERE task_id = ?1 AND depends_on_id = ?2",
+        params![task_id, depends_on_id],
+    )?;
+    if removed == 0 {
+        return Err(AuralisError::not_found("Dependency"));
+    }
+    list(conn, task_id)
+}
+
+/// Both directions of a task's dependencies, oldest first.
+pub fn list(conn: &Connection, task_id: &str) -> Result<Dependencies, AuralisError> {
+    tasks::get(conn, task_id)?;
+    Ok(Dependencies {
+        depends_on: tasks::select(
+            conn,
+            "id IN (SELECT depends_on_id FROM task_dependencies WHERE task_id = ?1)
+             ORDER BY created_at, rowid",
+            params![task_id],
+        )?,
+        dependents: tasks::select(
+            conn,
+            "id IN (SELECT task_id FROM task_dependencies WHERE depends_on_id = ?1)
+             ORDER BY created_at, rowid",
+            params![task_id],
+        )?,
+    })
+}
+
+/// Unfinished tasks waiting on any of `ids` that are blocked right now. Taken
+/// before a change, it feeds [`unblocked`] afterwards.
+pub(crate) fn blocked_dependents(conn: &Connection, ids: &[String]) -> Result<Vec<String>, AuralisError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let slots = vec!["?"; ids.len()].join(", ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT tasks.id
+         FROM tasks JOIN task_dependencies d ON d.task_id = tasks.id
+         WHERE d.depends_on_id IN ({slots})
+           AND tasks.status != 'done'
+           AND {BLOCKED_SQL}"
+    ))?;
+    let found = stmt.query_map(params_from_iter(ids), |r| r.get(0))?;
+    Ok(found.collect::<Result<_, _>>()?)
+}
+
+/// Those of `ids` that are no longer blocked, oldest first.
+pub(crate) fn unblocked(conn: &Connection, ids: &[String]) -> Result<Vec<TaskItem>, AuralisError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let slots = vec!["?"; ids.len()].join(", ");
+    tasks::select(
+        conn,
+        &format!("id IN ({slots}) AND NOT {BLOCKED_SQL} ORDER BY created_at, rowid"),
+        params_from_iter(ids),
+    )
+}
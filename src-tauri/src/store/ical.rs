@@ -0,0 +1,482 @@
+use crate::datetime;
+use crate::domain::{Priority, TaskStatus};
+use crate::error::{AuralisError, ParseError};
+use crate::ical::{self, Component, Property};
+use crate::recurrence::Recurrence;
+use chrono::{Duration, Local, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+#[derive(Serialize, Default)]
+pub struct IcalImportSummary {
+    pub events_created: u32,
+    pub events_updated: u32,
+    pub tasks_created: u32,
+    pub tasks_updated: u32,
+    pub warnings: Vec<String>,
+}
+
+/// A rendered VCALENDAR and how many items went into it.
+pub struct IcalExport {
+    pub text: String,
+    pub events: u32,
+    pub tasks: u32,
+}
+
+enum Upsert {
+    Created,
+    Updated,
+}
+
+/// Imports VEVENTs into `calendar_events` and VTODOs into `tasks`. Items are matched
+/// by UID, so importing the same file again updates rather than duplicates.
+/// Items that fail are reported in `warnings` and do not abort the rest.
+pub fn import(
+    conn: &mut Connection,
+    text: &str,
+    area_id: Option<&str>, // area for imported tasks (default: Admin / Life)
+) -> Result<IcalImportSummary, AuralisError> {
+    let roots = ical::parse(text)?;
+
+    let components: Vec<&Component> = roots
+        .iter()
+        .flat_map(|root| {
+            if root.name == "VCALENDAR" {
+                root.children.iter().collect::<Vec<_>>()
+            } else {
+                vec![root]
+            }
+        })
+        .collect();
+
+    let task_area = area_id.unwrap_or("area_admin_life");
+    let mut summary = IcalImportSummary::default();
+
+    let tx = conn.transaction()?;
+
+    for comp in components {
+        let label = comp
+            .text("SUMMARY")
+            .or_else(|| comp.text("UID"))
+            .unwrap_or_else(|| comp.name.clone());
+
+        match comp.name.as_str() {
+            "VEVENT" => match import_event(&tx, comp, &mut summary.warnings) {
+                Ok(Upsert::Created) => summary.events_created += 1,
+                Ok(Upsert::Updated) => summary.events_updated += 1,
+                Err(e) => summary.warnings.push(format!("Skipped event '{label}': {e}")),
+            },
+            "VTODO" => match import_todo(&tx, comp, task_area, &mut summary.warnings) {
+                Ok(Upsert::Created) => summary.tasks_created += 1,
+                Ok(Upsert::Updated) => summary.tasks_updated += 1,
+                Err(e) => summary.warnings.push(format!("Skipped task '{label}': {e}")),
+            },
+            _ => {}
+        }
+    }
+
+    tx.commit()?;
+
+    Ok(summary)
+}
+
+/// Renders all calendar events plus every task with a due date as one VCALENDAR.
+pub fn export(conn: &Connection) -> Result<IcalExport, AuralisError> {
+    let mut cal = Component::new("VCALENDAR");
+    cal.push_raw("VERSION", "2.0");
+    cal.push_raw("PRODID", "-//Auralis//Auralis//EN");
+    cal.push_raw("CALSCALE", "GREGORIAN");
+
+    let stamp = ical::format_utc(Utc::now());
+    let events = export_events(conn, &mut cal, &stamp)?;
+    let tasks = export_tasks(conn, &mut cal, &stamp)?;
+
+    let mut text = String::new();
+    cal.write(&mut text);
+
+    Ok(IcalExport { text, events, tasks })
+}
+
+// Our own exports use `<id>@auralis` UIDs, so those map straight back to the row.
+fn local_id(uid: &str) -> Option<&str> {
+    uid.strip_suffix("@auralis")
+}
+
+// RRULE plus EXDATEs (converted to UTC) in the form stored in the `recurrence` columns.
+// Rules Auralis cannot expand are dropped with a warning rather than failing the item.
+fn recurrence_of(comp: &Component, label: &str, warnings: &mut Vec<String>) -> Option<String> {
+    let rrule = comp.get("RRULE")?;
+    let mut lines = vec![format!("RRULE:{}", rrule.value)];
+
+    for ex in comp.get_all("EXDATE") {
+        for value in ex.value.split(',').filter(|v| !v.trim().is_empty()) {
+            let single = Property {
+                name: ex.name.clone(),
+                params: ex.params.clone(),
+                value: value.to_string(),
+            };
+            match ical::parse_time(&single) {
+                Ok(t) if t.all_day => lines.push(format!("EXDATE;VALUE=DATE:{}", value.trim())),
+                Ok(t) => lines.push(format!("EXDATE:{}", ical::format_utc(t.at))),
+                Err(e) => warnings.push(format!("Ignored EXDATE on '{label}': {e}")),
+            }
+        }
+    }
+
+    match Recurrence::parse(&lines.join("\n")) {
+        Ok(rule) => Some(rule.to_string()),
+        Err(e) => {
+            warnings.push(format!("Imported '{label}' without its recurrence: {e}"));
+            None
+        }
+    }
+}
+
+fn import_event(
+    conn: &Connection,
+    comp: &Component,
+    warnings: &mut Vec<String>,
+) -> Result<Upsert, AuralisError> {
+    let title = comp.text("SUMMARY").unwrap_or_else(|| "(untitled)".to_string());
+
+    let start = comp
+        .get("DTSTART")
+        .ok_or_else(|| ParseError::from("missing DTSTART"))
+        .and_then(ical::parse_time)?;
+
+    let mut end = match (comp.get("DTEND"), comp.get("DURATION")) {
+        (Some(p), _) => ical::parse_time(p)?.at,
+        (None, Some(p)) => start
+            .at
+            .checked_add_signed(ical::parse_duration(&p.value)?)
+            .ok_or_else(|| AuralisError::validation(format!("Invalid duration '{}'", p.value)))?,
+        (None, None) if start.all_day => start.at + Duration::days(1),
+        (None, None) => start.at,
+    };
+    // Zero-length events are legal in iCalendar but not in our table.
+    if end <= start.at {
+        end = start.at + Duration::minutes(30);
+    }
+
+    let event_type = match comp.text("X-AURALIS-TYPE").as_deref() {
+        Some("block") => "block",
+        _ => "event",
+    };
+    let location = comp.text("LOCATION");
+    let recurrence = recurrence_of(comp, &title, warnings);
+    let uid = comp.text("UID");
+
+    let existing: Option<String> = match &uid {
+        Some(uid) => conn
+            .query_row(
+                "SELECT id FROM calendar_events WHERE ical_uid = ?1 OR id = ?2",
+                params![uid, local_id(uid)],
+                |r| r.get(0),
+            )
+            .optional()?,
+        None => None,
+    };
+
+    let start_at = datetime::format_utc(start.at);
+    let end_at = datetime::format_utc(end);
+
+    match existing {
+        Some(id) => {
+            conn.execute(
+                "UPDATE calendar_events
+                 SET title = ?1, start_at = ?2, end_at = ?3, type = ?4, location = ?5,
+                     recurrence = ?6, ical_uid = ?7
+                 WHERE id = ?8",
+                params![title, start_at, end_at, event_type, location, recurrence, uid, id],
+            )?;
+            Ok(Upsert::Updated)
+        }
+        None => {
+            let id = format!("event_{}", uuid::Uuid::new_v4());
+            conn.execute(
+                "INSERT INTO calendar_events
+                    (id, title, start_at, end_at, type, location, recurrence, ical_uid)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![id, title, start_at, end_at, event_type, location, recurrence, uid],
+            )?;
+            Ok(Upsert::Created)
+        }
+    }
+}
+
+fn import_todo(
+    conn: &Connection,
+    comp: &Component,
+    area_id: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Upsert, AuralisError> {
+    let title = comp.text("SUMMARY").unwrap_or_else(|| "(untitled)".to_string());
+
+    let due_at = comp
+        .get("DUE")
+        .map(ical::parse_time)
+        .transpose()?
+        .map(|t| datetime::format_utc(t.at));
+    let scheduled_at = comp
+        .get("DTSTART")
+        .map(ical::parse_time)
+        .transpose()?
+        .map(|t| datetime::format_utc(t.at));
+
+    // RFC 5545: 1-4 high, 5 medium, 6-9 low, 0 undefined.
+    let priority = match comp.get("PRIORITY").and_then(|p| p.value.trim().parse::<u8>().ok()) {
+        Some(1..=4) => Priority::High,
+        Some(6..=9) => Priority::Low,
+        _ => Priority::Normal,
+    };
+
+    // STATUS wins, since other clients only update that. Our own X-AURALIS-STATUS
+    // just tells a deferred task apart from a todo, both exported as NEEDS-ACTION.
+    let deferred = comp.text("X-AURALIS-STATUS").and_then(|s| s.parse().ok()) == Some(TaskStatus::Deferred);
+    let status = match comp.text("STATUS").map(|s| s.to_ascii_uppercase()).as_deref() {
+        Some("COMPLETED") => TaskStatus::Done,
+        Some("IN-PROCESS") => TaskStatus::Doing,
+        Some("CANCELLED") => TaskStatus::Deferred,
+        _ if deferred => TaskStatus::Deferred,
+        _ => TaskStatus::Todo,
+    };
+
+    let completed_at = if status == TaskStatus::Done {
+        let at = comp
+            .get("COMPLETED")
+            .and_then(|p| ical::parse_time(p).ok())
+            .map(|t| t.at)
+            .unwrap_or_else(Utc::now);
+        Some(datetime::format_utc(at))
+    } else {
+        None
+    };
+
+    let notes = comp.text("DESCRIPTION");
+    let recurrence = recurrence_of(comp, &title, warnings);
+    let uid = comp.text("UID");
+
+    let existing: Option<String> = match &uid {
+        Some(uid) => conn
+            .query_row(
+                "SELECT id FROM tasks WHERE ical_uid = ?1 OR id = ?2",
+                params![uid, local_id(uid)],
+                |r| r.get(0),
+            )
+            .optional()?,
+        None => None,
+    };
+
+    match existing {
+        Some(id) => {
+            conn.execute(
+                "UPDATE tasks
+                 SET title = ?1, priority = ?2, due_at = ?3, scheduled_at = ?4, notes = ?5,
+                     recurrence = ?6, status = ?7, completed_at = ?8, ical_uid = ?9
+                 WHERE id = ?10",
+                params![
+                    title,
+                    priority,
+                    due_at,
+                    scheduled_at,
+                    notes,
+                    recurrence,
+                    status,
+                    completed_at,
+                    uid,
+                    id
+                ],
+            )?;
+            Ok(Upsert::Updated)
+        }
+        None => {
+            let id = format!("task_{}", uuid::Uuid::new_v4());
+            conn.execute(
+                "INSERT INTO tasks
+                    (id, area_id, title, status, priority, due_at, scheduled_at, notes,
+                     recurrence, completed_at, ical_uid)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    id,
+                    area_id,
+                    title,
+                    status,
+                    priority,
+                    due_at,
+                    scheduled_at,
+                    notes,
+                    recurrence,
+                    completed_at,
+                    uid
+                ],
+            )?;
+            Ok(Upsert::Created)
+        }
+    }
+}
+
+// Stored timestamps are already UTC; re-parse them only to change the format.
+fn utc_value(stored: &str) -> Result<String, AuralisError> {
+    Ok(ical::format_utc(datetime::parse(stored)?))
+}
+
+// DTSTART goes out in UTC, so a floating UNTIL or EXDATE must too (RFC 5545 3.3.10).
+fn utc_recurrence(stored: &str) -> String {
+    match Recurrence::parse(stored) {
+        Ok(rule) => rule.in_utc(Local).to_string(),
+        Err(_) => stored.to_string(),
+    }
+}
+
+fn export_events(conn: &Connection, cal: &mut Component, stamp: &str) -> Result<u32, AuralisError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, start_at, end_at, type, location, recurrence, ical_uid
+             FROM calendar_events
+             ORDER BY start_at ASC",
+        )?;
+
+    type Row = (
+        String,
+        String,
+        String,
+        String,
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    );
+
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get(0)?,
+                r.get(1)?,
+                r.get(2)?,
+                r.get(3)?,
+                r.get(4)?,
+                r.get(5)?,
+                r.get(6)?,
+                r.get(7)?,
+            ))
+        })?;
+
+    let mut count = 0;
+    for r in rows {
+        let row: Row = r?;
+        let (id, title, start_at, end_at, event_type, location, recurrence, uid) = row;
+
+        let mut ev = Component::new("VEVENT");
+        ev.push_text("UID", &uid.unwrap_or_else(|| format!("{id}@auralis")));
+        ev.push_raw("DTSTAMP", stamp);
+        ev.push_raw("DTSTART", utc_value(&start_at)?);
+        ev.push_raw("DTEND", utc_value(&end_at)?);
+        ev.push_text("SUMMARY", &title);
+        if let Some(location) = location {
+            ev.push_text("LOCATION", &location);
+        }
+        if event_type == "block" {
+            ev.push_raw("X-AURALIS-TYPE", "block");
+        }
+        if let Some(recurrence) = recurrence {
+            for line in utc_recurrence(&recurrence).lines() {
+                ev.push_line(line);
+            }
+        }
+
+        cal.children.push(ev);
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+fn export_tasks(conn: &Connection, cal: &mut Component, stamp: &str) -> Result<u32, AuralisError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, status, priority, due_at, scheduled_at, notes, recurrence,
+                    completed_at, ical_uid
+             FROM tasks
+             WHERE due_at IS NOT NULL
+             ORDER BY due_at ASC",
+        )?;
+
+    type Row = (
+        String,
+        String,
+        TaskStatus,
+        Priority,
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    );
+
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get(0)?,
+                r.get(1)?,
+                r.get(2)?,
+                r.get(3)?,
+                r.get(4)?,
+                r.get(5)?,
+                r.get(6)?,
+                r.get(7)?,
+                r.get(8)?,
+                r.get(9)?,
+            ))
+        })?;
+
+    let mut count = 0;
+    for r in rows {
+        let row: Row = r?;
+        let (id, title, status, priority, due_at, scheduled_at, notes, recurrence, completed_at, uid) =
+            row;
+
+        let mut todo = Component::new("VTODO");
+        todo.push_text("UID", &uid.unwrap_or_else(|| format!("{id}@auralis")));
+        todo.push_raw("DTSTAMP", stamp);
+        todo.push_text("SUMMARY", &title);
+        todo.push_raw("DUE", utc_value(&due_at)?);
+        if let Some(scheduled_at) = scheduled_at {
+            todo.push_raw("DTSTART", utc_value(&scheduled_at)?);
+        }
+        todo.push_raw(
+            "PRIORITY",
+            match priority {
+                Priority::High => "1",
+                Priority::Normal => "5",
+                Priority::Low => "9",
+            },
+        );
+        todo.push_raw(
+            "STATUS",
+            match status {
+                TaskStatus::Done => "COMPLETED",
+                TaskStatus::Doing => "IN-PROCESS",
+                TaskStatus::Todo | TaskStatus::Deferred => "NEEDS-ACTION",
+            },
+        );
+        // "deferred" has no iCalendar equivalent; keep it for round-trips.
+        todo.push_raw("X-AURALIS-STATUS", status.as_str());
+        if let Some(completed_at) = completed_at {
+            todo.push_raw("COMPLETED", utc_value(&completed_at)?);
+        }
+        if let Some(notes) = notes {
+            todo.push_text("DESCRIPTION", &notes);
+        }
+        if let Some(recurrence) = recurrence {
+            for line in utc_recurrence(&recurrence).lines() {
+                todo.push_line(line);
+            }
+        }
+
+        cal.children.push(todo);
+        count += 1;
+    }
+
+    Ok(count)
+}
@@ -0,0 +1,130 @@
+use super::{tags, tasks};
+use crate::domain::{InboxSource, InboxState, TagTarget};
+use crate::error::AuralisError;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct InboxItem {
+    pub id: String,
+    pub content: String,
+    pub source: InboxSource,
+    pub state: InboxState,
+    pub created_at: String,
+}
+
+pub fn add(conn: &Connection, content: &str, source: InboxSource) -> Result<String, AuralisError> {
+    let content = content.trim().to_string();
+    if content.is_empty() {
+        return Err(AuralisError::validation("Content cannot be empty"));
+    }
+
+    let id = format!("inbox_{}", uuid::Uuid::new_v4());
+
+    conn.execute(
+        "INSERT INTO inbox_items (id, content, source, state)
+         VALUES (?1, ?2, ?3, 'unprocessed')",
+        params![id, content, source],
+    )?;
+
+    Ok(id)
+}
+
+pub fn list(conn: &Connection, state: Option<InboxState>) -> Result<Vec<InboxItem>, AuralisError> {
+    let mut items: Vec<InboxItem> = Vec::new();
+
+    let mut stmt = conn.prepare(
+        "SELECT id, content, source, state, created_at
+         FROM inbox_items
+         WHERE ?1 IS NULL OR state = ?1
+         ORDER BY created_at DESC",
+    )?;
+
+    let rows = stmt.query_map(params![state], |row| {
+        Ok(InboxItem {
+            id: row.get(0)?,
+            content: row.get(1)?,
+            source: row.get(2)?,
+            state: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    })?;
+
+    for r in rows {
+        items.push(r?);
+    }
+
+    Ok(items)
+}
+
+pub fn set_state(conn: &Connection, id: &str, state: InboxState) -> Result<(), AuralisError> {
+    let updated = conn.execute(
+        "UPDATE inbox_items SET state = ?1 WHERE id = ?2",
+        params![state, id],
+    )?;
+
+    if updated == 0 {
+        return Err(AuralisError::not_found("Inbox item"));
+    }
+
+    Ok(())
+}
+
+/// Turns an unprocessed inbox item into a task and marks it processed. The first
+/// line is read with the quick-add syntax; any further lines become the task's notes.
+pub fn convert_to_task(conn: &mut Connection, inbox_id: &str) -> Result<String, AuralisError> {
+    let tx = conn.transaction()?;
+
+    // 1) Fetch inbox content (must exist + be unprocessed)
+    let row: Option<(String, InboxState)> = tx
+        .query_row(
+            "SELECT content, state
+             FROM inbox_items
+             WHERE id = ?1",
+            params![inbox_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()?;
+
+    let (content, state) = match row {
+        Some(v) => v,
+        None => return Err(AuralisError::not_found("Inbox item")),
+    };
+
+    if state != InboxState::Unprocessed {
+        return Err(AuralisError::validation("Only unprocessed inbox items can be converted"));
+    }
+
+    // 2) Read the first line with the quick-add syntax; the rest becomes notes
+    let mut lines = content.lines();
+    let first_line = lines.next().unwrap_or("").trim();
+    let rest = lines.collect::<Vec<_>>().join("\n");
+    let rest = rest.trim();
+
+    let mut parsed = tasks::quick_add_parse(&tx, first_line)?;
+    if parsed.title.is_empty() {
+        parsed.title = "Inbox item".to_string();
+    }
+
+    // Clamp length on a character boundary
+    if let Some((cut, _)) = parsed.title.char_indices().nth(120) {
+        parsed.title.truncate(cut);
+    }
+
+    // 3) Insert task (defaults area to Admin/Life)
+    let notes = (!rest.is_empty()).then_some(rest);
+    let task_id = tasks::quick_add_insert(&tx, &parsed, notes)?;
+
+    // 4) The task keeps the item's tags
+    tags::copy(&tx, (TagTarget::Inbox, inbox_id), (TagTarget::Task, &task_id))?;
+
+    // 5) Mark inbox as processed
+    tx.execute(
+        "UPDATE inbox_items SET state = 'processed' WHERE id = ?1",
+        params![inbox_id],
+    )?;
+
+    tx.commit()?;
+
+    Ok(task_id)
+}
@@ -0,0 +1,314 @@
+//! The `links` table: every `[[wiki-link]]` in note content (see `crate::wikilink`)
+//! and what it points at.
+//!
+//! A link with a `note:`, `project:` or `task:` prefix matches that kind only; a
+//! plain `[[Name]]` matches a note title first, then a project name. Matching is by
+//! id or case-insensitive title, newest first. Links that match nothing are kept
+//! and resolved later, once something with that name exists.
+
+use crate::domain::LinkKind;
+use crate::error::AuralisError;
+use crate::wikilink::{self, WikiLink};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashSet;
+
+#[derive(Serialize, Debug)]
+pub struct OutgoingLink {
+    pub position: i64, // byte offset in the note's content
+    pub kind: Option<LinkKind>,
+    pub target_text: String,
+    pub heading: Option<String>,
+    pub label: Option<String>,
+    pub target_kind: Option<LinkKind>, // None while the link matches nothing
+    pub target_id: Option<String>,
+    pub target_title: Option<String>,
+}
+
+/// A note that links to an entity, with the lines the links are on.
+#[derive(Serialize, Debug)]
+pub struct Backlink {
+    pub note_id: String,
+    pub title: String,
+    pub updated_at: String,
+    pub contexts: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EntityLinks {
+    pub outgoing: Vec<OutgoingLink>, // only notes have any
+    pub backlinks: Vec<Backlink>,
+}
+
+fn lookup(conn: &Connection, kind: LinkKind, text: &str) -> Result<Option<String>, AuralisError> {
+    let sql = match kind {
+        LinkKind::Note => {
+            "SELECT id FROM notes WHERE id = ?1 OR title = ?1 COLLATE NOCASE
+             ORDER BY id = ?1 DESC, updated_at DESC LIMIT 1"
+        }
+        LinkKind::Project => {
+            "SELECT id FROM projects WHERE id = ?1 OR name = ?1 COLLATE NOCASE
+             ORDER BY id = ?1 DESC, created_at DESC LIMIT 1"
+        }
+        LinkKind::Task => {
+            "SELECT id FROM tasks WHERE id = ?1 OR title = ?1 COLLATE NOCASE
+             ORDER BY id = ?1 DESC, status = 'done', created_at DESC LIMIT 1"
+        }
+    };
+    Ok(conn.query_row(sql, params![text], |r| r.get(0)).optional()?)
+}
+
+fn resolve(conn: &Connection, kind: Option<LinkKind>, text: &str) -> Result<Option<(LinkKind, String)>, AuralisError> {
+    let kinds = match kind {
+        Some(kind) => vec![kind],
+        None => vec![LinkKind::Note, LinkKind::Project],
+    };
+    for kind in kinds {
+        if let Some(id) = lookup(conn, kind, text)? {
+            return Ok(Some((kind, id)));
+        }
+    }
+    Ok(None)
+}
+
+fn insert(conn: &Connection, note_id: &str, link: &WikiLink) -> Result<(), AuralisError> {
+    let target = resolve(conn, link.kind, &link.target)?;
+    conn.execute(
+        "INSERT INTO links (source_note_id, position, kind, target_text, heading, label, target_kind, target_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            note_id,
+            link.span.start as i64,
+            link.kind,
+            link.target,
+            link.heading,
+            link.label,
+            target.as_ref().map(|(kind, _)| *kind),
+            target.as_ref().map(|(_, id)| id),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Re-reads the links in one note's content.
+pub fn refresh_note(conn: &Connection, note_id: &str) -> Result<(), AuralisError> {
+    conn.execute("DELETE FROM links WHERE source_note_id = ?1", params![note_id])?;
+
+    let content: Option<String> = conn
+        .query_row("SELECT content FROM notes WHERE id = ?1", params![note_id], |r| r.get(0))
+        .optional()?;
+    for link in wikilink::parse(content.as_deref().unwrap_or_default()) {
+        insert(conn, note_id, &link)?;
+    }
+    Ok(())
+}
+
+/// Retries every link that matched nothing; something it names may exist now.
+pub fn resolve_pending(conn: &Connection) -> Result<(), AuralisError> {
+    let mut stmt = conn.prepare("SELECT id, kind, target_text FROM links WHERE target_id IS NULL")?;
+    let pending: Vec<(i64, Option<LinkKind>, String)> = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+        .collect::<Result<_, _>>()?;
+
+    for (id, kind, text) in pending {
+        if let Some((target_kind, target_id)) = resolve(conn, kind, &text)? {
+            conn.execute(
+                "UPDATE links SET target_kind = ?1, target_id = ?2 WHERE id = ?3",
+                params![target_kind, target_id, id],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Rebuilds the whole table from note content.
+pub fn rebuild(conn: &Connection) -> Result<(), AuralisError> {
+    conn.execute("DELETE FROM links", [])?;
+    let mut stmt = conn.prepare("SELECT id FROM notes")?;
+    let ids: Vec<String> = stmt.query_map([], |r| r.get(0))?.collect::<Result<_, _>>()?;
+    for id in ids {
+        refresh_note(conn, &id)?;
+    }
+    Ok(())
+}
+
+/// Builds the table on first run after the migration that added it (or after
+/// restoring a backup from before then), when notes have links but none are stored.
+pub fn backfill(conn: &Connection) -> Result<(), AuralisError> {
+    let stored = conn.query_row("SELECT 1 FROM links LIMIT 1", [], |_| Ok(())).optional()?;
+    let linked = conn
+        .query_row("SELECT 1 FROM notes WHERE content LIKE '%[[%' LIMIT 1", [], |_| Ok(()))
+        .optional()?;
+    if stored.is_none() && linked.is_some() {
+        rebuild(conn)?;
+    }
+    Ok(())
+}
+
+/// Keeps links in step with a note that was just created or edited. `old_title`
+/// is its title before the edit.
+pub fn note_saved(conn: &Connection, note_id: &str, old_title: Option<&str>) -> Result<(), AuralisError> {
+    refresh_note(conn, note_id)?;
+    if let Some(old) = old_title {
+        let new: String = conn.query_row("SELECT title FROM notes WHERE id = ?1", params![note_id], |r| r.get(0))?;
+        target_renamed(conn, LinkKind::Note, note_id, old, &new)?;
+    }
+    resolve_pending(conn)
+}
+
+/// Rewrites `[[old]]` to `[[new]]` in every note linking to the renamed entity.
+/// A plain link that would match something else under the new name gets a kind
+/// prefix, so it keeps pointing at the same target.
+pub fn target_renamed(
+    conn: &Connection,
+    kind: LinkKind,
+    id: &str,
+    old: &str,
+    new: &str,
+) -> Result<(), AuralisError> {
+    if old == new {
+        return Ok(());
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT l.source_note_id, l.position, n.content
+         FROM links l JOIN notes n ON n.id = l.source_note_id
+         WHERE l.target_kind = ?1 AND l.target_id = ?2",
+    )?;
+    let rows: Vec<(String, i64, String)> = stmt
+        .query_map(params![kind, id], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+        .collect::<Result<_, _>>()?;
+
+    let plain_stays = resolve(conn, None, new)?.is_some_and(|(k, i)| k == kind && i == id);
+    let mut sources: Vec<(String, String)> = Vec::new();
+    let mut positions: HashSet<(String, usize)> = HashSet::new();
+    for (source, position, content) in rows {
+        positions.insert((source.clone(), position as usize));
+        if !sources.iter().any(|(s, _)| *s == source) {
+            sources.push((source, content));
+        }
+    }
+
+    for (source, content) in sources {
+        let rewritten = wikilink::rewrite(&content, |link| {
+            if !positions.contains(&(source.clone(), link.span.start)) || !link.target.eq_ignore_ascii_case(old) {
+                return None;
+            }
+            match link.kind {
+                None if !plain_stays => Some(format!("{kind}:{new}")),
+                _ => Some(new.to_string()),
+            }
+        });
+        if rewritten != content {
+            conn.execute("UPDATE notes SET content = ?1 WHERE id = ?2", params![rewritten, source])?;
+            refresh_note(conn, &source)?;
+        }
+    }
+    Ok(())
+}
+
+fn outgoing(conn: &Connection, note_id: &str) -> Result<Vec<OutgoingLink>, AuralisError> {
+    let mut stmt = conn.prepare(
+        "SELECT position, kind, target_text, heading, label, target_kind, target_id,
+                CASE target_kind
+                  WHEN 'note' THEN (SELECT title FROM notes WHERE id = target_id)
+                  WHEN 'task' THEN (SELECT title FROM tasks WHERE id = target_id)
+                  WHEN 'project' THEN (SELECT name FROM projects WHERE id = target_id)
+                END
+         FROM links
+         WHERE source_note_id = ?1
+         ORDER BY position",
+    )?;
+    let items = stmt.query_map(params![note_id], |r| {
+        Ok(OutgoingLink {
+            position: r.get(0)?,
+            kind: r.get(1)?,
+            target_text: r.get(2)?,
+            heading: r.get(3)?,
+            label: r.get(4)?,
+            target_kind: r.get(5)?,
+            target_id: r.get(6)?,
+            target_title: r.get(7)?,
+        })
+    })?;
+    Ok(items.collect::<Result<_, _>>()?)
+}
+
+// The line of `content` around byte offset `position`.
+fn context(content: &str, position: usize) -> String {
+    let position = position.min(content.len());
+    let start = content[..position].rfind('\n').map_or(0, |i| i + 1);
+    let end = content[position..].find('\n').map_or(content.len(), |i| position + i);
+    content[start..end].trim().to_string()
+}
+
+/// Notes linking to an entity, most recently edited first.
+pub fn backlinks(conn: &Connection, kind: LinkKind, id: &str) -> Result<Vec<Backlink>, AuralisError> {
+    resolve_pending(conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT n.id, n.title, n.updated_at, n.content, l.position
+         FROM links l JOIN notes n ON n.id = l.source_note_id
+         WHERE l.target_kind = ?1 AND l.target_id = ?2
+         ORDER BY n.updated_at DESC, n.id, l.position",
+    )?;
+    let rows = stmt.query_map(params![kind, id], |r| {
+        Ok((
+            r.get::<_, String>(0)?,
+            r.get::<_, String>(1)?,
+            r.get::<_, String>(2)?,
+            r.get::<_, String>(3)?,
+            r.get::<_, i64>(4)?,
+        ))
+    })?;
+
+    let mut items: Vec<Backlink> = Vec::new();
+    for row in rows {
+        let (note_id, title, updated_at, content, position) = row?;
+        let line = context(&content, position as usize);
+        match items.last_mut() {
+            Some(last) if last.note_id == note_id => {
+                if !last.contexts.contains(&line) {
+                    last.contexts.push(line);
+                }
+            }
+            _ => items.push(Backlink {
+                note_id,
+                title,
+                updated_at,
+                contexts: vec![line],
+            }),
+        }
+    }
+    Ok(items)
+}
+
+fn exists(conn: &Connection, kind: LinkKind, id: &str) -> Result<bool, AuralisError> {
+    let table = match kind {
+        LinkKind::Note => "notes",
+        LinkKind::Task => "tasks",
+        LinkKind::Project => "projects",
+    };
+    Ok(conn
+        .query_row(&format!("SELECT 1 FROM {table} WHERE id = ?1"), params![id], |_| Ok(()))
+        .optional()?
+        .is_some())
+}
+
+/// Links out of (for a note) and into an entity.
+pub fn entity_links(conn: &Connection, kind: LinkKind, id: &str) -> Result<EntityLinks, AuralisError> {
+    if !exists(conn, kind, id)? {
+        return Err(AuralisError::not_found(match kind {
+            LinkKind::Note => "Note",
+            LinkKind::Task => "Task",
+            LinkKind::Project => "Project",
+        }));
+    }
+
+    let backlinks = backlinks(conn, kind, id)?;
+    let outgoing = match kind {
+        LinkKind::Note => outgoing(conn, id)?,
+        _ => Vec::new(),
+    };
+    Ok(EntityLinks { outgoing, backlinks })
+}
@@ -0,0 +1,26 @@
+//! Data access for every entity, as plain functions over a `rusqlite::Connection`.
+//! The Tauri commands lock the shared connection and delegate here, so everything
+//! below can be used (and tested) without a running app.
+
+pub mod agenda;
+pub mod areas;
+pub mod calendar;
+pub mod checklists;
+pub mod dependencies;
+pub mod ical;
+pub mod inbox;
+pub mod links;
+pub mod notes;
+pub mod project_stats;
+pub mod project_templates;
+pub mod projects;
+pub mod revisions;
+pub mod search;
+pub mod settings;
+pub mod smart_lists;
+pub mod subtasks;
+pub mod tags;
+pub mod tasks;
+pub mod time_entries;
+pub mod weekly_review;
+pub mod workspace;
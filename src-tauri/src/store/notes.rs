@@ -0,0 +1,165 @@
+use crate::domain::TagTarget;
+use crate::error::AuralisError;
+use crate::store::{links, revisions, tags};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct NoteItem {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub area_id: Option<String>,
+    pub project_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+fn note_from_row(row: &Row) -> rusqlite::Result<NoteItem> {
+    Ok(NoteItem {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        content: row.get(2)?,
+        area_id: row.get(3)?,
+        project_id: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn validate(title: &str, content: &str) -> Result<(String, String), AuralisError> {
+    let title = title.trim().to_string();
+    let content = content.trim().to_string();
+
+    if title.is_empty() {
+        return Err(AuralisError::validation("Title cannot be empty"));
+    }
+    if content.is_empty() {
+        return Err(AuralisError::validation("Content cannot be empty"));
+    }
+
+    Ok((title, content))
+}
+
+/// Creates a note and records the `[[links]]` in it, in one transaction.
+pub fn add(
+    conn: &mut Connection,
+    title: &str,
+    content: &str,
+    area_id: Option<&str>,
+    project_id: Option<&str>,
+) -> Result<String, AuralisError> {
+    let tx = conn.transaction()?;
+    let id = add_in(&tx, title, content, area_id, project_id)?;
+    tx.commit()?;
+    Ok(id)
+}
+
+/// [`add`] inside the caller's transaction.
+pub(crate) fn add_in(
+    conn: &Connection,
+    title: &str,
+    content: &str,
+    area_id: Option<&str>,
+    project_id: Option<&str>,
+) -> Result<String, AuralisError> {
+    let (title, content) = validate(title, content)?;
+
+    let id = format!("note_{}", uuid::Uuid::new_v4());
+
+    conn.execute(
+        "INSERT INTO notes (id, title, content, area_id, project_id)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![id, title, content, area_id, project_id],
+    )?;
+    links::note_saved(conn, &id, None)?;
+
+    Ok(id)
+}
+
+/// Notes for a project, else for an area, else all of them; most recently edited first.
+/// `tag` (an id or name) narrows any of these further.
+pub fn list(
+    conn: &Connection,
+    area_id: Option<&str>,
+    project_id: Option<&str>,
+    tag: Option<&str>,
+) -> Result<Vec<NoteItem>, AuralisError> {
+    let mut items: Vec<NoteItem> = Vec::new();
+
+    // priority: project filter > area filter > all
+    let (filter, value) = match (project_id, area_id) {
+        (Some(pid), _) => ("project_id = ?1", Some(pid)),
+        (None, Some(aid)) => ("area_id = ?1", Some(aid)),
+        (None, None) => ("?1 IS NULL", None),
+    };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, title, content, area_id, project_id, created_at, updated_at
+         FROM notes
+         WHERE {filter}
+           AND (?2 IS NULL OR {})
+         ORDER BY updated_at DESC",
+        tags::filter(TagTarget::Note, "notes.id", 2)
+    ))?;
+
+    let rows = stmt.query_map(params![value, tag], note_from_row)?;
+
+    for r in rows {
+        items.push(r?);
+    }
+
+    Ok(items)
+}
+
+/// Saves a note. A new title is carried into `[[links]]` in other notes; both
+/// commit together, along with pruning old revisions.
+pub fn update(
+    conn: &mut Connection,
+    id: &str,
+    title: &str,
+    content: &str,
+    area_id: Option<&str>,
+    project_id: Option<&str>,
+) -> Result<(), AuralisError> {
+    let (title, content) = validate(title, content)?;
+    let tx = conn.transaction()?;
+    let old_title: String = tx
+        .query_row("SELECT title FROM notes WHERE id = ?1", params![id], |r| r.get(0))
+        .optional()?
+        .ok_or_else(|| AuralisError::not_found("Note"))?;
+
+    tx.execute(
+        "UPDATE notes
+         SET title = ?1, content = ?2, area_id = ?3, project_id = ?4
+         WHERE id = ?5",
+        params![title, content, area_id, project_id, id],
+    )?;
+    links::note_saved(&tx, id, Some(&old_title))?;
+    revisions::prune(&tx)?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<(), AuralisError> {
+    let deleted = conn.execute("DELETE FROM notes WHERE id = ?1", params![id])?;
+
+    if deleted == 0 {
+        return Err(AuralisError::not_found("Note"));
+    }
+
+    revisions::prune(conn)
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<NoteItem, AuralisError> {
+    conn.query_row(
+        "SELECT id, title, content, area_id, project_id, created_at, updated_at
+         FROM notes
+         WHERE id = ?1",
+        params![id],
+        note_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AuralisError::not_found("Note"))
+}
@@ -0,0 +1,171 @@
+//! Progress and health of projects, worked out from their tasks in one query so
+//! the project list does not need a call per project.
+//!
+//! Health is, in order of precedence:
+//! - `done` / `paused` when the project itself is completed or paused;
+//! - `at_risk` when an open task is overdue, or the deadline is less than
+//!   [`AT_RISK_DAYS`] away (or has passed) with work left;
+//! - `stalled` when work is left but nothing was completed for [`STALLED_DAYS`]
+//!   (counted from the project's creation if nothing ever was);
+//! - `on_track` otherwise.
+
+use crate::datetime;
+use crate::domain::ProjectStatus;
+use crate::error::AuralisError;
+use chrono::{DateTime, Utc};
+use rusqlite::{params_from_iter, types::Value, Connection};
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub const AT_RISK_DAYS: i64 = 7;
+pub const STALLED_DAYS: i64 = 14;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectHealth {
+    OnTrack,
+    AtRisk,
+    Stalled,
+    Paused,
+    Done,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProjectStats {
+    pub project_id: String,
+    pub open_tasks: i64, // todo or doing
+    pub done_tasks: i64,
+    pub deferred_tasks: i64,
+    /// Done tasks out of all tasks, deferred ones included; 0 for an empty project.
+    pub percent_complete: i64,
+    pub overdue_tasks: i64,
+    pub last_completed_at: Option<String>,
+    pub days_since_last_completion: Option<i64>,
+    pub deadline_at: Option<String>,
+    /// Whole days left, negative once the deadline has passed.
+    pub days_to_deadline: Option<i64>,
+    pub health: ProjectHealth,
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<ProjectStats, AuralisError> {
+    let mut stats = many(conn, &[id.to_string()])?;
+    Ok(stats.remove(0))
+}
+
+/// Stats for each of `ids`, in the same order.
+pub fn many(conn: &Connection, ids: &[String]) -> Result<Vec<ProjectStats>, AuralisError> {
+    many_at(conn, ids, Utc::now())
+}
+
+pub fn many_at(conn: &Connection, ids: &[String], now: DateTime<Utc>) -> Result<Vec<ProjectStats>, AuralisError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let slots: Vec<String> = (2..ids.len() + 2).map(|i| format!("?{i}")).collect();
+    let sql = format!(
+        "SELECT p.id, p.status, p.created_at, p.deadline_at,
+                COALESCE(SUM(t.status IN ('todo', 'doing')), 0),
+                COALESCE(SUM(t.status = 'done'), 0),
+                COALESCE(SUM(t.status = 'deferred'), 0),
+                COALESCE(SUM(t.status IN ('todo', 'doing') AND t.due_at < ?1), 0),
+                MAX(CASE WHEN t.status = 'done' THEN t.completed_at END)
+         FROM projects p
+         LEFT JOIN tasks t ON t.project_id = p.id
+         WHERE p.id IN ({})
+         GROUP BY p.id",
+        slots.join(", ")
+    );
+    let mut values = vec![Value::Text(datetime::format_utc(now))];
+    values.extend(ids.iter().map(|id| Value::Text(id.clone())));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values.iter()), |r| {
+        Ok(Row {
+            id: r.get(0)?,
+            status: r.get(1)?,
+            created_at: r.get(2)?,
+            deadline_at: r.get(3)?,
+            open: r.get(4)?,
+            done: r.get(5)?,
+            deferred: r.get(6)?,
+            overdue: r.get(7)?,
+            last_completed_at: r.get(8)?,
+        })
+    })?;
+    let mut found: HashMap<String, Row> = HashMap::new();
+    for row in rows {
+        let row = row?;
+        found.insert(row.id.clone(), row);
+    }
+
+    ids.iter()
+        .map(|id| {
+            found
+                .get(id)
+                .ok_or_else(|| AuralisError::not_found("Project"))
+                .and_then(|row| row.stats(now))
+        })
+        .collect()
+}
+
+struct Row {
+    id: String,
+    status: ProjectStatus,
+    created_at: String,
+    deadline_at: Option<String>,
+    open: i64,
+    done: i64,
+    deferred: i64,
+    overdue: i64,
+    last_completed_at: Option<String>,
+}
+
+impl Row {
+    fn stats(&self, now: DateTime<Utc>) -> Result<ProjectStats, AuralisError> {
+        let days_since = |at: &str| -> Result<i64, AuralisError> {
+            Ok(whole_days(datetime::parse(at)?, now))
+        };
+        let days_since_last_completion = self.last_completed_at.as_deref().map(days_since).transpose()?;
+        let days_to_deadline = self
+            .deadline_at
+            .as_deref()
+            .map(|at| Ok::<_, AuralisError>(whole_days(now, datetime::parse(at)?)))
+            .transpose()?;
+        let idle_days = match days_since_last_completion {
+            Some(days) => days,
+            None => days_since(&self.created_at)?,
+        };
+
+        let total = self.open + self.done + self.deferred;
+        let work_left = self.open + self.deferred > 0;
+        let health = match self.status {
+            ProjectStatus::Completed => ProjectHealth::Done,
+            ProjectStatus::Paused => ProjectHealth::Paused,
+            _ if self.overdue > 0 || (work_left && days_to_deadline.is_some_and(|d| d < AT_RISK_DAYS)) => {
+                ProjectHealth::AtRisk
+            }
+            _ if work_left && idle_days >= STALLED_DAYS => ProjectHealth::Stalled,
+            _ => ProjectHealth::OnTrack,
+        };
+
+        Ok(ProjectStats {
+            project_id: self.id.clone(),
+            open_tasks: self.open,
+            done_tasks: self.done,
+            deferred_tasks: self.deferred,
+            percent_complete: if total == 0 { 0 } else { self.done * 100 / total },
+            overdue_tasks: self.overdue,
+            last_completed_at: self.last_completed_at.clone(),
+            days_since_last_completion,
+            deadline_at: self.deadline_at.clone(),
+            days_to_deadline,
+            health,
+        })
+    }
+}
+
+/// Whole days from `from` to `to`, rounded down.
+fn whole_days(from: DateTime<Utc>, to: DateTime<Utc>) -> i64 {
+    (to - from).num_seconds().div_euclid(86_400)
+}
@@ -0,0 +1,276 @@
+//! Project templates: a reusable list of tasks, with due dates kept as day offsets
+//! from an anchor date, plus an optional starter note. [`instantiate`] turns one
+//! into an active project in a single transaction.
+
+use crate::datetime;
+use crate::domain::{Priority, ProjectStatus};
+use crate::error::AuralisError;
+use crate::store::agenda::parse_date;
+use crate::store::notes;
+use crate::store::projects::{self, ProjectItem};
+use crate::store::tasks::{self, TaskItem};
+use chrono::Duration;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+// Furthest a due date may sit from the anchor, either way.
+const MAX_OFFSET_DAYS: i64 = 3650;
+
+#[derive(Serialize, Debug)]
+pub struct TemplateTask {
+    pub id: String,
+    pub position: i64,
+    pub title: String,
+    pub priority: Priority,
+    pub due_offset_days: Option<i64>,
+    pub estimate_minutes: Option<i64>,
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub name: String,
+    pub note: Option<String>,
+    pub tasks: Vec<TemplateTask>, // in order
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct TemplateTaskInput {
+    pub title: String,
+    pub priority: Option<Priority>, // normal when omitted
+    pub due_offset_days: Option<i64>,
+    pub estimate_minutes: Option<i64>,
+    pub notes: Option<String>,
+}
+
+/// A whole template; saving one replaces its task list.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct ProjectTemplateInput {
+    pub name: String,
+    pub note: Option<String>,
+    pub tasks: Vec<TemplateTaskInput>,
+}
+
+/// What [`instantiate`] created.
+#[derive(Serialize, Debug)]
+pub struct TemplateInstance {
+    pub project: ProjectItem,
+    pub tasks: Vec<TaskItem>,
+    pub note_id: Option<String>,
+}
+
+fn template_from_row(row: &Row) -> rusqlite::Result<ProjectTemplate> {
+    Ok(ProjectTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        note: row.get(2)?,
+        tasks: Vec::new(),
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+fn template_tasks(conn: &Connection, template_id: &str) -> Result<Vec<TemplateTask>, AuralisError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, position, title, priority, due_offset_days, estimate_minutes, notes
+         FROM project_template_tasks
+         WHERE template_id = ?1
+         ORDER BY position, rowid",
+    )?;
+    let rows = stmt.query_map(params![template_id], |r| {
+        Ok(TemplateTask {
+            id: r.get(0)?,
+            position: r.get(1)?,
+            title: r.get(2)?,
+            priority: r.get(3)?,
+            due_offset_days: r.get(4)?,
+            estimate_minutes: r.get(5)?,
+            notes: r.get(6)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<ProjectTemplate, AuralisError> {
+    let mut template = conn
+        .query_row(
+            "SELECT id, name, note, created_at, updated_at FROM project_templates WHERE id = ?1",
+            params![id],
+            template_from_row,
+        )
+        .optional()?
+        .ok_or_else(|| AuralisError::not_found("Project template"))?;
+    template.tasks = template_tasks(conn, id)?;
+    Ok(template)
+}
+
+/// Every template with its tasks, by name.
+pub fn list(conn: &Connection) -> Result<Vec<ProjectTemplate>, AuralisError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, note, created_at, updated_at
+         FROM project_templates
+         ORDER BY name COLLATE NOCASE",
+    )?;
+    let mut templates: Vec<ProjectTemplate> = stmt
+        .query_map([], template_from_row)?
+        .collect::<Result<_, _>>()?;
+    for template in &mut templates {
+        template.tasks = template_tasks(conn, &template.id)?;
+    }
+    Ok(templates)
+}
+
+fn clean(text: Option<String>) -> Option<String> {
+    text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Creates a template (without `id`) or replaces an existing one, tasks included.
+/// A template needs at least one task, so the project made from it can be active.
+pub fn save(conn: &mut Connection, id: Option<&str>, input: ProjectTemplateInput) -> Result<ProjectTemplate, AuralisError> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AuralisError::validation("Name cannot be empty"));
+    }
+    if input.tasks.is_empty() {
+        return Err(AuralisError::validation("A template needs at least one task"));
+    }
+    for task in &input.tasks {
+        if task.title.trim().is_empty() {
+            return Err(AuralisError::validation("Task titles cannot be empty"));
+        }
+        if task.due_offset_days.is_some_and(|d| d.abs() > MAX_OFFSET_DAYS) {
+            return Err(AuralisError::validation(format!(
+                "Due offsets must be within {MAX_OFFSET_DAYS} days of the anchor date"
+            )));
+        }
+        tasks::check_estimate(task.estimate_minutes)?;
+    }
+
+    let tx = conn.transaction()?;
+    let taken: Option<String> = tx
+        .query_row(
+            "SELECT id FROM project_templates WHERE name = ?1 COLLATE NOCASE AND id IS NOT ?2",
+            params![name, id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    if taken.is_some() {
+        return Err(AuralisError::validation(format!("A project template named '{name}' already exists")));
+    }
+
+    let note = clean(input.note);
+    let id = match id {
+        Some(id) => {
+            let updated = tx.execute(
+                "UPDATE project_templates
+                 SET name = ?1, note = ?2, updated_at = (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
+                 WHERE id = ?3",
+                params![name, note, id],
+            )?;
+            if updated == 0 {
+                return Err(AuralisError::not_found("Project template"));
+            }
+            tx.execute("DELETE FROM project_template_tasks WHERE template_id = ?1", params![id])?;
+            id.to_string()
+        }
+        None => {
+            let id = format!("template_{}", uuid::Uuid::new_v4());
+            tx.execute(
+                "INSERT INTO project_templates (id, name, note) VALUES (?1, ?2, ?3)",
+                params![id, name, note],
+            )?;
+            id
+        }
+    };
+
+    for (position, task) in input.tasks.into_iter().enumerate() {
+        tx.execute(
+            "INSERT INTO project_template_tasks
+                 (id, template_id, position, title, priority, due_offset_days, estimate_minutes, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                format!("template_task_{}", uuid::Uuid::new_v4()),
+                id,
+                position as i64,
+                task.title.trim(),
+                task.priority.unwrap_or(Priority::Normal),
+                task.due_offset_days,
+                task.estimate_minutes,
+                clean(task.notes),
+            ],
+        )?;
+    }
+    tx.commit()?;
+
+    get(conn, &id)
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<(), AuralisError> {
+    let deleted = conn.execute("DELETE FROM project_templates WHERE id = ?1", params![id])?;
+    if deleted == 0 {
+        return Err(AuralisError::not_found("Project template"));
+    }
+    Ok(())
+}
+
+/// Creates a project from a template in `area_id` (or the fallback area), named
+/// `name` (or after the template). Tasks are due `due_offset_days` after
+/// `anchor_date` (YYYY-MM-DD, local midnight). The project is inserted paused and
+/// activated once its tasks exist, all in one transaction, so the next-action
+/// rule holds throughout.
+pub fn instantiate(
+    conn: &mut Connection,
+    template_id: &str,
+    area_id: Option<&str>,
+    name: Option<&str>,
+    anchor_date: &str,
+) -> Result<TemplateInstance, AuralisError> {
+    let anchor = parse_date(anchor_date)?;
+    let tx = conn.transaction()?;
+    let template = get(&tx, template_id)?;
+
+    let area_id = area_id.unwrap_or("area_admin_life");
+    let name = name.map(str::trim).filter(|n| !n.is_empty()).unwrap_or(&template.name);
+    projects::ensure_area(&tx, area_id)?;
+    projects::ensure_name_free(&tx, None, area_id, name)?;
+    let project_id = projects::add(&tx, name, Some(area_id))?;
+
+    for task in &template.tasks {
+        let due_at = task
+            .due_offset_days
+            .map(|days| datetime::normalize(&(anchor + Duration::days(days)).format("%Y-%m-%d").to_string()))
+            .transpose()?;
+        tx.execute(
+            "INSERT INTO tasks (id, area_id, project_id, title, priority, due_at, estimate_minutes, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                format!("task_{}", uuid::Uuid::new_v4()),
+                area_id,
+                project_id,
+                task.title,
+                task.priority,
+                due_at,
+                task.estimate_minutes,
+                task.notes,
+            ],
+        )?;
+    }
+
+    let note_id = match &template.note {
+        Some(note) => Some(notes::add_in(&tx, name, note, Some(area_id), Some(&project_id))?),
+        None => None,
+    };
+    projects::set_status(&tx, &project_id, ProjectStatus::Active)?;
+    tx.commit()?;
+
+    Ok(TemplateInstance {
+        project: projects::get(conn, &project_id)?,
+        tasks: tasks::select(conn, "project_id = ?1 ORDER BY created_at, rowid", params![project_id])?,
+        note_id,
+    })
+}
@@ -0,0 +1,260 @@
+use crate::datetime;
+use crate::domain::{LinkKind, Priority, ProjectStatus, TagTarget};
+use crate::error::AuralisError;
+use crate::store::{links, tags};
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Debug)]
+pub struct ProjectItem {
+    pub id: String,
+    pub area_id: String,
+    pub name: String,
+    pub status: ProjectStatus,
+    pub goal: Option<String>,
+    pub deadline_at: Option<String>,
+    pub priority: Option<Priority>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+/// Partial update for [`update`]. Omitted fields are left alone; `null` clears
+/// a nullable column. Status is changed through [`set_status`].
+#[derive(Deserialize, Default)]
+pub struct ProjectPatch {
+    pub name: Option<String>,
+    pub area_id: Option<String>,
+    #[serde(default, deserialize_with = "crate::patch::nullable")]
+    pub goal: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::patch::nullable")]
+    pub deadline_at: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::patch::nullable")]
+    pub priority: Option<Option<Priority>>,
+}
+
+const PROJECT_COLUMNS: &str = "id, area_id, name, status, goal, deadline_at, priority, created_at, completed_at";
+
+fn project_from_row(row: &Row) -> rusqlite::Result<ProjectItem> {
+    Ok(ProjectItem {
+        id: row.get(0)?,
+        area_id: row.get(1)?,
+        name: row.get(2)?,
+        status: row.get(3)?,
+        goal: row.get(4)?,
+        deadline_at: row.get(5)?,
+        priority: row.get(6)?,
+        created_at: row.get(7)?,
+        completed_at: row.get(8)?,
+    })
+}
+
+/// New projects start paused: the schema only lets a project be active once it
+/// has an open task.
+pub fn add(conn: &Connection, name: &str, area_id: Option<&str>) -> Result<String, AuralisError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AuralisError::validation("Name cannot be empty"));
+    }
+
+    let id = format!("project_{}", uuid::Uuid::new_v4());
+    let area_id = area_id.unwrap_or("area_admin_life");
+
+    conn.execute(
+        "INSERT INTO projects (id, area_id, name, status)
+         VALUES (?1, ?2, ?3, 'paused')",
+        params![id, area_id, name],
+    )?;
+
+    Ok(id)
+}
+
+/// Projects, newest first, optionally narrowed to a status and/or a tag (by id or name).
+pub fn list(
+    conn: &Connection,
+    status: Option<ProjectStatus>,
+    tag: Option<&str>,
+) -> Result<Vec<ProjectItem>, AuralisError> {
+    let mut items: Vec<ProjectItem> = Vec::new();
+
+    let tag_filter = tags::filter(TagTarget::Project, "projects.id", 2);
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {PROJECT_COLUMNS}
+         FROM projects
+         WHERE (?1 IS NULL OR status = ?1)
+           AND (?2 IS NULL OR {tag_filter})
+         ORDER BY created_at DESC",
+    ))?;
+
+    let rows = stmt.query_map(params![status, tag], project_from_row)?;
+
+    for r in rows {
+        items.push(r?);
+    }
+
+    Ok(items)
+}
+
+pub fn set_status(conn: &Connection, id: &str, status: ProjectStatus) -> Result<(), AuralisError> {
+    let updated = conn.execute(
+        "UPDATE projects SET status = ?1 WHERE id = ?2",
+        params![status, id],
+    )?;
+
+    if updated == 0 {
+        return Err(AuralisError::not_found("Project"));
+    }
+
+    Ok(())
+}
+
+/// Renames a project; `[[links]]` to it in notes are rewritten to the new name.
+pub fn rename(conn: &mut Connection, id: &str, name: &str) -> Result<(), AuralisError> {
+    let patch = ProjectPatch {
+        name: Some(name.to_string()),
+        ..Default::default()
+    };
+    update(conn, id, patch, false).map(|_| ())
+}
+
+/// Applies `patch`. A project name is unique within its area, so renames and
+/// moves are checked against the area the project ends up in. With `move_tasks`,
+/// a move to another area takes the project's tasks along; otherwise they stay
+/// where they are. Moved tasks and rewritten links commit together with the project.
+pub fn update(
+    conn: &mut Connection,
+    id: &str,
+    patch: ProjectPatch,
+    move_tasks: bool,
+) -> Result<ProjectItem, AuralisError> {
+    let tx = conn.transaction()?;
+    let current = get(&tx, id)?;
+    let mut sets: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    let name = match patch.name {
+        Some(name) => {
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return Err(AuralisError::validation("Name cannot be empty"));
+            }
+            sets.push("name");
+            values.push(Value::Text(name.clone()));
+            name
+        }
+        None => current.name.clone(),
+    };
+
+    let area_id = match patch.area_id {
+        Some(area_id) => {
+            ensure_area(&tx, &area_id)?;
+            sets.push("area_id");
+            values.push(Value::Text(area_id.clone()));
+            area_id
+        }
+        None => current.area_id.clone(),
+    };
+
+    if let Some(goal) = patch.goal {
+        sets.push("goal");
+        values.push(
+            goal.map(|g| g.trim().to_string())
+                .filter(|g| !g.is_empty())
+                .map_or(Value::Null, Value::Text),
+        );
+    }
+
+    if let Some(deadline_at) = patch.deadline_at {
+        let deadline_at = match deadline_at.as_deref().map(str::trim) {
+            Some(d) if !d.is_empty() => Value::Text(datetime::normalize(d)?),
+            _ => Value::Null,
+        };
+        sets.push("deadline_at");
+        values.push(deadline_at);
+    }
+
+    if let Some(priority) = patch.priority {
+        sets.push("priority");
+        values.push(priority.map_or(Value::Null, |p| Value::Text(p.to_string())));
+    }
+
+    if sets.is_empty() {
+        return Ok(current);
+    }
+
+    ensure_name_free(&tx, Some(id), &area_id, &name)?;
+
+    let assignments: Vec<String> = sets
+        .iter()
+        .enumerate()
+        .map(|(i, col)| format!("{col} = ?{}", i + 1))
+        .collect();
+    let sql = format!(
+        "UPDATE projects SET {} WHERE id = ?{}",
+        assignments.join(", "),
+        values.len() + 1
+    );
+    values.push(Value::Text(id.to_string()));
+    tx.execute(&sql, params_from_iter(values.iter()))?;
+
+    if move_tasks && area_id != current.area_id {
+        tx.execute(
+            "UPDATE tasks SET area_id = ?1 WHERE project_id = ?2",
+            params![area_id, id],
+        )?;
+    }
+    links::target_renamed(&tx, LinkKind::Project, id, &current.name, &name)?;
+
+    tx.commit()?;
+
+    get(conn, id)
+}
+
+pub(crate) fn ensure_area(conn: &Connection, area_id: &str) -> Result<(), AuralisError> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM areas WHERE id = ?1)",
+        params![area_id],
+        |r| r.get(0),
+    )?;
+    if !exists {
+        return Err(AuralisError::not_found("Area"));
+    }
+    Ok(())
+}
+
+/// Project names are unique within an area; this turns a clash into a readable
+/// error before the index refuses it. `id` is the project being renamed or moved.
+pub(crate) fn ensure_name_free(
+    conn: &Connection,
+    id: Option<&str>,
+    area_id: &str,
+    name: &str,
+) -> Result<(), AuralisError> {
+    let taken: Option<String> = conn
+        .query_row(
+            "SELECT a.name FROM projects p JOIN areas a ON a.id = p.area_id
+             WHERE p.area_id = ?1 AND p.name = ?2 AND p.id IS NOT ?3",
+            params![area_id, name, id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    match taken {
+        Some(area) => Err(AuralisError::validation(format!(
+            "A project named '{name}' already exists in {area}"
+        ))),
+        None => Ok(()),
+    }
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<ProjectItem, AuralisError> {
+    conn.query_row(
+        &format!(
+            "SELECT {PROJECT_COLUMNS}
+             FROM projects
+             WHERE id = ?1"
+        ),
+        params![id],
+        project_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AuralisError::not_found("Project"))
+}
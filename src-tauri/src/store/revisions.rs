@@ -0,0 +1,229 @@
+//! Note history. Triggers save the previous version of a note to `note_revisions`
+//! whenever it is edited or deleted, however that happens (commands, vault sync,
+//! workspace import); this module reads, compares and restores those versions.
+//!
+//! Retention: a note always keeps its `KEEP_LATEST` newest revisions; older ones
+//! expire after `KEEP_DAYS`. Once a note is deleted, its whole history is kept for
+//! `KEEP_DAYS` after the delete so it can still be brought back.
+
+use crate::datetime;
+use crate::diff::{self, DiffLine};
+use crate::error::AuralisError;
+use crate::store::links;
+use crate::store::notes::{self, NoteItem};
+use chrono::{Duration, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+
+const KEEP_LATEST: i64 = 20;
+const KEEP_DAYS: i64 = 90;
+
+#[derive(Serialize, Debug)]
+pub struct NoteRevision {
+    pub id: i64,
+    pub note_id: String,
+    pub title: String,
+    pub content: String,
+    pub area_id: Option<String>,
+    pub project_id: Option<String>,
+    pub note_created_at: String,
+    pub edited_at: String,  // when this version was written
+    pub reason: String,     // update | delete: what replaced it
+    pub created_at: String, // when it was replaced
+}
+
+/// One side of a diff: a revision, or the note as it is now (`revision_id: None`).
+#[derive(Serialize, Debug)]
+pub struct NoteVersion {
+    pub revision_id: Option<i64>,
+    pub title: String,
+    pub edited_at: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct NoteRevisionDiff {
+    pub from: NoteVersion,
+    pub to: NoteVersion,
+    pub lines: Vec<DiffLine>,
+}
+
+const COLUMNS: &str =
+    "id, note_id, title, content, area_id, project_id, note_created_at, edited_at, reason, created_at";
+
+fn revision_from_row(row: &Row) -> rusqlite::Result<NoteRevision> {
+    Ok(NoteRevision {
+        id: row.get(0)?,
+        note_id: row.get(1)?,
+        title: row.get(2)?,
+        content: row.get(3)?,
+        area_id: row.get(4)?,
+        project_id: row.get(5)?,
+        note_created_at: row.get(6)?,
+        edited_at: row.get(7)?,
+        reason: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+fn get(conn: &Connection, note_id: &str, revision_id: i64) -> Result<NoteRevision, AuralisError> {
+    conn.query_row(
+        &format!("SELECT {COLUMNS} FROM note_revisions WHERE id = ?1 AND note_id = ?2"),
+        params![revision_id, note_id],
+        revision_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AuralisError::not_found("Revision"))
+}
+
+/// Earlier versions of a note, newest first. Also works for a deleted note.
+pub fn history(conn: &Connection, note_id: &str) -> Result<Vec<NoteRevision>, AuralisError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COLUMNS} FROM note_revisions WHERE note_id = ?1 ORDER BY id DESC"
+    ))?;
+    let items: Vec<NoteRevision> = stmt
+        .query_map(params![note_id], revision_from_row)?
+        .collect::<Result<_, _>>()?;
+
+    if items.is_empty() {
+        // Distinguish "no history yet" from a note that never existed.
+        notes::get(conn, note_id)?;
+    }
+    Ok(items)
+}
+
+/// The last version of every deleted note that can still be restored, most
+/// recently deleted first.
+pub fn deleted(conn: &Connection) -> Result<Vec<NoteRevision>, AuralisError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COLUMNS} FROM note_revisions r
+         WHERE reason = 'delete'
+           AND NOT EXISTS (SELECT 1 FROM notes n WHERE n.id = r.note_id)
+           AND id = (SELECT MAX(id) FROM note_revisions WHERE note_id = r.note_id)
+         ORDER BY id DESC"
+    ))?;
+    let items = stmt.query_map([], revision_from_row)?;
+    Ok(items.collect::<Result<_, _>>()?)
+}
+
+fn version(conn: &Connection, note_id: &str, revision_id: Option<i64>) -> Result<(NoteVersion, String), AuralisError> {
+    Ok(match revision_id {
+        Some(id) => {
+            let r = get(conn, note_id, id)?;
+            (
+                NoteVersion {
+                    revision_id: Some(id),
+                    title: r.title,
+                    edited_at: r.edited_at,
+                },
+                r.content,
+            )
+        }
+        None => {
+            let n = notes::get(conn, note_id)?;
+            (
+                NoteVersion {
+                    revision_id: None,
+                    title: n.title,
+                    edited_at: n.updated_at,
+                },
+                n.content,
+            )
+        }
+    })
+}
+
+/// Line-level diff of a note's content between revision `from` and revision `to`.
+/// `None` on either side stands for the note as it is now.
+pub fn diff(
+    conn: &Connection,
+    note_id: &str,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<NoteRevisionDiff, AuralisError> {
+    let (from, old) = version(conn, note_id, from)?;
+    let (to, new) = version(conn, note_id, to)?;
+
+    Ok(NoteRevisionDiff {
+        from,
+        to,
+        lines: diff::lines(&old, &new),
+    })
+}
+
+fn exists(conn: &Connection, table: &str, id: &str) -> Result<bool, AuralisError> {
+    Ok(conn
+        .query_row(&format!("SELECT 1 FROM {table} WHERE id = ?1"), params![id], |_| Ok(()))
+        .optional()?
+        .is_some())
+}
+
+/// Puts revision `revision_id` back as the current version of the note,
+/// re-creating the note if it was deleted. The version it replaces goes into the
+/// history like any other edit, so a restore can itself be undone. An area or
+/// project that no longer exists is dropped.
+pub fn restore(conn: &mut Connection, note_id: &str, revision_id: i64) -> Result<NoteItem, AuralisError> {
+    let tx = conn.transaction()?;
+    let r = get(&tx, note_id, revision_id)?;
+    let old_title: Option<String> = tx
+        .query_row("SELECT title FROM notes WHERE id = ?1", params![note_id], |row| row.get(0))
+        .optional()?;
+    let area_id = match r.area_id {
+        Some(id) if exists(&tx, "areas", &id)? => Some(id),
+        _ => None,
+    };
+    let project_id = match r.project_id {
+        Some(id) if exists(&tx, "projects", &id)? => Some(id),
+        _ => None,
+    };
+
+    let updated = tx.execute(
+        "UPDATE notes SET title = ?1, content = ?2, area_id = ?3, project_id = ?4 WHERE id = ?5",
+        params![r.title, r.content, area_id, project_id, note_id],
+    )?;
+    if updated == 0 {
+        tx.execute(
+            "INSERT INTO notes (id, title, content, area_id, project_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![note_id, r.title, r.content, area_id, project_id, r.note_created_at],
+        )?;
+    }
+
+    links::note_saved(&tx, note_id, old_title.as_deref())?;
+    prune(&tx)?;
+    let note = notes::get(&tx, note_id)?;
+    tx.commit()?;
+    Ok(note)
+}
+
+/// Applies the retention policy described at the top of this module.
+pub fn prune(conn: &Connection) -> Result<(), AuralisError> {
+    let cutoff = datetime::format_utc(Utc::now() - Duration::days(KEEP_DAYS));
+
+    // Histories of notes deleted long enough ago.
+    conn.execute(
+        "DELETE FROM note_revisions
+         WHERE note_id IN (
+             SELECT note_id FROM note_revisions r
+             WHERE reason = 'delete' AND created_at < ?1
+               AND NOT EXISTS (SELECT 1 FROM notes n WHERE n.id = r.note_id)
+               AND id = (SELECT MAX(id) FROM note_revisions WHERE note_id = r.note_id)
+         )",
+        params![cutoff],
+    )?;
+
+    // Expired revisions beyond the newest few of each note.
+    conn.execute(
+        "DELETE FROM note_revisions
+         WHERE created_at < ?1
+           AND id IN (
+             SELECT id FROM (
+               SELECT id, ROW_NUMBER() OVER (PARTITION BY note_id ORDER BY id DESC) AS rank
+               FROM note_revisions
+             )
+             WHERE rank > ?2
+           )",
+        params![cutoff, KEEP_LATEST],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use crate::error::AuralisError;
+use rusqlite::{params_from_iter, Connection};
+use serde::Serialize;
+
+const KINDS: [&str; 4] = ["note", "task", "project", "inbox"];
+
+// Control characters never appear in stored text, so they can mark matches safely
+// until the snippet has been HTML-escaped.
+const MARK_OPEN: &str = "\u{1}";
+const MARK_CLOSE: &str = "\u{2}";
+
+#[derive(Serialize)]
+pub struct SearchHit {
+    pub kind: String, // note | task | project | inbox
+    pub id: String,
+    pub title: String,   // HTML-escaped, matches wrapped in <mark>
+    pub snippet: String, // HTML-escaped, matches wrapped in <mark>
+    pub rank: f64,       // lower is better (bm25)
+}
+
+/// Turns free text into an FTS5 query: every word must match, the last one as a
+/// prefix so results update while typing. FTS syntax in the input is neutralised.
+fn to_fts_query(query: &str) -> Option<String> {
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|w| w.replace('"', ""))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let last = words.len().checked_sub(1)?;
+    let terms: Vec<String> = words
+        .iter()
+        .enumerate()
+        .map(|(i, w)| {
+            if i == last {
+                format!("\"{w}\"*")
+            } else {
+                format!("\"{w}\"")
+            }
+        })
+        .collect();
+
+    Some(terms.join(" "))
+}
+
+fn render_marked(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace(MARK_OPEN, "<mark>")
+        .replace(MARK_CLOSE, "</mark>")
+}
+
+/// Ranked full-text search across notes, tasks, projects and inbox items.
+/// `kinds` narrows the entity types (default: all); `limit` defaults to 20.
+pub fn search(
+    conn: &Connection,
+    query: &str,
+    kinds: Option<Vec<String>>,
+    limit: Option<u32>,
+) -> Result<Vec<SearchHit>, AuralisError> {
+    let fts_query = match to_fts_query(query) {
+        Some(q) => q,
+        None => return Ok(Vec::new()),
+    };
+
+    let kinds = kinds.unwrap_or_default();
+    if let Some(bad) = kinds.iter().find(|k| !KINDS.contains(&k.as_str())) {
+        return Err(AuralisError::Validation(format!(
+            "Invalid kind '{bad}' (must be one of: note, task, project, inbox)"
+        )));
+    }
+
+    let limit = limit.unwrap_or(20).clamp(1, 100);
+
+    let mut sql = String::from(
+        "SELECT kind, entity_id,
+                highlight(search_index, 2, ?1, ?2),
+                snippet(search_index, 3, ?1, ?2, '…', 16),
+                bm25(search_index, 0.0, 0.0, 10.0, 1.0) AS rank
+         FROM search_index
+         WHERE search_index MATCH ?3",
+    );
+
+    let mut values: Vec<String> = vec![MARK_OPEN.into(), MARK_CLOSE.into(), fts_query];
+
+    if !kinds.is_empty() {
+        let placeholders: Vec<String> = (0..kinds.len())
+            .map(|i| format!("?{}", values.len() + 1 + i))
+            .collect();
+        sql.push_str(&format!(" AND kind IN ({})", placeholders.join(", ")));
+        values.extend(kinds);
+    }
+
+    sql.push_str(&format!(" ORDER BY rank LIMIT {limit}"));
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt
+        .query_map(params_from_iter(values.iter()), |row| {
+            let title: String = row.get(2)?;
+            let snippet: String = row.get(3)?;
+            Ok(SearchHit {
+                kind: row.get(0)?,
+                id: row.get(1)?,
+                title: render_marked(&title),
+                snippet: render_marked(&snippet),
+                rank: row.get(4)?,
+            })
+        })?;
+
+    let mut hits: Vec<SearchHit> = Vec::new();
+    for r in rows {
+        hits.push(r?);
+    }
+
+    Ok(hits)
+}
@@ -0,0 +1,21 @@
+use crate::error::AuralisError;
+use rusqlite::{params, Connection, OptionalExtension};
+
+pub fn get(conn: &Connection, key: &str) -> Result<Option<String>, AuralisError> {
+    Ok(conn
+        .query_row("SELECT value FROM settings WHERE key = ?1", params![key], |r| r.get(0))
+        .optional()?)
+}
+
+/// Stores `value` under `key`; `None` removes the setting.
+pub fn set(conn: &Connection, key: &str, value: Option<&str>) -> Result<(), AuralisError> {
+    match value {
+        Some(value) => conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?,
+        None => conn.execute("DELETE FROM settings WHERE key = ?1", params![key])?,
+    };
+    Ok(())
+}
@@ -0,0 +1,131 @@
+//! Saved task queries ("Today", "Overdue", "Waiting", ...), in the filter language of
+//! `crate::task_query`.
+
+use crate::error::AuralisError;
+use crate::store::tasks::{self, TaskItem};
+use crate::task_query;
+use chrono::Local;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+
+#[derive(Serialize, Debug)]
+pub struct SmartList {
+    pub id: String,
+    pub name: String,
+    pub query: String,
+    pub position: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+fn smart_list_from_row(row: &Row) -> rusqlite::Result<SmartList> {
+    Ok(SmartList {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        query: row.get(2)?,
+        position: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<SmartList, AuralisError> {
+    conn.query_row(
+        "SELECT id, name, query, position, created_at, updated_at
+         FROM smart_lists
+         WHERE id = ?1",
+        params![id],
+        smart_list_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AuralisError::not_found("Smart list"))
+}
+
+/// All smart lists, in their pinned order.
+pub fn list(conn: &Connection) -> Result<Vec<SmartList>, AuralisError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, query, position, created_at, updated_at
+         FROM smart_lists
+         ORDER BY position, name COLLATE NOCASE",
+    )?;
+    let items = stmt.query_map([], smart_list_from_row)?;
+    Ok(items.collect::<Result<_, _>>()?)
+}
+
+/// Creates a smart list (without `id`) at the end of the list, or renames and
+/// re-queries an existing one. The query is checked before anything is stored.
+pub fn save(conn: &Connection, id: Option<&str>, name: &str, query: &str) -> Result<SmartList, AuralisError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AuralisError::validation("Name cannot be empty"));
+    }
+    let query = query.trim();
+    task_query::compile(query, Local::now().naive_local())?;
+
+    let taken: Option<String> = conn
+        .query_row(
+            "SELECT id FROM smart_lists WHERE name = ?1 COLLATE NOCASE AND id IS NOT ?2",
+            params![name, id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    if taken.is_some() {
+        return Err(AuralisError::validation(format!("A smart list named '{name}' already exists")));
+    }
+
+    match id {
+        Some(id) => {
+            let updated = conn.execute(
+                "UPDATE smart_lists
+                 SET name = ?1, query = ?2, updated_at = (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
+                 WHERE id = ?3",
+                params![name, query, id],
+            )?;
+            if updated == 0 {
+                return Err(AuralisError::not_found("Smart list"));
+            }
+            get(conn, id)
+        }
+        None => {
+            let id = format!("smart_list_{}", uuid::Uuid::new_v4());
+            conn.execute(
+                "INSERT INTO smart_lists (id, name, query, position)
+                 VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(position) + 1, 0) FROM smart_lists))",
+                params![id, name, query],
+            )?;
+            get(conn, &id)
+        }
+    }
+}
+
+/// Pins the lists in the given order; lists not named keep their place after them.
+pub fn reorder(conn: &mut Connection, ids: &[String]) -> Result<Vec<SmartList>, AuralisError> {
+    let tx = conn.transaction()?;
+    for id in ids {
+        get(&tx, id)?;
+    }
+    let count = ids.len() as i64;
+    tx.execute("UPDATE smart_lists SET position = position + ?1", params![count])?;
+    for (position, id) in ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE smart_lists SET position = ?1 WHERE id = ?2",
+            params![position as i64, id],
+        )?;
+    }
+    tx.commit()?;
+    list(conn)
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<(), AuralisError> {
+    let deleted = conn.execute("DELETE FROM smart_lists WHERE id = ?1", params![id])?;
+    if deleted == 0 {
+        return Err(AuralisError::not_found("Smart list"));
+    }
+    Ok(())
+}
+
+/// The tasks a smart list currently matches.
+pub fn run(conn: &Connection, id: &str) -> Result<Vec<TaskItem>, AuralisError> {
+    let list = get(conn, id)?;
+    tasks::query(conn, &list.query)
+}
@@ -0,0 +1,285 @@
+//! Subtasks: tasks with a `parent_task_id`, nested at most [`MAX_DEPTH`] levels.
+//! A subtask always sits in its parent's project. A task with open subtasks cannot
+//! be completed on its own (a schema trigger refuses it); [`complete`] closes the
+//! whole tree at once.
+
+use crate::domain::TaskStatus;
+use crate::error::AuralisError;
+use crate::store::dependencies;
+use crate::store::tasks::{self, StatusChange, TaskItem};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// A task, its subtasks and theirs.
+pub const MAX_DEPTH: usize = 3;
+
+// Stops the recursive queries should the data ever contain a cycle.
+const WALK_LIMIT: i64 = 64;
+
+#[derive(Serialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Progress {
+    pub done: i64,
+    pub total: i64,
+}
+
+#[derive(Serialize)]
+pub struct TaskNode {
+    #[serde(flatten)]
+    pub task: TaskItem,
+    pub checklist: Progress, // this task's own checklist
+    pub progress: Progress,  // every subtask below it, plus all their checklists and its own
+    pub children: Vec<TaskNode>,
+}
+
+// Parent, grandparent, ... of a task.
+pub(crate) fn ancestors(conn: &Connection, id: &str) -> Result<Vec<String>, AuralisError> {
+    let mut stmt = conn.prepare(
+        "WITH RECURSIVE up(id, depth) AS (
+             SELECT parent_task_id, 1 FROM tasks WHERE id = ?1 AND parent_task_id IS NOT NULL
+             UNION ALL
+             SELECT t.parent_task_id, up.depth + 1
+             FROM tasks t JOIN up ON t.id = up.id
+             WHERE t.parent_task_id IS NOT NULL AND up.depth < ?2
+         )
+         SELECT id FROM up ORDER BY depth",
+    )?;
+    let ids = stmt.query_map(params![id, WALK_LIMIT], |r| r.get(0))?;
+    Ok(ids.collect::<Result<_, _>>()?)
+}
+
+// Every task below `id`, deepest first, with its depth below `id`.
+fn descendants(conn: &Connection, id: &str) -> Result<Vec<(String, i64)>, AuralisError> {
+    let mut stmt = conn.prepare(
+        "WITH RECURSIVE down(id, depth) AS (
+             SELECT id, 1 FROM tasks WHERE parent_task_id = ?1
+             UNION ALL
+             SELECT t.id, down.depth + 1
+             FROM tasks t JOIN down ON t.parent_task_id = down.id
+             WHERE down.depth < ?2
+         )
+         SELECT id, depth FROM down ORDER BY depth DESC",
+    )?;
+    let rows = stmt.query_map(params![id, WALK_LIMIT], |r| Ok((r.get(0)?, r.get(1)?)))?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+// Whether any of `ids` waits directly on any of `others`.
+fn depends_on_any(conn: &Connection, ids: &[&str], others: &[&str]) -> Result<bool, AuralisError> {
+    let mut stmt = conn.prepare(
+        "SELECT EXISTS(SELECT 1 FROM task_dependencies WHERE task_id = ?1 AND depends_on_id = ?2)",
+    )?;
+    for id in ids {
+        for other in others {
+            if stmt.query_row(params![id, other], |r| r.get(0))? {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn depth_error() -> AuralisError {
+    AuralisError::validation(format!("Subtasks can only be nested {MAX_DEPTH} levels deep"))
+}
+
+/// Adds a subtask under `parent_id`, in the parent's area and project.
+pub fn add(conn: &mut Connection, parent_id: &str, title: &str) -> Result<String, AuralisError> {
+    let tx = conn.transaction()?;
+    let parent = tasks::get(&tx, parent_id)?;
+    if parent.status == TaskStatus::Done {
+        return Err(AuralisError::validation("Reopen the task before adding subtasks to it"));
+    }
+    if ancestors(&tx, parent_id)?.len() + 1 >= MAX_DEPTH {
+        return Err(depth_error());
+    }
+
+    let id = tasks::add(&tx, title, Some(&parent.area_id), parent.project_id.as_deref())?;
+    tx.execute(
+        "UPDATE tasks SET parent_task_id = ?1 WHERE id = ?2",
+        params![parent_id, id],
+    )?;
+    tx.commit()?;
+    Ok(id)
+}
+
+/// Moves a task (with its subtasks) under `parent_id`, into the parent's project,
+/// or back to the top level with `None`.
+pub fn set_parent(conn: &mut Connection, id: &str, parent_id: Option<&str>) -> Result<TaskItem, AuralisError> {
+    let tx = conn.transaction()?;
+    let task = tasks::get(&tx, id)?;
+
+    if let Some(parent_id) = parent_id {
+        let parent = tasks::get(&tx, parent_id)?;
+        let above = ancestors(&tx, parent_id)?;
+        if parent_id == id || above.iter().any(|a| a == id) {
+            return Err(AuralisError::validation("A task cannot be moved under itself or one of its subtasks"));
+        }
+        let below = descendants(&tx, id)?;
+        let height = 1 + below.first().map_or(0, |(_, depth)| *depth) as usize;
+        if above.len() + 1 + height > MAX_DEPTH {
+            return Err(depth_error());
+        }
+        if parent.status == TaskStatus::Done && task.status != TaskStatus::Done {
+            return Err(AuralisError::validation("Reopen the task before adding subtasks to it"));
+        }
+        // The rule dependencies::add enforces, seen from the other side.
+        let subtree: Vec<&str> = std::iter::once(id).chain(below.iter().map(|(d, _)| d.as_str())).collect();
+        let new_above: Vec<&str> = std::iter::once(parent_id).chain(above.iter().map(String::as_str)).collect();
+        if depends_on_any(&tx, &subtree, &new_above)? {
+            return Err(AuralisError::validation("A subtask cannot depend on a task it belongs to"));
+        }
+
+        tx.execute(
+            "UPDATE tasks SET parent_task_id = ?1, project_id = ?2 WHERE id = ?3",
+            params![parent_id, parent.project_id, id],
+        )?;
+        move_subtasks(&tx, id)?;
+    } else {
+        tx.execute("UPDATE tasks SET parent_task_id = NULL WHERE id = ?1", params![id])?;
+    }
+    tx.commit()?;
+
+    tasks::get(conn, id)
+}
+
+/// Refuses to move a subtask to a project other than its parent's.
+pub(crate) fn check_project(conn: &Connection, id: &str, project_id: Option<&str>) -> Result<(), AuralisError> {
+    let parent_project: Option<Option<String>> = conn
+        .query_row(
+            "SELECT p.project_id FROM tasks t JOIN tasks p ON p.id = t.parent_task_id WHERE t.id = ?1",
+            params![id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    match parent_project {
+        Some(parent) if parent.as_deref() != project_id => Err(AuralisError::validation(
+            "A subtask stays in its parent's project. Move the parent, or make the subtask a task of its own first",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Puts every subtask below `id` into `id`'s project.
+pub(crate) fn move_subtasks(conn: &Connection, id: &str) -> Result<(), AuralisError> {
+    conn.execute(
+        "WITH RECURSIVE down(id, depth) AS (
+             SELECT id, 1 FROM tasks WHERE parent_task_id = ?1
+             UNION ALL
+             SELECT t.id, down.depth + 1
+             FROM tasks t JOIN down ON t.parent_task_id = down.id
+             WHERE down.depth < ?2
+         )
+         UPDATE tasks
+         SET project_id = (SELECT project_id FROM tasks WHERE id = ?1)
+         WHERE id IN (SELECT id FROM down)",
+        params![id, WALK_LIMIT],
+    )?;
+    Ok(())
+}
+
+/// Reopens completed parents of a task that was just reopened.
+pub(crate) fn reopen_ancestors(conn: &Connection, id: &str) -> Result<(), AuralisError> {
+    for ancestor in ancestors(conn, id)? {
+        conn.execute(
+            "UPDATE tasks SET status = 'todo', completed_at = NULL WHERE id = ?1 AND status = 'done'",
+            params![ancestor],
+        )?;
+    }
+    Ok(())
+}
+
+/// Completes a task together with all of its open subtasks. Repeating subtasks end
+/// with their parent rather than starting another round; a repeating parent still
+/// gets its next instance. Tasks waiting on any of them are reported once unblocked.
+pub fn complete(conn: &mut Connection, id: &str) -> Result<StatusChange, AuralisError> {
+    let tx = conn.transaction()?;
+    tasks::get(&tx, id)?;
+
+    let below = descendants(&tx, id)?;
+    let mut closing: Vec<String> = below.iter().map(|(child, _)| child.clone()).collect();
+    closing.push(id.to_string());
+    let waiting = dependencies::blocked_dependents(&tx, &closing)?;
+
+    // Deepest first, so no task is closed before its own subtasks.
+    for (child, _) in below {
+        tx.execute(
+            "UPDATE tasks
+             SET status = 'done', completed_at = (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
+             WHERE id = ?1 AND status IN ('todo', 'doing')",
+            params![child],
+        )?;
+    }
+    let next_id = tasks::set_status_in(&tx, id, TaskStatus::Done)?;
+    let unblocked = dependencies::unblocked(&tx, &waiting)?;
+
+    tx.commit()?;
+    Ok(StatusChange { next_id, unblocked })
+}
+
+fn checklist_counts(conn: &Connection, project_id: Option<&str>) -> Result<HashMap<String, Progress>, AuralisError> {
+    let mut stmt = conn.prepare(
+        "SELECT c.task_id, SUM(c.done), COUNT(1)
+         FROM checklist_items c JOIN tasks t ON t.id = c.task_id
+         WHERE t.project_id IS ?1
+         GROUP BY c.task_id",
+    )?;
+    let rows = stmt.query_map(params![project_id], |r| {
+        Ok((r.get::<_, String>(0)?, Progress { done: r.get(1)?, total: r.get(2)? }))
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+fn node(task: TaskItem, children_of: &mut HashMap<String, Vec<TaskItem>>, checklists: &HashMap<String, Progress>) -> TaskNode {
+    let children: Vec<TaskNode> = children_of
+        .remove(&task.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| node(child, children_of, checklists))
+        .collect();
+
+    let checklist = checklists.get(&task.id).copied().unwrap_or_default();
+    let mut progress = checklist;
+    for child in &children {
+        progress.total += 1 + child.progress.total;
+        progress.done += (child.task.status == TaskStatus::Done) as i64 + child.progress.done;
+    }
+
+    TaskNode {
+        task,
+        checklist,
+        progress,
+        children,
+    }
+}
+
+/// The tasks of a project (or, with `None`, those in no project) as a tree, oldest
+/// first at each level, with checklist and subtask progress rolled up.
+pub fn tree(conn: &Connection, project_id: Option<&str>) -> Result<Vec<TaskNode>, AuralisError> {
+    if let Some(project_id) = project_id {
+        let found = conn
+            .query_row("SELECT 1 FROM projects WHERE id = ?1", params![project_id], |_| Ok(()))
+            .optional()?;
+        if found.is_none() {
+            return Err(AuralisError::not_found("Project"));
+        }
+    }
+
+    let items = tasks::select(conn, "project_id IS ?1 ORDER BY created_at, rowid", params![project_id])?;
+    let checklists = checklist_counts(conn, project_id)?;
+
+    let ids: HashSet<String> = items.iter().map(|t| t.id.clone()).collect();
+    let mut roots = Vec::new();
+    let mut children_of: HashMap<String, Vec<TaskItem>> = HashMap::new();
+    for task in items {
+        match task.parent_task_id.clone().filter(|p| ids.contains(p)) {
+            Some(parent) => children_of.entry(parent).or_default().push(task),
+            None => roots.push(task),
+        }
+    }
+
+    Ok(roots
+        .into_iter()
+        .map(|task| node(task, &mut children_of, &checklists))
+        .collect())
+}
@@ -0,0 +1,212 @@
+//! Tags: free-form labels shared by tasks, notes, projects and inbox items.
+//! `tags` holds the names; `entity_tags` says which entity carries which tag.
+
+use crate::domain::TagTarget;
+use crate::error::AuralisError;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+
+const MAX_NAME: usize = 64;
+
+#[derive(Serialize, Debug)]
+pub struct TagItem {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+    pub usage_count: i64, // entities carrying the tag
+}
+
+fn tag_from_row(row: &Row) -> rusqlite::Result<TagItem> {
+    Ok(TagItem {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        created_at: row.get(2)?,
+        usage_count: row.get(3)?,
+    })
+}
+
+const SELECT: &str = "SELECT t.id, t.name, t.created_at,
+                             (SELECT COUNT(1) FROM entity_tags et WHERE et.tag_id = t.id)
+                      FROM tags t";
+
+/// SQL condition for "the row whose id is in `id_column` carries the tag named or
+/// identified by parameter `?{param}`", for the list filters.
+pub(crate) fn filter(kind: TagTarget, id_column: &str, param: usize) -> String {
+    format!(
+        "EXISTS (SELECT 1 FROM entity_tags et JOIN tags t ON t.id = et.tag_id
+                 WHERE et.entity_kind = '{kind}' AND et.entity_id = {id_column}
+                   AND (t.id = ?{param} OR t.name = ?{param} COLLATE NOCASE))"
+    )
+}
+
+/// Trims a tag name, dropping a leading '#' (`#waiting` and `waiting` are the same
+/// tag). `@` is kept, as in `@phone`.
+fn clean_name(name: &str) -> Result<String, AuralisError> {
+    let name = name.trim();
+    let name = name.strip_prefix('#').unwrap_or(name).trim();
+    if name.is_empty() {
+        return Err(AuralisError::validation("Tag name cannot be empty"));
+    }
+    if name.chars().count() > MAX_NAME {
+        return Err(AuralisError::validation(format!("Tag name cannot be longer than {MAX_NAME} characters")));
+    }
+    if name.contains(['\n', '\r', ',']) {
+        return Err(AuralisError::validation("Tag name cannot contain commas or line breaks"));
+    }
+    Ok(name.to_string())
+}
+
+fn entity_table(kind: TagTarget) -> &'static str {
+    match kind {
+        TagTarget::Task => "tasks",
+        TagTarget::Note => "notes",
+        TagTarget::Project => "projects",
+        TagTarget::Inbox => "inbox_items",
+    }
+}
+
+fn ensure_entity(conn: &Connection, kind: TagTarget, id: &str) -> Result<(), AuralisError> {
+    let found = conn
+        .query_row(
+            &format!("SELECT 1 FROM {} WHERE id = ?1", entity_table(kind)),
+            params![id],
+            |_| Ok(()),
+        )
+        .optional()?;
+    if found.is_none() {
+        return Err(AuralisError::not_found(match kind {
+            TagTarget::Task => "Task",
+            TagTarget::Note => "Note",
+            TagTarget::Project => "Project",
+            TagTarget::Inbox => "Inbox item",
+        }));
+    }
+    Ok(())
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<TagItem, AuralisError> {
+    conn.query_row(&format!("{SELECT} WHERE t.id = ?1"), params![id], tag_from_row)
+        .optional()?
+        .ok_or_else(|| AuralisError::not_found("Tag"))
+}
+
+fn find_by_name(conn: &Connection, name: &str) -> Result<Option<TagItem>, AuralisError> {
+    Ok(conn
+        .query_row(&format!("{SELECT} WHERE t.name = ?1 COLLATE NOCASE"), params![name], tag_from_row)
+        .optional()?)
+}
+
+/// All tags, by name.
+pub fn list(conn: &Connection) -> Result<Vec<TagItem>, AuralisError> {
+    let mut stmt = conn.prepare(&format!("{SELECT} ORDER BY t.name COLLATE NOCASE"))?;
+    let items = stmt.query_map([], tag_from_row)?;
+    Ok(items.collect::<Result<_, _>>()?)
+}
+
+/// The tags on one entity, by name.
+pub fn for_entity(conn: &Connection, kind: TagTarget, id: &str) -> Result<Vec<TagItem>, AuralisError> {
+    let mut stmt = conn.prepare(&format!(
+        "{SELECT} JOIN entity_tags e ON e.tag_id = t.id
+         WHERE e.entity_kind = ?1 AND e.entity_id = ?2
+         ORDER BY t.name COLLATE NOCASE"
+    ))?;
+    let items = stmt.query_map(params![kind, id], tag_from_row)?;
+    Ok(items.collect::<Result<_, _>>()?)
+}
+
+/// Tags an entity, creating the tag if no tag has that name yet (names match
+/// regardless of case). Tagging twice is a no-op.
+pub fn add(conn: &Connection, kind: TagTarget, entity_id: &str, name: &str) -> Result<TagItem, AuralisError> {
+    let name = clean_name(name)?;
+    ensure_entity(conn, kind, entity_id)?;
+
+    let tag_id = match find_by_name(conn, &name)? {
+        Some(tag) => tag.id,
+        None => {
+            let id = format!("tag_{}", uuid::Uuid::new_v4());
+            conn.execute("INSERT INTO tags (id, name) VALUES (?1, ?2)", params![id, name])?;
+            id
+        }
+    };
+
+    conn.execute(
+        "INSERT OR IGNORE INTO entity_tags (id, tag_id, entity_kind, entity_id)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![format!("entity_tag_{}", uuid::Uuid::new_v4()), tag_id, kind, entity_id],
+    )?;
+
+    get(conn, &tag_id)
+}
+
+/// Gives entity `to` every tag that `from` has.
+pub(crate) fn copy(conn: &Connection, from: (TagTarget, &str), to: (TagTarget, &str)) -> Result<(), AuralisError> {
+    let mut stmt = conn.prepare("SELECT tag_id FROM entity_tags WHERE entity_kind = ?1 AND entity_id = ?2")?;
+    let tag_ids: Vec<String> = stmt
+        .query_map(params![from.0, from.1], |r| r.get(0))?
+        .collect::<Result<_, _>>()?;
+
+    for tag_id in tag_ids {
+        conn.execute(
+            "INSERT OR IGNORE INTO entity_tags (id, tag_id, entity_kind, entity_id)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![format!("entity_tag_{}", uuid::Uuid::new_v4()), tag_id, to.0, to.1],
+        )?;
+    }
+    Ok(())
+}
+
+/// Takes a tag off an entity. The tag itself stays, even when nothing uses it.
+pub fn remove(conn: &Connection, kind: TagTarget, entity_id: &str, tag_id: &str) -> Result<(), AuralisError> {
+    let removed = conn.execute(
+        "DELETE FROM entity_tags WHERE tag_id = ?1 AND entity_kind = ?2 AND entity_id = ?3",
+        params![tag_id, kind, entity_id],
+    )?;
+    if removed == 0 {
+        return Err(AuralisError::not_found("Tag"));
+    }
+    Ok(())
+}
+
+/// Renames a tag everywhere. Renaming onto another tag's name is refused; use
+/// [`merge`] for that.
+pub fn rename(conn: &Connection, id: &str, name: &str) -> Result<TagItem, AuralisError> {
+    let name = clean_name(name)?;
+    get(conn, id)?;
+
+    if let Some(other) = find_by_name(conn, &name)?.filter(|t| t.id != id) {
+        return Err(AuralisError::validation(format!(
+            "A tag named '{}' already exists; merge the tags instead",
+            other.name
+        )));
+    }
+
+    conn.execute("UPDATE tags SET name = ?1 WHERE id = ?2", params![name, id])?;
+    get(conn, id)
+}
+
+/// Moves everything tagged `source_id` over to `target_id`, then deletes the source tag.
+pub fn merge(conn: &Connection, source_id: &str, target_id: &str) -> Result<TagItem, AuralisError> {
+    if source_id == target_id {
+        return Err(AuralisError::validation("Cannot merge a tag into itself"));
+    }
+    get(conn, source_id)?;
+    get(conn, target_id)?;
+
+    // Entities carrying both keep their existing row for the target.
+    conn.execute(
+        "UPDATE OR IGNORE entity_tags SET tag_id = ?1 WHERE tag_id = ?2",
+        params![target_id, source_id],
+    )?;
+    conn.execute("DELETE FROM tags WHERE id = ?1", params![source_id])?;
+
+    get(conn, target_id)
+}
+
+/// Deletes a tag and takes it off everything that carried it.
+pub fn delete(conn: &Connection, id: &str) -> Result<(), AuralisError> {
+    let deleted = conn.execute("DELETE FROM tags WHERE id = ?1", params![id])?;
+    if deleted == 0 {
+        return Err(AuralisError::not_found("Tag"));
+    }
+    Ok(())
+}
@@ -0,0 +1,637 @@
+use crate::datetime;
+use crate::domain::{LinkKind, Priority, TagTarget, TaskStatus};
+use crate::error::AuralisError;
+use crate::quick_add::{self, QuickAdd, Sigil};
+use crate::recurrence::Recurrence;
+use crate::store::dependencies::{self, BLOCKED_SQL};
+use crate::store::{checklists, links, subtasks, tags};
+use crate::task_query;
+use chrono::{Local, NaiveDateTime, Utc};
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+/// Longest estimate a task can carry: 1000 hours.
+pub const MAX_ESTIMATE_MINUTES: i64 = 1000 * 60;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TaskItem {
+    pub id: String,
+    pub area_id: String,
+    pub project_id: Option<String>,
+    pub title: String,
+    pub status: TaskStatus,
+    pub priority: Priority,
+    pub due_at: Option<String>,
+    pub scheduled_at: Option<String>,
+    pub estimate_minutes: Option<i64>,
+    pub notes: Option<String>,
+    pub recurrence: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    pub parent_task_id: Option<String>,
+    /// Waiting on a task that is not done yet (see `store::dependencies`).
+    pub blocked: bool,
+    /// Last edit to the task itself (not its checklist, time or dependencies).
+    pub updated_at: String,
+}
+
+/// What [`set_status`] changed besides the task itself.
+#[derive(Serialize, Default, Debug)]
+pub struct StatusChange {
+    /// The next instance of a repeating task that was completed.
+    pub next_id: Option<String>,
+    /// Tasks that were waiting on this one and now have nothing left to wait for.
+    pub unblocked: Vec<TaskItem>,
+}
+
+/// Partial update for [`update`]. Omitted fields are left alone; `null` clears
+/// a nullable column. Status is changed through [`set_status`], which also
+/// handles completion time and repeating tasks.
+#[derive(Deserialize, Default)]
+pub struct TaskPatch {
+    pub title: Option<String>,
+    pub area_id: Option<String>,
+    #[serde(default, deserialize_with = "crate::patch::nullable")]
+    pub project_id: Option<Option<String>>,
+    pub priority: Option<Priority>,
+    #[serde(default, deserialize_with = "crate::patch::nullable")]
+    pub due_at: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::patch::nullable")]
+    pub scheduled_at: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::patch::nullable")]
+    pub estimate_minutes: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "crate::patch::nullable")]
+    pub notes: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::patch::nullable")]
+    pub recurrence: Option<Option<String>>,
+}
+
+fn task_from_row(row: &Row) -> rusqlite::Result<TaskItem> {
+    Ok(TaskItem {
+        id: row.get(0)?,
+        area_id: row.get(1)?,
+        project_id: row.get(2)?,
+        title: row.get(3)?,
+        status: row.get(4)?,
+        priority: row.get(5)?,
+        due_at: row.get(6)?,
+        scheduled_at: row.get(7)?,
+        estimate_minutes: row.get(8)?,
+        notes: row.get(9)?,
+        recurrence: row.get(10)?,
+        created_at: row.get(11)?,
+        completed_at: row.get(12)?,
+        parent_task_id: row.get(13)?,
+        blocked: row.get(14)?,
+        updated_at: row.get(15)?,
+    })
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<TaskItem, AuralisError> {
+    conn.query_row(
+        &format!(
+            "SELECT id, area_id, project_id, title, status, priority, due_at, scheduled_at,
+                    estimate_minutes, notes, recurrence, created_at, completed_at, parent_task_id,
+                    {BLOCKED_SQL}, updated_at
+             FROM tasks
+             WHERE id = ?1"
+        ),
+        params![id],
+        task_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AuralisError::not_found("Task"))
+}
+
+fn exists(conn: &Connection, table: &str, id: &str) -> Result<bool, AuralisError> {
+    conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM {table} WHERE id = ?1)"),
+        params![id],
+        |r| r.get(0),
+    )
+    .map_err(AuralisError::from)
+}
+
+pub(crate) fn check_estimate(minutes: Option<i64>) -> Result<(), AuralisError> {
+    match minutes {
+        Some(m) if m < 0 => Err(AuralisError::validation("Estimate cannot be negative")),
+        Some(m) if m > MAX_ESTIMATE_MINUTES => Err(AuralisError::validation(format!(
+            "Estimate cannot be more than {} hours",
+            MAX_ESTIMATE_MINUTES / 60
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn normalize_date(value: Option<String>) -> Result<Value, AuralisError> {
+    match value.as_deref().map(str::trim) {
+        Some(v) if !v.is_empty() => Ok(Value::Text(datetime::normalize(v)?)),
+        _ => Ok(Value::Null),
+    }
+}
+
+pub fn add(
+    conn: &Connection,
+    title: &str,
+    area_id: Option<&str>,
+    project_id: Option<&str>,
+) -> Result<String, AuralisError> {
+    let title = title.trim().to_string();
+    if title.is_empty() {
+        return Err(AuralisError::validation("Title cannot be empty"));
+    }
+
+    // Default to your seeded fallback area
+    let area_id = area_id.unwrap_or("area_admin_life");
+
+    let id = format!("task_{}", uuid::Uuid::new_v4());
+
+    conn.execute(
+        "INSERT INTO tasks (id, area_id, project_id, title)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![id, area_id, project_id, title],
+    )?;
+
+    Ok(id)
+}
+
+/// Tasks, newest first, optionally narrowed to a status and/or a tag (by id or name).
+pub fn list(
+    conn: &Connection,
+    status: Option<TaskStatus>,
+    tag: Option<&str>,
+) -> Result<Vec<TaskItem>, AuralisError> {
+    let mut items: Vec<TaskItem> = Vec::new();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, area_id, project_id, title, status, priority,
+                due_at, scheduled_at, estimate_minutes, notes, recurrence,
+                created_at, completed_at, parent_task_id, {BLOCKED_SQL}, updated_at
+         FROM tasks
+         WHERE (?1 IS NULL OR status = ?1)
+           AND (?2 IS NULL OR {})
+         ORDER BY created_at DESC",
+        tags::filter(TagTarget::Task, "tasks.id", 2)
+    ))?;
+
+    let rows = stmt.query_map(params![status, tag], task_from_row)?;
+
+    for r in rows {
+        items.push(r?);
+    }
+
+    Ok(items)
+}
+
+/// Tasks matching a query in the filter language of `crate::task_query`.
+pub fn query(conn: &Connection, query: &str) -> Result<Vec<TaskItem>, AuralisError> {
+    query_at(conn, query, Local::now().naive_local())
+}
+
+/// [`query`], with relative dates read against the local time `now`.
+pub fn query_at(conn: &Connection, query: &str, now: NaiveDateTime) -> Result<Vec<TaskItem>, AuralisError> {
+    let compiled = task_query::compile(query, now)?;
+    select(
+        conn,
+        &format!("{} ORDER BY {}", compiled.conditions, compiled.order_by),
+        params_from_iter(compiled.params),
+    )
+}
+
+/// Tasks matching `condition` (the rest of a `WHERE` clause, `ORDER BY` included).
+pub(crate) fn select(
+    conn: &Connection,
+    condition: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<TaskItem>, AuralisError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, area_id, project_id, title, status, priority,
+                due_at, scheduled_at, estimate_minutes, notes, recurrence,
+                created_at, completed_at, parent_task_id, {BLOCKED_SQL}, updated_at
+         FROM tasks
+         WHERE {condition}"
+    ))?;
+    let items = stmt.query_map(params, task_from_row)?;
+    Ok(items.collect::<Result<_, _>>()?)
+}
+
+/// Sets a task's status. Completing a task reports the tasks it unblocked, and
+/// for a repeating task the id of its next instance. Reopening a subtask reopens
+/// its completed parents too.
+pub fn set_status(
+    conn: &mut Connection,
+    id: &str,
+    status: TaskStatus,
+) -> Result<StatusChange, AuralisError> {
+    let tx = conn.transaction()?;
+    let waiting = dependencies::blocked_dependents(&tx, &[id.to_string()])?;
+    let next_id = set_status_in(&tx, id, status)?;
+    let unblocked = dependencies::unblocked(&tx, &waiting)?;
+    tx.commit()?;
+    Ok(StatusChange { next_id, unblocked })
+}
+
+/// [`set_status`] inside the caller's transaction.
+pub(crate) fn set_status_in(
+    conn: &Connection,
+    id: &str,
+    status: TaskStatus,
+) -> Result<Option<String>, AuralisError> {
+    // The next instance goes in first, so closing this one never trips the
+    // "active project must keep an open task" rule.
+    let next_id = if status == TaskStatus::Done {
+        spawn_next_instance(conn, id)?
+    } else {
+        None
+    };
+
+    // When marking done, set completed_at; otherwise clear it.
+    let updated = if status == TaskStatus::Done {
+        conn.execute(
+            "UPDATE tasks
+             SET status = ?1,
+                 completed_at = (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
+             WHERE id = ?2",
+            params![status, id],
+        )
+    } else {
+        conn.execute(
+            "UPDATE tasks
+             SET status = ?1,
+                 completed_at = NULL
+             WHERE id = ?2",
+            params![status, id],
+        )
+    }?;
+
+    if updated == 0 {
+        return Err(AuralisError::not_found("Task"));
+    }
+
+    if matches!(status, TaskStatus::Todo | TaskStatus::Doing) {
+        subtasks::reopen_ancestors(conn, id)?;
+    }
+
+    Ok(next_id)
+}
+
+// Copies a repeating task forward to its next occurrence. The series is anchored on
+// due_at (or scheduled_at), and occurrences already in the past are skipped.
+fn spawn_next_instance(conn: &Connection, id: &str) -> Result<Option<String>, AuralisError> {
+    type Row = (
+        TaskStatus,
+        String,
+        Option<String>,
+        String,
+        Priority,
+        Option<String>,
+        Option<String>,
+        Option<i64>,
+        Option<String>,
+        Option<String>,
+    );
+
+    let row: Option<Row> = conn
+        .query_row(
+            "SELECT status, area_id, project_id, title, priority, due_at, scheduled_at,
+                    estimate_minutes, notes, recurrence
+             FROM tasks
+             WHERE id = ?1",
+            params![id],
+            |r| {
+                Ok((
+                    r.get(0)?,
+                    r.get(1)?,
+                    r.get(2)?,
+                    r.get(3)?,
+                    r.get(4)?,
+                    r.get(5)?,
+                    r.get(6)?,
+                    r.get(7)?,
+                    r.get(8)?,
+                    r.get(9)?,
+                ))
+            },
+        )
+        .optional()?;
+
+    let (status, area_id, project_id, title, priority, due_at, scheduled_at, estimate, notes, rule) =
+        match row {
+            Some(v) => v,
+            None => return Err(AuralisError::not_found("Task")),
+        };
+
+    let rule = match (status, rule) {
+        (TaskStatus::Done, _) | (_, None) => return Ok(None),
+        (_, Some(r)) => Recurrence::parse(&r)?,
+    };
+
+    let due = due_at.as_deref().map(datetime::parse).transpose()?;
+    let scheduled = scheduled_at.as_deref().map(datetime::parse).transpose()?;
+    let now = Utc::now();
+    let anchor = due.or(scheduled).unwrap_or(now);
+
+    let next = match rule.next_after(anchor, anchor.max(now), Local) {
+        Some(n) => n,
+        None => return Ok(None),
+    };
+    let delta = next - anchor;
+
+    let (next_due, next_scheduled) = match (due, scheduled) {
+        (None, None) => (Some(next), None),
+        (d, s) => (d.map(|d| d + delta), s.map(|s| s + delta)),
+    };
+    let next_rule = rule.split_at(anchor, next, Local).1;
+
+    let next_id = format!("task_{}", uuid::Uuid::new_v4());
+
+    conn.execute(
+        "INSERT INTO tasks (id, area_id, project_id, title, priority, due_at, scheduled_at,
+                            estimate_minutes, notes, recurrence, parent_task_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
+                 (SELECT parent_task_id FROM tasks WHERE id = ?11))",
+        params![
+            next_id,
+            area_id,
+            project_id,
+            title,
+            priority,
+            next_due.map(datetime::format_utc),
+            next_scheduled.map(datetime::format_utc),
+            estimate,
+            notes,
+            next_rule.map(|r| r.to_string()),
+            id
+        ],
+    )?;
+    // The next instance starts with the same checklist, all unticked.
+    checklists::copy(conn, id, &next_id)?;
+
+    Ok(Some(next_id))
+}
+
+pub fn list_by_project(conn: &Connection, project_id: &str) -> Result<Vec<TaskItem>, AuralisError> {
+    let mut items: Vec<TaskItem> = Vec::new();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, area_id, project_id, title, status, priority,
+                due_at, scheduled_at, estimate_minutes, notes, recurrence,
+                created_at, completed_at, parent_task_id, {BLOCKED_SQL}, updated_at
+         FROM tasks
+         WHERE project_id = ?1
+         ORDER BY created_at DESC"
+    ))?;
+
+    let rows = stmt.query_map([project_id], task_from_row)?;
+
+    for r in rows {
+        items.push(r?);
+    }
+
+    Ok(items)
+}
+
+pub fn set_project(
+    conn: &Connection,
+    id: &str,
+    project_id: Option<&str>, // None to unassign
+) -> Result<(), AuralisError> {
+    subtasks::check_project(conn, id, project_id)?;
+
+    let updated = conn.execute(
+        "UPDATE tasks SET project_id = ?1 WHERE id = ?2",
+        params![project_id, id],
+    )?;
+
+    if updated == 0 {
+        return Err(AuralisError::not_found("Task"));
+    }
+
+    subtasks::move_subtasks(conn, id)
+}
+
+pub fn set_recurrence(
+    conn: &Connection,
+    id: &str,
+    recurrence: Option<&str>, // RRULE text, None to stop repeating
+) -> Result<(), AuralisError> {
+    let recurrence = match recurrence.map(str::trim) {
+        Some(r) if !r.is_empty() => Some(Recurrence::parse(r)?.to_string()),
+        _ => None,
+    };
+
+    let updated = conn.execute(
+        "UPDATE tasks SET recurrence = ?1 WHERE id = ?2",
+        params![recurrence, id],
+    )?;
+
+    if updated == 0 {
+        return Err(AuralisError::not_found("Task"));
+    }
+
+    Ok(())
+}
+
+/// Applies a partial update to a task and returns the stored result. Moving its
+/// subtasks and rewriting links to it happen in the same transaction.
+pub fn update(conn: &mut Connection, id: &str, patch: TaskPatch) -> Result<TaskItem, AuralisError> {
+    let tx = conn.transaction()?;
+    let task = update_in(&tx, id, patch)?;
+    tx.commit()?;
+    Ok(task)
+}
+
+/// [`update`] inside the caller's transaction.
+pub(crate) fn update_in(conn: &Connection, id: &str, patch: TaskPatch) -> Result<TaskItem, AuralisError> {
+    let mut sets: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    let mut renamed = None;
+    if let Some(title) = patch.title {
+        let title = title.trim().to_string();
+        if title.is_empty() {
+            return Err(AuralisError::validation("Title cannot be empty"));
+        }
+        renamed = Some((get(conn, id)?.title, title.clone()));
+        sets.push("title");
+        values.push(Value::Text(title));
+    }
+
+    if let Some(priority) = patch.priority {
+        sets.push("priority");
+        values.push(Value::Text(priority.to_string()));
+    }
+
+    if let Some(due_at) = patch.due_at {
+        sets.push("due_at");
+        values.push(normalize_date(due_at)?);
+    }
+
+    if let Some(scheduled_at) = patch.scheduled_at {
+        sets.push("scheduled_at");
+        values.push(normalize_date(scheduled_at)?);
+    }
+
+    if let Some(estimate) = patch.estimate_minutes {
+        check_estimate(estimate)?;
+        sets.push("estimate_minutes");
+        values.push(estimate.map_or(Value::Null, Value::Integer));
+    }
+
+    if let Some(notes) = patch.notes {
+        sets.push("notes");
+        values.push(
+            notes
+                .filter(|n| !n.trim().is_empty())
+                .map_or(Value::Null, Value::Text),
+        );
+    }
+
+    if let Some(recurrence) = patch.recurrence {
+        let recurrence = match recurrence.as_deref().map(str::trim) {
+            Some(r) if !r.is_empty() => Some(Recurrence::parse(r)?.to_string()),
+            _ => None,
+        };
+        sets.push("recurrence");
+        values.push(recurrence.map_or(Value::Null, Value::Text));
+    }
+
+    if let Some(area_id) = patch.area_id {
+        if !exists(conn, "areas", &area_id)? {
+            return Err(AuralisError::not_found("Area"));
+        }
+        sets.push("area_id");
+        values.push(Value::Text(area_id));
+    }
+
+    let moved = patch.project_id.is_some();
+    if let Some(project_id) = patch.project_id {
+        if let Some(p) = project_id.as_deref() {
+            if !exists(conn, "projects", p)? {
+                return Err(AuralisError::not_found("Project"));
+            }
+        }
+        subtasks::check_project(conn, id, project_id.as_deref())?;
+        sets.push("project_id");
+        values.push(project_id.map_or(Value::Null, Value::Text));
+    }
+
+    if !sets.is_empty() {
+        let assignments: Vec<String> = sets
+            .iter()
+            .enumerate()
+            .map(|(i, col)| format!("{col} = ?{}", i + 1))
+            .collect();
+        let sql = format!(
+            "UPDATE tasks SET {} WHERE id = ?{}",
+            assignments.join(", "),
+            values.len() + 1
+        );
+        values.push(Value::Text(id.to_string()));
+
+        let updated = conn.execute(&sql, params_from_iter(values.iter()))?;
+
+        if updated == 0 {
+            return Err(AuralisError::not_found("Task"));
+        }
+    }
+
+    if moved {
+        subtasks::move_subtasks(conn, id)?;
+    }
+    if let Some((old, new)) = renamed {
+        links::target_renamed(conn, LinkKind::Task, id, &old, &new)?;
+    }
+
+    get(conn, id)
+}
+
+/// Parses quick-add text against the current areas and projects. A project picked
+/// without an area puts the task in that project's area.
+pub fn quick_add_parse(conn: &Connection, text: &str) -> Result<QuickAdd, AuralisError> {
+    let areas = name_index(conn, "SELECT id, name FROM areas WHERE active = 1")?;
+    let projects = name_index(
+        conn,
+        "SELECT id, name FROM projects WHERE status != 'completed'",
+    )?;
+
+    let mut parsed = quick_add::parse(text, Local::now().naive_local(), |sigil, word| {
+        let candidates = match sigil {
+            Sigil::Area => &areas,
+            Sigil::Project => &projects,
+        };
+        quick_add::match_name(word, candidates).map(str::to_string)
+    });
+
+    if parsed.area_id.is_none() {
+        if let Some(project_id) = &parsed.project_id {
+            parsed.area_id = conn
+                .query_row(
+                    "SELECT area_id FROM projects WHERE id = ?1",
+                    params![project_id],
+                    |r| r.get(0),
+                )
+                .optional()?;
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn name_index(conn: &Connection, sql: &str) -> Result<Vec<(String, String)>, AuralisError> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+
+    let mut items = Vec::new();
+    for r in rows {
+        items.push(r?);
+    }
+    Ok(items)
+}
+
+/// Inserts a task from a quick-add parse, falling back to Admin/Life and normal priority.
+pub fn quick_add_insert(
+    conn: &Connection,
+    parsed: &QuickAdd,
+    notes: Option<&str>,
+) -> Result<String, AuralisError> {
+    if parsed.title.trim().is_empty() {
+        return Err(AuralisError::validation("Title cannot be empty"));
+    }
+    check_estimate(parsed.estimate_minutes)?;
+
+    let id = format!("task_{}", uuid::Uuid::new_v4());
+
+    conn.execute(
+        "INSERT INTO tasks (id, area_id, project_id, title, priority, due_at, scheduled_at,
+                            estimate_minutes, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            id,
+            parsed.area_id.as_deref().unwrap_or("area_admin_life"),
+            parsed.project_id,
+            parsed.title,
+            parsed.priority.unwrap_or(Priority::Normal),
+            parsed.due_at,
+            parsed.scheduled_at,
+            parsed.estimate_minutes,
+            notes
+        ],
+    )?;
+
+    Ok(id)
+}
+
+#[derive(Serialize)]
+pub struct QuickAddResult {
+    pub id: String,
+    pub parsed: QuickAdd,
+}
+
+/// Creates a task from inline syntax such as
+/// `Call dentist tomorrow 3pm !high #health +Q4Launch ~30m`.
+pub fn quick_add(conn: &Connection, text: &str) -> Result<QuickAddResult, AuralisError> {
+    let parsed = quick_add_parse(conn, text)?;
+    let id = quick_add_insert(conn, &parsed, None)?;
+    Ok(QuickAddResult { id, parsed })
+}
@@ -0,0 +1,495 @@
+//! Time tracking: entries of time spent on tasks, from the timer or added by hand,
+//! and reports of actual against estimated time. At most one timer runs at a time;
+//! it lives in the database, so it survives the app going away.
+
+use crate::datetime;
+use crate::error::AuralisError;
+use crate::store::agenda::{day_start, parse_date};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How often the running timer is stamped while the app is up.
+pub const HEARTBEAT_SECS: u64 = 60;
+
+// A gap this much longer than the heartbeat means the app was not running.
+const INTERRUPTED_AFTER_SECS: i64 = 5 * 60;
+
+// Longest range one report may cover.
+const MAX_REPORT_DAYS: i64 = 366;
+
+#[derive(Serialize, Debug)]
+pub struct TimeEntry {
+    pub id: String,
+    pub task_id: String,
+    pub task_title: String,
+    pub started_at: String,
+    pub ended_at: Option<String>, // None while the timer runs
+    pub note: Option<String>,
+    /// Set on a running timer found after the app went away: the last moment it was
+    /// known to be running. Stop it there, or resume to count the gap.
+    pub interrupted_at: Option<String>,
+    pub seconds: i64, // up to now for the running timer
+    pub created_at: String,
+}
+
+/// A time entry added by hand.
+#[derive(Deserialize)]
+pub struct TimeEntryInput {
+    pub task_id: String,
+    pub started_at: String,
+    pub ended_at: String,
+    pub note: Option<String>,
+}
+
+/// Partial update for [`update`]. Omitted fields are left alone; setting `ended_at`
+/// on the running timer stops it.
+#[derive(Deserialize, Default)]
+pub struct TimeEntryPatch {
+    pub task_id: Option<String>,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    #[serde(default, deserialize_with = "crate::patch::nullable")]
+    pub note: Option<Option<String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportGroup {
+    Task,
+    Project,
+    Area,
+    Day,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ReportRow {
+    /// Task, project or area id, or the date; None for time on tasks in no project.
+    pub key: Option<String>,
+    pub label: String,
+    pub actual_seconds: i64,
+    /// Summed over the tasks tracked in this row that have an estimate.
+    pub estimate_minutes: Option<i64>,
+    pub task_count: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TimeReport {
+    pub time_zone: Option<String>, // None for the system time zone
+    pub group_by: ReportGroup,
+    pub start_at: String, // UTC bounds of the range, half-open
+    pub end_at: String,
+    /// Days in order, every day of the range included; otherwise most time first.
+    pub rows: Vec<ReportRow>,
+    pub total_seconds: i64,
+}
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<TimeEntry> {
+    Ok(TimeEntry {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        task_title: row.get(2)?,
+        started_at: row.get(3)?,
+        ended_at: row.get(4)?,
+        note: row.get(5)?,
+        interrupted_at: row.get(6)?,
+        seconds: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+fn select(conn: &Connection, condition: &str, params: impl rusqlite::Params) -> Result<Vec<TimeEntry>, AuralisError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT e.id, e.task_id, t.title, e.started_at, e.ended_at, e.note, e.interrupted_at,
+                CAST(ROUND((julianday(COALESCE(e.ended_at, 'now')) - julianday(e.started_at)) * 86400) AS INTEGER),
+                e.created_at
+         FROM time_entries e JOIN tasks t ON t.id = e.task_id
+         WHERE {condition}"
+    ))?;
+    let entries = stmt.query_map(params, entry_from_row)?;
+    Ok(entries.collect::<Result<_, _>>()?)
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<TimeEntry, AuralisError> {
+    select(conn, "e.id = ?1", params![id])?
+        .pop()
+        .ok_or_else(|| AuralisError::not_found("Time entry"))
+}
+
+/// The running timer, if any.
+pub fn running(conn: &Connection) -> Result<Option<TimeEntry>, AuralisError> {
+    Ok(select(conn, "e.ended_at IS NULL", [])?.pop())
+}
+
+fn now() -> String {
+    datetime::format_utc(Utc::now())
+}
+
+fn clean_note(note: Option<String>) -> Option<String> {
+    note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty())
+}
+
+fn ensure_task(conn: &Connection, task_id: &str) -> Result<(), AuralisError> {
+    conn.query_row("SELECT 1 FROM tasks WHERE id = ?1", params![task_id], |_| Ok(()))
+        .optional()?
+        .ok_or_else(|| AuralisError::not_found("Task"))
+}
+
+fn check_span(started_at: &str, ended_at: &str) -> Result<(), AuralisError> {
+    if ended_at < started_at {
+        return Err(AuralisError::validation("A time entry cannot end before it starts"));
+    }
+    if ended_at > now().as_str() {
+        return Err(AuralisError::validation("A time entry cannot end in the future"));
+    }
+    Ok(())
+}
+
+/// Starts the timer on a task. A timer running on another task is stopped first;
+/// one already running on this task is returned as it is.
+pub fn start(conn: &mut Connection, task_id: &str) -> Result<TimeEntry, AuralisError> {
+    let tx = conn.transaction()?;
+    ensure_task(&tx, task_id)?;
+
+    if let Some(current) = running(&tx)? {
+        if current.task_id == task_id {
+            return Ok(current);
+        }
+        stop(&tx, None)?;
+    }
+
+    let id = format!("time_{}", uuid::Uuid::new_v4());
+    let started_at = now();
+    tx.execute(
+        "INSERT INTO time_entries (id, task_id, started_at, last_seen_at) VALUES (?1, ?2, ?3, ?3)",
+        params![id, task_id, started_at],
+    )?;
+    let entry = get(&tx, &id)?;
+    tx.commit()?;
+    Ok(entry)
+}
+
+/// Stops the running timer, now or at `ended_at` (e.g. its `interrupted_at`).
+pub fn stop(conn: &Connection, ended_at: Option<&str>) -> Result<TimeEntry, AuralisError> {
+    let current = running(conn)?.ok_or_else(|| AuralisError::not_found("Running timer"))?;
+    let ended_at = match ended_at {
+        Some(at) => datetime::normalize(at)?,
+        None => now(),
+    };
+    check_span(&current.started_at, &ended_at)?;
+
+    conn.execute(
+        "UPDATE time_entries
+         SET ended_at = ?1, last_seen_at = NULL, interrupted_at = NULL
+         WHERE id = ?2",
+        params![ended_at, current.id],
+    )?;
+    get(conn, &current.id)
+}
+
+/// Keeps an interrupted timer running, counting the time the app was away.
+pub fn resume(conn: &Connection) -> Result<TimeEntry, AuralisError> {
+    let current = running(conn)?.ok_or_else(|| AuralisError::not_found("Running timer"))?;
+    conn.execute(
+        "UPDATE time_entries SET interrupted_at = NULL, last_seen_at = ?1 WHERE id = ?2",
+        params![now(), current.id],
+    )?;
+    get(conn, &current.id)
+}
+
+/// Records that the running timer is still being watched.
+pub fn heartbeat(conn: &Connection) -> Result<(), AuralisError> {
+    conn.execute(
+        "UPDATE time_entries SET last_seen_at = ?1 WHERE ended_at IS NULL",
+        params![now()],
+    )?;
+    Ok(())
+}
+
+/// Run at launch, before the first [`heartbeat`]: marks a timer left running by a
+/// previous session as interrupted. Returns the running timer, if any.
+pub fn recover(conn: &Connection) -> Result<Option<TimeEntry>, AuralisError> {
+    recover_at(conn, Utc::now())
+}
+
+/// [`recover`], as if launched at `now`.
+pub fn recover_at(conn: &Connection, now: DateTime<Utc>) -> Result<Option<TimeEntry>, AuralisError> {
+    let cutoff = datetime::format_utc(now - Duration::seconds(INTERRUPTED_AFTER_SECS));
+    conn.execute(
+        "UPDATE time_entries
+         SET interrupted_at = COALESCE(last_seen_at, started_at)
+         WHERE ended_at IS NULL
+           AND interrupted_at IS NULL
+           AND COALESCE(last_seen_at, started_at) < ?1",
+        params![cutoff],
+    )?;
+    running(conn)
+}
+
+/// Adds a finished entry by hand.
+pub fn add(conn: &Connection, input: TimeEntryInput) -> Result<TimeEntry, AuralisError> {
+    ensure_task(conn, &input.task_id)?;
+    let started_at = datetime::normalize(&input.started_at)?;
+    let ended_at = datetime::normalize(&input.ended_at)?;
+    check_span(&started_at, &ended_at)?;
+
+    let id = format!("time_{}", uuid::Uuid::new_v4());
+    conn.execute(
+        "INSERT INTO time_entries (id, task_id, started_at, ended_at, note)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![id, input.task_id, started_at, ended_at, clean_note(input.note)],
+    )?;
+    get(conn, &id)
+}
+
+/// Applies a partial update to an entry and returns the stored result.
+pub fn update(conn: &Connection, id: &str, patch: TimeEntryPatch) -> Result<TimeEntry, AuralisError> {
+    let entry = get(conn, id)?;
+
+    let task_id = match patch.task_id {
+        Some(task_id) => {
+            ensure_task(conn, &task_id)?;
+            task_id
+        }
+        None => entry.task_id,
+    };
+    let started_at = match patch.started_at {
+        Some(at) => datetime::normalize(&at)?,
+        None => entry.started_at,
+    };
+    let ended_at = match patch.ended_at {
+        Some(at) => Some(datetime::normalize(&at)?),
+        None => entry.ended_at,
+    };
+    match &ended_at {
+        Some(ended_at) => check_span(&started_at, ended_at)?,
+        None if started_at > now() => {
+            return Err(AuralisError::validation("The running timer cannot start in the future"));
+        }
+        None => {}
+    }
+    let note = match patch.note {
+        Some(note) => clean_note(note),
+        None => entry.note,
+    };
+
+    conn.execute(
+        "UPDATE time_entries
+         SET task_id = ?1, started_at = ?2, ended_at = ?3, note = ?4,
+             last_seen_at = CASE WHEN ?3 IS NULL THEN last_seen_at END,
+             interrupted_at = CASE WHEN ?3 IS NULL THEN interrupted_at END
+         WHERE id = ?5",
+        params![task_id, started_at, ended_at, note, id],
+    )?;
+    get(conn, id)
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<(), AuralisError> {
+    let deleted = conn.execute("DELETE FROM time_entries WHERE id = ?1", params![id])?;
+    if deleted == 0 {
+        return Err(AuralisError::not_found("Time entry"));
+    }
+    Ok(())
+}
+
+/// Entries overlapping `from`..`to` (either may be omitted), optionally for one
+/// task, newest first.
+pub fn list(
+    conn: &Connection,
+    task_id: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Vec<TimeEntry>, AuralisError> {
+    let from = from.map(datetime::normalize).transpose()?;
+    let to = to.map(datetime::normalize).transpose()?;
+    select(
+        conn,
+        "(?1 IS NULL OR e.task_id = ?1)
+           AND (?2 IS NULL OR COALESCE(e.ended_at, ?4) > ?2)
+           AND (?3 IS NULL OR e.started_at < ?3)
+         ORDER BY e.started_at DESC",
+        params![task_id, from, to, now()],
+    )
+}
+
+/// Time tracked on the days `start` through `end` (inclusive; just `start` when
+/// omitted), grouped by task, project, area or day, next to the estimates of the
+/// tasks involved. Entries crossing the range or a day boundary are split there.
+/// `time_zone` is an IANA name such as `Europe/Berlin`.
+pub fn report(
+    conn: &Connection,
+    start: &str,
+    end: Option<&str>,
+    group_by: ReportGroup,
+    time_zone: Option<&str>,
+) -> Result<TimeReport, AuralisError> {
+    let first = parse_date(start)?;
+    let last = end.map(parse_date).transpose()?.unwrap_or(first);
+    if last < first {
+        return Err(AuralisError::validation("End date cannot be before the start date"));
+    }
+    if (last - first).num_days() >= MAX_REPORT_DAYS {
+        return Err(AuralisError::validation(format!(
+            "A report can cover at most {MAX_REPORT_DAYS} days"
+        )));
+    }
+
+    match time_zone.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(name) => {
+            let tz: chrono_tz::Tz = name
+                .parse()
+                .map_err(|_| AuralisError::validation(format!("Unknown time zone '{name}'")))?;
+            build_report(conn, first, last, group_by, tz, Some(name.to_string()))
+        }
+        None => build_report(conn, first, last, group_by, Local, None),
+    }
+}
+
+// One entry as the report needs it.
+struct Tracked {
+    task_id: String,
+    title: String,
+    estimate_minutes: Option<i64>,
+    project: Option<(String, String)>,
+    area: (String, String),
+}
+
+#[derive(Default)]
+struct Bucket {
+    label: String,
+    seconds: i64,
+    estimates: HashMap<String, Option<i64>>, // by task
+}
+
+fn build_report<Tz: TimeZone>(
+    conn: &Connection,
+    first: NaiveDate,
+    last: NaiveDate,
+    group_by: ReportGroup,
+    tz: Tz,
+    time_zone: Option<String>,
+) -> Result<TimeReport, AuralisError> {
+    let days: Vec<(String, DateTime<Utc>, DateTime<Utc>)> = first
+        .iter_days()
+        .take_while(|d| *d <= last)
+        .map(|date| {
+            let next = date.succ_opt().unwrap_or(date);
+            (date.format("%Y-%m-%d").to_string(), day_start(date, &tz), day_start(next, &tz))
+        })
+        .collect();
+    let range_start = days[0].1;
+    let range_end = days[days.len() - 1].2;
+
+    let mut stmt = conn.prepare(
+        "SELECT e.task_id, t.title, t.estimate_minutes, p.id, p.name, a.id, a.name,
+                e.started_at, COALESCE(e.ended_at, ?3)
+         FROM time_entries e
+         JOIN tasks t ON t.id = e.task_id
+         JOIN areas a ON a.id = t.area_id
+         LEFT JOIN projects p ON p.id = t.project_id
+         WHERE e.started_at < ?2 AND COALESCE(e.ended_at, ?3) > ?1
+         ORDER BY e.started_at",
+    )?;
+    let rows = stmt.query_map(
+        params![
+            datetime::format_utc(range_start),
+            datetime::format_utc(range_end),
+            now()
+        ],
+        |r| {
+            let project_id: Option<String> = r.get(3)?;
+            let project_name: Option<String> = r.get(4)?;
+            Ok((
+                Tracked {
+                    task_id: r.get(0)?,
+                    title: r.get(1)?,
+                    estimate_minutes: r.get(2)?,
+                    project: project_id.zip(project_name),
+                    area: (r.get(5)?, r.get(6)?),
+                },
+                r.get::<_, String>(7)?,
+                r.get::<_, String>(8)?,
+            ))
+        },
+    )?;
+
+    let mut order: Vec<Option<String>> = Vec::new();
+    let mut buckets: HashMap<Option<String>, Bucket> = HashMap::new();
+    if group_by == ReportGroup::Day {
+        for (date, _, _) in &days {
+            order.push(Some(date.clone()));
+            buckets.insert(
+                Some(date.clone()),
+                Bucket {
+                    label: date.clone(),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    for row in rows {
+        let (entry, started_at, ended_at) = row?;
+        let started_at = datetime::parse(&started_at)?.max(range_start);
+        let ended_at = datetime::parse(&ended_at)?.min(range_end);
+
+        let mut add = |key: Option<String>, label: &str, seconds: i64| {
+            if seconds <= 0 {
+                return;
+            }
+            let bucket = buckets.entry(key.clone()).or_insert_with(|| {
+                order.push(key);
+                Bucket {
+                    label: label.to_string(),
+                    ..Default::default()
+                }
+            });
+            bucket.seconds += seconds;
+            bucket.estimates.insert(entry.task_id.clone(), entry.estimate_minutes);
+        };
+
+        let seconds = (ended_at - started_at).num_seconds();
+        match group_by {
+            ReportGroup::Task => add(Some(entry.task_id.clone()), &entry.title, seconds),
+            ReportGroup::Project => match &entry.project {
+                Some((id, name)) => add(Some(id.clone()), name, seconds),
+                None => add(None, "No project", seconds),
+            },
+            ReportGroup::Area => add(Some(entry.area.0.clone()), &entry.area.1, seconds),
+            ReportGroup::Day => {
+                for (date, day_start, day_end) in &days {
+                    let overlap = ended_at.min(*day_end) - started_at.max(*day_start);
+                    add(Some(date.clone()), date, overlap.num_seconds());
+                }
+            }
+        }
+    }
+
+    let mut rows: Vec<ReportRow> = order
+        .into_iter()
+        .filter_map(|key| {
+            let bucket = buckets.remove(&key)?;
+            let estimates: Vec<i64> = bucket.estimates.values().flatten().copied().collect();
+            Some(ReportRow {
+                key,
+                label: bucket.label,
+                actual_seconds: bucket.seconds,
+                estimate_minutes: (!estimates.is_empty()).then(|| estimates.iter().sum()),
+                task_count: bucket.estimates.len(),
+            })
+        })
+        .collect();
+    if group_by != ReportGroup::Day {
+        rows.sort_by(|a, b| b.actual_seconds.cmp(&a.actual_seconds).then_with(|| a.label.cmp(&b.label)));
+    }
+
+    Ok(TimeReport {
+        time_zone,
+        group_by,
+        start_at: datetime::format_utc(range_start),
+        end_at: datetime::format_utc(range_end),
+        total_seconds: rows.iter().map(|r| r.actual_seconds).sum(),
+        rows,
+    })
+}
@@ -0,0 +1,287 @@
+//! The GTD weekly review: a guided checklist through the inbox, every active
+//! project, stale tasks, deferred tasks and the coming weeks' calendar, in that
+//! order. Progress is kept in `weekly_reviews` / `weekly_review_items`, so a review
+//! can be left and picked up again; completed reviews make up the history.
+//!
+//! Each step lists what is left to look at, live: processing an inbox item or
+//! editing a stale task takes it off the list. Everything else is ticked off with
+//! [`mark`]. A step can only be left once nothing in it is outstanding.
+
+use crate::datetime;
+use crate::domain::{InboxState, ProjectStatus, ReviewStep};
+use crate::error::AuralisError;
+use crate::store::calendar::{self, CalendarOccurrence};
+use crate::store::inbox::{self, InboxItem};
+use crate::store::project_stats::{self, ProjectStats};
+use crate::store::projects::{self, ProjectItem};
+use crate::store::tasks::{self, TaskItem};
+use chrono::{Duration, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use std::collections::HashSet;
+
+pub const DEFAULT_STALE_DAYS: i64 = 30;
+/// How far ahead the calendar step looks.
+pub const UPCOMING_DAYS: i64 = 14;
+
+#[derive(Serialize, Debug)]
+pub struct WeeklyReview {
+    pub id: String,
+    pub step: ReviewStep,
+    /// Open tasks not edited for this many days count as stale.
+    pub stale_days: i64,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    pub reviewed_items: i64,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReviewItem {
+    /// Unprocessed; cleared by processing it in the inbox.
+    Inbox { item: InboxItem },
+    Project {
+        project: ProjectItem,
+        stats: ProjectStats,
+        reviewed: bool,
+    },
+    Task { task: TaskItem, reviewed: bool },
+    /// Marking an occurrence of a recurring event marks the whole series.
+    Event {
+        occurrence: CalendarOccurrence,
+        reviewed: bool,
+    },
+}
+
+impl ReviewItem {
+    fn entity_id(&self) -> &str {
+        match self {
+            ReviewItem::Inbox { item } => &item.id,
+            ReviewItem::Project { project, .. } => &project.id,
+            ReviewItem::Task { task, .. } => &task.id,
+            ReviewItem::Event { occurrence, .. } => &occurrence.event.id,
+        }
+    }
+
+    fn outstanding(&self) -> bool {
+        match self {
+            ReviewItem::Inbox { .. } => true,
+            ReviewItem::Project { reviewed, .. }
+            | ReviewItem::Task { reviewed, .. }
+            | ReviewItem::Event { reviewed, .. } => !reviewed,
+        }
+    }
+}
+
+/// The review in progress and what its current step lists.
+#[derive(Serialize)]
+pub struct ReviewState {
+    pub review: WeeklyReview,
+    pub items: Vec<ReviewItem>,
+    pub remaining: usize,
+}
+
+const REVIEW_COLUMNS: &str = "id, step, stale_days, started_at, completed_at,
+     (SELECT COUNT(1) FROM weekly_review_items i WHERE i.review_id = weekly_reviews.id)";
+
+fn review_from_row(row: &Row) -> rusqlite::Result<WeeklyReview> {
+    Ok(WeeklyReview {
+        id: row.get(0)?,
+        step: row.get(1)?,
+        stale_days: row.get(2)?,
+        started_at: row.get(3)?,
+        completed_at: row.get(4)?,
+        reviewed_items: row.get(5)?,
+    })
+}
+
+fn open_review(conn: &Connection) -> Result<Option<WeeklyReview>, AuralisError> {
+    Ok(conn
+        .query_row(
+            &format!("SELECT {REVIEW_COLUMNS} FROM weekly_reviews WHERE completed_at IS NULL"),
+            [],
+            review_from_row,
+        )
+        .optional()?)
+}
+
+fn require_open(conn: &Connection) -> Result<WeeklyReview, AuralisError> {
+    open_review(conn)?.ok_or_else(|| AuralisError::not_found("Weekly review in progress"))
+}
+
+pub fn current(conn: &Connection) -> Result<Option<ReviewState>, AuralisError> {
+    open_review(conn)?.map(|review| state(conn, review)).transpose()
+}
+
+/// Starts a review, or picks up the one already in progress (keeping its settings).
+pub fn start(conn: &Connection, stale_days: Option<i64>) -> Result<ReviewState, AuralisError> {
+    if let Some(review) = open_review(conn)? {
+        return state(conn, review);
+    }
+    let stale_days = stale_days.unwrap_or(DEFAULT_STALE_DAYS);
+    if !(1..=365).contains(&stale_days) {
+        return Err(AuralisError::validation("Stale days must be between 1 and 365"));
+    }
+
+    let id = format!("review_{}", uuid::Uuid::new_v4());
+    conn.execute(
+        "INSERT INTO weekly_reviews (id, stale_days) VALUES (?1, ?2)",
+        params![id, stale_days],
+    )?;
+    state(conn, require_open(conn)?)
+}
+
+/// Ticks an item of the current step off, or back on.
+pub fn mark(conn: &Connection, entity_id: &str, reviewed: bool) -> Result<ReviewState, AuralisError> {
+    let review = require_open(conn)?;
+    if review.step == ReviewStep::Inbox {
+        return Err(AuralisError::validation("Inbox items are cleared by processing them"));
+    }
+    if !items(conn, &review)?.iter().any(|item| item.entity_id() == entity_id) {
+        return Err(AuralisError::not_found("Review item"));
+    }
+
+    if reviewed {
+        conn.execute(
+            "INSERT INTO weekly_review_items (id, review_id, step, entity_id)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (review_id, step, entity_id) DO NOTHING",
+            params![format!("review_item_{}", uuid::Uuid::new_v4()), review.id, review.step, entity_id],
+        )?;
+    } else {
+        conn.execute(
+            "DELETE FROM weekly_review_items WHERE review_id = ?1 AND step = ?2 AND entity_id = ?3",
+            params![review.id, review.step, entity_id],
+        )?;
+    }
+    state(conn, require_open(conn)?)
+}
+
+/// Moves on to the next step once the current one is clear; leaving the last
+/// step completes the review.
+pub fn advance(conn: &Connection) -> Result<ReviewState, AuralisError> {
+    let review = require_open(conn)?;
+    let current = state(conn, review)?;
+    if current.remaining > 0 {
+        let message = match current.review.step {
+            ReviewStep::Inbox => format!("Process the {} item(s) left in the inbox first", current.remaining),
+            _ => format!("{} item(s) in this step still need a look", current.remaining),
+        };
+        return Err(AuralisError::validation(message));
+    }
+
+    let next = match current.review.step {
+        ReviewStep::Inbox => ReviewStep::Projects,
+        ReviewStep::Projects => ReviewStep::StaleTasks,
+        ReviewStep::StaleTasks => ReviewStep::DeferredTasks,
+        ReviewStep::DeferredTasks => ReviewStep::Calendar,
+        ReviewStep::Calendar | ReviewStep::Done => ReviewStep::Done,
+    };
+    conn.execute(
+        "UPDATE weekly_reviews
+         SET step = ?1,
+             completed_at = CASE WHEN ?1 = 'done' THEN strftime('%Y-%m-%dT%H:%M:%fZ', 'now') END
+         WHERE id = ?2",
+        params![next, current.review.id],
+    )?;
+    let review = conn.query_row(
+        &format!("SELECT {REVIEW_COLUMNS} FROM weekly_reviews WHERE id = ?1"),
+        params![current.review.id],
+        review_from_row,
+    )?;
+    state(conn, review)
+}
+
+/// Drops the review in progress and what was ticked off in it.
+pub fn abandon(conn: &Connection) -> Result<(), AuralisError> {
+    let review = require_open(conn)?;
+    conn.execute("DELETE FROM weekly_reviews WHERE id = ?1", params![review.id])?;
+    Ok(())
+}
+
+/// Completed reviews, most recent first.
+pub fn history(conn: &Connection, limit: Option<i64>) -> Result<Vec<WeeklyReview>, AuralisError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {REVIEW_COLUMNS}
+         FROM weekly_reviews
+         WHERE completed_at IS NOT NULL
+         ORDER BY completed_at DESC, rowid DESC
+         LIMIT ?1"
+    ))?;
+    let rows = stmt.query_map(params![limit.unwrap_or(-1)], review_from_row)?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+fn state(conn: &Connection, review: WeeklyReview) -> Result<ReviewState, AuralisError> {
+    let items = items(conn, &review)?;
+    let remaining = items.iter().filter(|item| item.outstanding()).count();
+    Ok(ReviewState { review, items, remaining })
+}
+
+fn items(conn: &Connection, review: &WeeklyReview) -> Result<Vec<ReviewItem>, AuralisError> {
+    let reviewed: HashSet<String> = {
+        let mut stmt = conn.prepare("SELECT entity_id FROM weekly_review_items WHERE review_id = ?1 AND step = ?2")?;
+        let rows = stmt.query_map(params![review.id, review.step], |r| r.get(0))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    let now = Utc::now();
+
+    let items = match review.step {
+        ReviewStep::Inbox => inbox::list(conn, Some(InboxState::Unprocessed))?
+            .into_iter()
+            .map(|item| ReviewItem::Inbox { item })
+            .collect(),
+        ReviewStep::Projects => {
+            let active = projects::list(conn, Some(ProjectStatus::Active), None)?;
+            let ids: Vec<String> = active.iter().map(|p| p.id.clone()).collect();
+            let stats = project_stats::many(conn, &ids)?;
+            active
+                .into_iter()
+                .zip(stats)
+                .map(|(project, stats)| ReviewItem::Project {
+                    reviewed: reviewed.contains(&project.id),
+                    project,
+                    stats,
+                })
+                .collect()
+        }
+        ReviewStep::StaleTasks => {
+            let cutoff = datetime::format_utc(now - Duration::days(review.stale_days));
+            task_items(
+                tasks::select(
+                    conn,
+                    "status IN ('todo', 'doing') AND updated_at < ?1 ORDER BY updated_at, rowid",
+                    params![cutoff],
+                )?,
+                &reviewed,
+            )
+        }
+        ReviewStep::DeferredTasks => task_items(
+            tasks::select(conn, "status = 'deferred' ORDER BY updated_at, rowid", [])?,
+            &reviewed,
+        ),
+        ReviewStep::Calendar => {
+            let start = datetime::format_utc(now);
+            let end = datetime::format_utc(now + Duration::days(UPCOMING_DAYS));
+            calendar::list_range(conn, &start, &end)?
+                .into_iter()
+                .map(|occurrence| ReviewItem::Event {
+                    reviewed: reviewed.contains(&occurrence.event.id),
+                    occurrence,
+                })
+                .collect()
+        }
+        ReviewStep::Done => Vec::new(),
+    };
+    Ok(items)
+}
+
+fn task_items(tasks: Vec<TaskItem>, reviewed: &HashSet<String>) -> Vec<ReviewItem> {
+    tasks
+        .into_iter()
+        .map(|task| ReviewItem::Task {
+            reviewed: reviewed.contains(&task.id),
+            task,
+        })
+        .collect()
+}
@@ -0,0 +1,288 @@
+//! Whole-workspace archives: every row of the user tables, all columns, as one
+//! JSON document that can be imported on another machine.
+
+use crate::datetime;
+use crate::error::{AuralisError, Rule};
+use crate::migrations;
+use crate::store::links;
+use chrono::Utc;
+use rusqlite::types::{Type, Value as SqlValue, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+const FORMAT: &str = "auralis-workspace";
+const VERSION: u32 = 1;
+
+/// Exported tables, parents before children so imports satisfy foreign keys.
+const TABLES: &[&str] = &[
+    "areas",
+    "projects",
+    "tasks",
+    "checklist_items",
+    "task_dependencies",
+    "time_entries",
+    "inbox_items",
+    "notes",
+    "calendar_events",
+    "tags",
+    "entity_tags",
+    "smart_lists",
+    "project_templates",
+    "project_template_tasks",
+    "weekly_reviews",
+    "weekly_review_items",
+];
+
+// Columns a trigger rewrites on every update; imports write them back afterwards.
+const TRIGGER_STAMPS: &[(&str, &str)] = &[("notes", "updated_at"), ("tasks", "updated_at")];
+
+pub type Row = Map<String, Value>;
+
+#[derive(Serialize, Deserialize)]
+pub struct Workspace {
+    pub format: String,
+    pub version: u32,
+    pub schema_version: i64,
+    pub exported_at: String,
+    /// Rows per table, keyed by table name.
+    #[serde(flatten)]
+    pub tables: BTreeMap<String, Vec<Row>>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Delete everything first, leaving exactly what the document holds.
+    Replace,
+    /// Insert new ids and overwrite existing ones; other rows are left alone.
+    Merge,
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct TableCounts {
+    pub created: u32,
+    pub updated: u32,
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct WorkspaceImportSummary {
+    pub tables: BTreeMap<String, TableCounts>,
+    pub warnings: Vec<String>,
+}
+
+fn to_json(value: ValueRef, column: usize) -> rusqlite::Result<Value> {
+    Ok(match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Number::from_f64(f).map_or(Value::Null, Value::Number),
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(_) => {
+            return Err(rusqlite::Error::InvalidColumnType(column, "blob".into(), Type::Blob))
+        }
+    })
+}
+
+fn to_sql(table: &str, column: &str, value: &Value) -> Result<SqlValue, AuralisError> {
+    Ok(match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        Value::Array(_) | Value::Object(_) => {
+            return Err(AuralisError::validation(format!(
+                "Unsupported value for {table}.{column}: {value}"
+            )))
+        }
+    })
+}
+
+fn export_table(conn: &Connection, table: &str) -> Result<Vec<Row>, AuralisError> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {table} ORDER BY rowid"))?;
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+
+    let rows = stmt.query_map([], |r| {
+        let mut row = Row::new();
+        for (i, name) in columns.iter().enumerate() {
+            row.insert(name.clone(), to_json(r.get_ref(i)?, i)?);
+        }
+        Ok(row)
+    })?;
+
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+pub fn export(conn: &Connection) -> Result<Workspace, AuralisError> {
+    let mut tables = BTreeMap::new();
+    for table in TABLES {
+        tables.insert(table.to_string(), export_table(conn, table)?);
+    }
+
+    Ok(Workspace {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        schema_version: migrations::current_version(conn).map_err(AuralisError::Db)?,
+        exported_at: datetime::format_utc(Utc::now()),
+        tables,
+    })
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<HashSet<String>, AuralisError> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)")?;
+    let names = stmt.query_map(params![table], |r| r.get(0))?;
+    Ok(names.collect::<Result<_, _>>()?)
+}
+
+// Empties every exported table, children first. Projects are paused beforehand so
+// removing their tasks does not trip the next-action rule.
+fn clear(conn: &Connection) -> Result<(), AuralisError> {
+    conn.execute("UPDATE projects SET status = 'paused' WHERE status = 'active'", [])?;
+    for table in TABLES.iter().rev() {
+        conn.execute(&format!("DELETE FROM {table}"), [])?;
+    }
+    Ok(())
+}
+
+/// Inserts or overwrites one row by id. Returns whether it was newly created.
+fn upsert(
+    conn: &Connection,
+    table: &str,
+    id: &str,
+    row: &[(&String, SqlValue)],
+) -> Result<bool, AuralisError> {
+    let exists = conn
+        .query_row(&format!("SELECT 1 FROM {table} WHERE id = ?1"), params![id], |_| Ok(()))
+        .optional()?
+        .is_some();
+
+    let names: Vec<String> = row.iter().map(|(c, _)| format!("\"{c}\"")).collect();
+    let mut values: Vec<&SqlValue> = row.iter().map(|(_, v)| v).collect();
+
+    if exists {
+        let sets: Vec<String> = names
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("{c} = ?{}", i + 1))
+            .collect();
+        let sql = format!("UPDATE {table} SET {} WHERE id = ?{}", sets.join(", "), names.len() + 1);
+        let id_value = SqlValue::Text(id.to_string());
+        values.push(&id_value);
+        conn.execute(&sql, params_from_iter(values))?;
+
+        for (_, column) in TRIGGER_STAMPS.iter().filter(|(t, _)| *t == table) {
+            if let Some((_, value)) = row.iter().find(|(c, _)| c.as_str() == *column) {
+                conn.execute(
+                    &format!("UPDATE {table} SET \"{column}\" = ?1 WHERE id = ?2"),
+                    params![value, id],
+                )?;
+            }
+        }
+    } else {
+        let slots: Vec<String> = (1..=names.len()).map(|i| format!("?{i}")).collect();
+        let sql = format!("INSERT INTO {table} ({}) VALUES ({})", names.join(", "), slots.join(", "));
+        conn.execute(&sql, params_from_iter(values))?;
+    }
+
+    Ok(!exists)
+}
+
+/// Loads a document produced by [`export`]. Tables go in parent-first order;
+/// projects are written paused and get their real status back once their tasks
+/// exist. A project that would break the next-action rule stays paused, with a
+/// warning. Any other failure rolls the whole import back.
+pub fn import(
+    conn: &mut Connection,
+    doc: &Workspace,
+    mode: ImportMode,
+) -> Result<WorkspaceImportSummary, AuralisError> {
+    if doc.format != FORMAT {
+        return Err(AuralisError::validation("Not an Auralis workspace export"));
+    }
+    if doc.version > VERSION || doc.schema_version > migrations::latest_version() {
+        return Err(AuralisError::validation(
+            "This export was made by a newer version of Auralis. Please update the app.",
+        ));
+    }
+
+    let tx = conn.transaction()?;
+    // Checked at commit instead, so rows may reference ones later in the document.
+    tx.pragma_update(None, "defer_foreign_keys", "ON")?;
+
+    if mode == ImportMode::Replace {
+        clear(&tx)?;
+    }
+
+    let mut summary = WorkspaceImportSummary::default();
+    let mut statuses: Vec<(String, SqlValue)> = Vec::new();
+
+    for table in TABLES {
+        let rows = doc.tables.get(*table).map(Vec::as_slice).unwrap_or_default();
+        let known = table_columns(&tx, table)?;
+        let mut unknown = BTreeSet::new();
+        let counts = summary.tables.entry(table.to_string()).or_default();
+
+        for row in rows {
+            let id = row
+                .get("id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| AuralisError::validation(format!("A row in {table} has no id")))?;
+
+            let mut values = Vec::new();
+            for (column, value) in row {
+                if !known.contains(column) {
+                    unknown.insert(column.as_str());
+                    continue;
+                }
+                let mut value = to_sql(table, column, value)?;
+                if *table == "projects" && column == "status" {
+                    statuses.push((id.to_string(), value));
+                    value = SqlValue::Text("paused".to_string());
+                }
+                values.push((column, value));
+            }
+
+            let created = upsert(&tx, table, id, &values).map_err(|e| match e {
+                AuralisError::Validation(m) => AuralisError::Validation(format!("{table} {id}: {m}")),
+                other => other,
+            })?;
+            if created {
+                counts.created += 1;
+            } else {
+                counts.updated += 1;
+            }
+        }
+
+        for column in unknown {
+            summary.warnings.push(format!("Ignored unknown column {table}.{column}"));
+        }
+    }
+
+    for (id, status) in statuses {
+        match tx.execute("UPDATE projects SET status = ?1 WHERE id = ?2", params![status, id]) {
+            Ok(_) => {}
+            Err(e) => match AuralisError::from(e) {
+                e if e.rule() == Some(Rule::NextActionRequired) => {
+                    summary.warnings.push(format!("Project {id} was left paused: it has no open task"));
+                }
+                e => return Err(e),
+            },
+        }
+    }
+
+    // Tasks fall back to this area, so it must survive a replace.
+    tx.execute(
+        "INSERT OR IGNORE INTO areas (id, name, active) VALUES ('area_admin_life', 'Admin / Life', 1)",
+        [],
+    )?;
+
+    // Links are derived from note content rather than exported.
+    links::rebuild(&tx)?;
+
+    tx.commit()?;
+
+    Ok(summary)
+}
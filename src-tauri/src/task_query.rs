@@ -0,0 +1,361 @@
+//! A small filter language for tasks, used by smart lists, e.g.
+//! `status:todo,doing due<+7d area:"Admin / Life" tag:@phone priority:high sort:due`.
+//!
+//! - Terms are separated by spaces and must all match. Commas inside a value mean
+//!   "any of". A leading `-` negates a term. Quote values that contain spaces.
+//! - `status:` and `priority:` take the stored values (`todo`, `high`, ...).
+//! - `area:`, `project:` and `tag:` take an id or a name, ignoring case.
+//!   `project:none` and `tag:none` match tasks without one.
+//! - `due`, `scheduled`, `created` and `completed` compare with `:`, `<`, `<=`, `>`
+//!   or `>=` against a day: `today`, `tomorrow`, `yesterday`, `+3d`, `-2w` or
+//!   `2024-05-14` (`:` means "on that day"). `now` is the current moment instead.
+//!   `due:none` / `due:any` test whether there is a date at all.
+//! - `sort:due,-priority` orders by `due`, `scheduled`, `created`, `completed`,
+//!   `priority`, `status` or `title`; `-` reverses. Without one, newest first.
+//! - Any other word is looked for in the title.
+//!
+//! Values are only ever bound as parameters; the SQL text is built from the fixed
+//! column names and operators below.
+
+use crate::datetime;
+use crate::domain::{Priority, TagTarget, TaskStatus};
+use crate::error::AuralisError;
+use crate::store::tags;
+use chrono::{Days, NaiveDate, NaiveDateTime};
+use rusqlite::types::Value;
+
+/// A query compiled for `SELECT ... FROM tasks WHERE {conditions} ORDER BY {order_by}`,
+/// with `?N` placeholders bound to `params`.
+#[derive(Debug)]
+pub struct Compiled {
+    pub conditions: String,
+    pub order_by: String,
+    pub params: Vec<Value>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    On,
+    Before,
+    OnOrBefore,
+    After,
+    OnOrAfter,
+}
+
+enum When {
+    Day(NaiveDate),
+    Now,
+}
+
+const OPS: &[(&str, Op)] = &[
+    ("<=", Op::OnOrBefore),
+    (">=", Op::OnOrAfter),
+    ("<", Op::Before),
+    (">", Op::After),
+    (":", Op::On),
+];
+
+const DATE_FIELDS: &[(&str, &str)] = &[
+    ("due", "due_at"),
+    ("scheduled", "scheduled_at"),
+    ("created", "created_at"),
+    ("completed", "completed_at"),
+];
+
+const PRIORITY_RANK: &str = "CASE priority WHEN 'high' THEN 0 WHEN 'normal' THEN 1 ELSE 2 END";
+const STATUS_RANK: &str =
+    "CASE status WHEN 'doing' THEN 0 WHEN 'todo' THEN 1 WHEN 'deferred' THEN 2 ELSE 3 END";
+
+/// Compiles `input`, reading relative dates against the local time `now`.
+pub fn compile(input: &str, now: NaiveDateTime) -> Result<Compiled, AuralisError> {
+    let mut query = Builder {
+        now,
+        conditions: Vec::new(),
+        order_by: Vec::new(),
+        params: Vec::new(),
+    };
+    for term in split(input, char::is_whitespace)? {
+        query.term(&term)?;
+    }
+
+    let conditions = match query.conditions.is_empty() {
+        true => "1".to_string(),
+        false => query.conditions.join(" AND "),
+    };
+    query.order_by.push("created_at DESC".to_string());
+    query.order_by.push("id".to_string());
+    Ok(Compiled {
+        conditions,
+        order_by: query.order_by.join(", "),
+        params: query.params,
+    })
+}
+
+struct Builder {
+    now: NaiveDateTime,
+    conditions: Vec<String>,
+    order_by: Vec<String>,
+    params: Vec<Value>,
+}
+
+impl Builder {
+    // Binds a value and returns its placeholder number.
+    fn bind(&mut self, value: impl Into<Value>) -> usize {
+        self.params.push(value.into());
+        self.params.len()
+    }
+
+    fn term(&mut self, term: &str) -> Result<(), AuralisError> {
+        let (negated, body) = match term.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, term),
+        };
+
+        let Some((field, op, value)) = field_term(body) else {
+            return self.title(negated, body);
+        };
+        if field == "sort" {
+            if negated || op != Op::On {
+                return Err(AuralisError::validation("Use sort:field or sort:-field"));
+            }
+            return self.sort(&unquote(value));
+        }
+
+        let values: Vec<String> = split(value, |c| c == ',')?
+            .iter()
+            .map(|v| unquote(v))
+            .filter(|v| !v.is_empty())
+            .collect();
+        if values.is_empty() {
+            return Err(AuralisError::validation(format!("'{field}' needs a value")));
+        }
+        if op != Op::On && DATE_FIELDS.iter().all(|(f, _)| *f != field) {
+            return Err(AuralisError::validation(format!("Use {field}:value, not a comparison")));
+        }
+
+        let mut any = Vec::new();
+        for value in &values {
+            any.push(self.condition(field, op, value)?);
+        }
+        let condition = format!("({})", any.join(" OR "));
+        self.conditions.push(match negated {
+            // A NULL comparison is neither true nor false; count it as "no match".
+            true => format!("NOT COALESCE({condition}, 0)"),
+            false => condition,
+        });
+        Ok(())
+    }
+
+    fn condition(&mut self, field: &str, op: Op, value: &str) -> Result<String, AuralisError> {
+        let none = value.eq_ignore_ascii_case("none");
+        Ok(match field {
+            "status" => {
+                let status: TaskStatus = value.to_ascii_lowercase().parse()?;
+                format!("status = ?{}", self.bind(status.to_string()))
+            }
+            "priority" => {
+                let priority: Priority = value.to_ascii_lowercase().parse()?;
+                format!("priority = ?{}", self.bind(priority.to_string()))
+            }
+            "area" => {
+                let p = self.bind(value.to_string());
+                format!("area_id IN (SELECT id FROM areas WHERE id = ?{p} OR name = ?{p} COLLATE NOCASE)")
+            }
+            "project" if none => "project_id IS NULL".to_string(),
+            "project" => {
+                let p = self.bind(value.to_string());
+                format!("project_id IN (SELECT id FROM projects WHERE id = ?{p} OR name = ?{p} COLLATE NOCASE)")
+            }
+            "tag" if none => format!(
+                "NOT EXISTS (SELECT 1 FROM entity_tags et WHERE et.entity_kind = '{}' AND et.entity_id = tasks.id)",
+                TagTarget::Task
+            ),
+            "tag" => tags::filter(TagTarget::Task, "tasks.id", self.bind(value.to_string())),
+            _ => {
+                let column = DATE_FIELDS
+                    .iter()
+                    .find(|(f, _)| *f == field)
+                    .map(|(_, c)| *c)
+                    .unwrap_or("due_at");
+                self.date(field, column, op, value)?
+            }
+        })
+    }
+
+    fn date(&mut self, field: &str, column: &str, op: Op, value: &str) -> Result<String, AuralisError> {
+        let value = value.to_ascii_lowercase();
+        match (value.as_str(), op) {
+            ("none", Op::On) => return Ok(format!("{column} IS NULL")),
+            ("any", Op::On) => return Ok(format!("{column} IS NOT NULL")),
+            ("none" | "any", _) => {
+                return Err(AuralisError::validation(format!("Use {field}:{value}, not a comparison")))
+            }
+            _ => {}
+        }
+
+        let instant = |dt: NaiveDateTime| -> Result<Value, AuralisError> {
+            let utc = datetime::local_to_utc(dt)?;
+            Ok(Value::Text(datetime::format_utc(utc)))
+        };
+
+        match when(&value, self.now.date())? {
+            When::Now => {
+                let sql_op = match op {
+                    Op::Before => "<",
+                    Op::OnOrBefore => "<=",
+                    Op::After => ">",
+                    Op::OnOrAfter => ">=",
+                    Op::On => return Err(AuralisError::validation(format!("Compare {field} with now using < or >"))),
+                };
+                let p = self.bind(instant(self.now)?);
+                Ok(format!("{column} {sql_op} ?{p}"))
+            }
+            When::Day(day) => {
+                let next = day
+                    .checked_add_days(Days::new(1))
+                    .ok_or_else(|| AuralisError::validation(format!("Date out of range: {value}")))?;
+                let start = instant(day.and_hms_opt(0, 0, 0).unwrap_or_default())?;
+                let end = instant(next.and_hms_opt(0, 0, 0).unwrap_or_default())?;
+                Ok(match op {
+                    Op::On => {
+                        let (s, e) = (self.bind(start), self.bind(end));
+                        format!("({column} >= ?{s} AND {column} < ?{e})")
+                    }
+                    Op::Before => format!("{column} < ?{}", self.bind(start)),
+                    Op::OnOrBefore => format!("{column} < ?{}", self.bind(end)),
+                    Op::After => format!("{column} >= ?{}", self.bind(end)),
+                    Op::OnOrAfter => format!("{column} >= ?{}", self.bind(start)),
+                })
+            }
+        }
+    }
+
+    fn sort(&mut self, value: &str) -> Result<(), AuralisError> {
+        for key in value.split(',').filter(|k| !k.is_empty()) {
+            let (descending, key) = match key.strip_prefix('-') {
+                Some(k) => (true, k),
+                None => (false, key),
+            };
+            let dir = if descending { "DESC" } else { "ASC" };
+            let column = match key.to_ascii_lowercase().as_str() {
+                "priority" => PRIORITY_RANK.to_string(),
+                "status" => STATUS_RANK.to_string(),
+                "title" => "title COLLATE NOCASE".to_string(),
+                other => match DATE_FIELDS.iter().find(|(f, _)| *f == other) {
+                    // Tasks without the date go last either way.
+                    Some((_, column)) => {
+                        self.order_by.push(format!("{column} IS NULL"));
+                        column.to_string()
+                    }
+                    None => {
+                        return Err(AuralisError::validation(format!(
+                            "Cannot sort by '{key}' (use due, scheduled, created, completed, priority, status or title)"
+                        )))
+                    }
+                },
+            };
+            self.order_by.push(format!("{column} {dir}"));
+        }
+        Ok(())
+    }
+
+    fn title(&mut self, negated: bool, text: &str) -> Result<(), AuralisError> {
+        let text = unquote(text);
+        if text.is_empty() {
+            return Ok(());
+        }
+        let escaped = text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        let p = self.bind(format!("%{escaped}%"));
+        let like = format!("title LIKE ?{p} ESCAPE '\\'");
+        self.conditions.push(match negated {
+            true => format!("NOT ({like})"),
+            false => like,
+        });
+        Ok(())
+    }
+}
+
+// Splits `field<op>value` for the known fields; anything else is title text.
+fn field_term(term: &str) -> Option<(&str, Op, &str)> {
+    let end = term.find(|c: char| !c.is_ascii_alphabetic())?;
+    let field = &term[..end];
+    let known = ["status", "priority", "area", "project", "tag", "sort"];
+    if !known.contains(&field) && DATE_FIELDS.iter().all(|(f, _)| *f != field) {
+        return None;
+    }
+    let rest = &term[end..];
+    OPS.iter()
+        .find_map(|(text, op)| rest.strip_prefix(text).map(|value| (field, *op, value)))
+}
+
+fn when(value: &str, today: NaiveDate) -> Result<When, AuralisError> {
+    let invalid = || {
+        AuralisError::validation(format!(
+            "Invalid date '{value}' (use today, tomorrow, yesterday, now, +3d, -2w or 2024-05-14)"
+        ))
+    };
+    let shift = |days: i64| {
+        let magnitude = Days::new(days.unsigned_abs());
+        match days >= 0 {
+            true => today.checked_add_days(magnitude),
+            false => today.checked_sub_days(magnitude),
+        }
+        .ok_or_else(invalid)
+    };
+
+    match value {
+        "now" => return Ok(When::Now),
+        "today" => return shift(0).map(When::Day),
+        "tomorrow" => return shift(1).map(When::Day),
+        "yesterday" => return shift(-1).map(When::Day),
+        _ => {}
+    }
+    if let Some(rest) = value.strip_prefix(['+', '-']) {
+        let (number, per_unit) = match (rest.strip_suffix('d'), rest.strip_suffix('w')) {
+            (Some(number), _) => (number, 1),
+            (_, Some(number)) => (number, 7),
+            _ => return Err(invalid()),
+        };
+        let n: i64 = number.parse().map_err(|_| invalid())?;
+        let days = n.checked_mul(per_unit).ok_or_else(invalid)?;
+        let days = match value.starts_with('-') {
+            true => days.checked_neg().ok_or_else(invalid)?,
+            false => days,
+        };
+        return shift(days).map(When::Day);
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(When::Day)
+        .map_err(|_| invalid())
+}
+
+// Splits on `sep` outside double quotes. The quotes are kept, since values are
+// split again on commas; `unquote` drops them once a value is final.
+fn split(input: &str, sep: impl Fn(char) -> bool) -> Result<Vec<String>, AuralisError> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if !quoted && sep(c) {
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if quoted {
+        return Err(AuralisError::validation("Unclosed quote in query"));
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    Ok(parts)
+}
+
+fn unquote(text: &str) -> String {
+    text.replace('"', "")
+}
@@ -0,0 +1,673 @@
+//! Two-way sync between the `notes` table and a folder of Markdown files, laid out
+//! the way Obsidian expects: one `<title>.md` per note, with YAML front matter for
+//! `id`, `area`, `project`, `created_at` and `updated_at`.
+//!
+//! `vault_files` remembers each note/file pair as of the last sync, so a pass can
+//! tell which side changed since. When both did, the newer side wins (the note's
+//! `updated_at` against the file's modification time) and the other version is
+//! written next to it as a conflict copy; nothing is overwritten silently.
+
+use crate::backup;
+use crate::datetime;
+use crate::error::AuralisError;
+use crate::frontmatter::Document;
+use crate::store::links;
+use crate::store::notes::{self, NoteItem};
+use chrono::{DateTime, Local, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `settings` key holding the vault directory.
+pub const DIR_SETTING: &str = "vault_dir";
+
+#[derive(Serialize, Default, Debug)]
+pub struct VaultSyncSummary {
+    pub files_written: u32,
+    pub notes_imported: u32,
+    pub files_deleted: u32,
+    pub notes_deleted: u32,
+    pub conflicts: Vec<String>, // conflict copies written, relative to the vault
+    pub warnings: Vec<String>,
+}
+
+impl VaultSyncSummary {
+    pub fn is_empty(&self) -> bool {
+        self.files_written == 0
+            && self.notes_imported == 0
+            && self.files_deleted == 0
+            && self.notes_deleted == 0
+            && self.conflicts.is_empty()
+            && self.warnings.is_empty()
+    }
+}
+
+struct Synced {
+    note_id: String,
+    path: String,
+    note_updated_at: String,
+    file_hash: String,
+}
+
+/// A vault file read as a note, with area/project names resolved to ids.
+struct FileNote {
+    doc: Document,
+    title: String,
+    content: String,
+    area_id: Option<String>,
+    project_id: Option<String>,
+    created_at: Option<String>,
+}
+
+// FNV-1a: stable across Rust releases, which `DefaultHasher` is not.
+fn hash(bytes: &[u8]) -> String {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in bytes {
+        h ^= u64::from(*b);
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{h:016x}")
+}
+
+fn io_error(what: &str, path: &Path, e: std::io::Error) -> AuralisError {
+    AuralisError::Io(format!("Failed to {what} {}: {e}", path.display()))
+}
+
+/// Every `.md` file under `dir`, keyed by '/'-separated relative path. Hidden
+/// entries (`.obsidian`, `.trash`, ...) are skipped.
+fn scan(root: &Path, dir: &Path, out: &mut BTreeMap<String, PathBuf>) -> Result<(), AuralisError> {
+    for item in fs::read_dir(dir).map_err(|e| io_error("read", dir, e))? {
+        let path = item.map_err(|e| io_error("read", dir, e))?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            scan(root, &path, out)?;
+        } else if name.ends_with(".md") {
+            let rel = path.strip_prefix(root).unwrap_or(&path);
+            let rel: Vec<&str> = rel.iter().filter_map(|c| c.to_str()).collect();
+            out.insert(rel.join("/"), path);
+        }
+    }
+    Ok(())
+}
+
+fn stem_of(rel: &str) -> &str {
+    let name = rel.rsplit('/').next().unwrap_or(rel);
+    name.strip_suffix(".md").unwrap_or(name)
+}
+
+fn folder_of(rel: &str) -> &str {
+    rel.rsplit_once('/').map_or("", |(folder, _)| folder)
+}
+
+/// A file name for `title`: characters that file systems or Obsidian links
+/// cannot take become '-'.
+fn stem_for(title: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .take(100)
+        .collect();
+    let cleaned = cleaned.trim().trim_start_matches('.').trim();
+    if cleaned.is_empty() {
+        "Untitled".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+struct Sync<'a> {
+    conn: &'a Connection,
+    root: &'a Path,
+    backups: &'a Path,
+    areas: Vec<(String, String)>,            // (id, name)
+    projects: Vec<(String, String, String)>, // (id, area_id, name)
+    taken: HashSet<String>,                  // relative paths in use
+    summary: VaultSyncSummary,
+}
+
+impl<'a> Sync<'a> {
+    fn new(conn: &'a Connection, root: &'a Path, backups: &'a Path) -> Result<Self, AuralisError> {
+        let mut stmt = conn.prepare("SELECT id, name FROM areas")?;
+        let areas = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        let mut stmt = conn.prepare("SELECT id, area_id, name FROM projects")?;
+        let projects = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(Sync {
+            conn,
+            root,
+            backups,
+            areas,
+            projects,
+            taken: HashSet::new(),
+            summary: VaultSyncSummary::default(),
+        })
+    }
+
+    fn abs(&self, rel: &str) -> PathBuf {
+        self.root.join(rel)
+    }
+
+    fn read(&self, rel: &str) -> Result<Vec<u8>, AuralisError> {
+        let path = self.abs(rel);
+        fs::read(&path).map_err(|e| io_error("read", &path, e))
+    }
+
+    fn write(&mut self, rel: &str, text: &str) -> Result<(), AuralisError> {
+        let path = self.abs(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| io_error("create", parent, e))?;
+        }
+        fs::write(&path, text).map_err(|e| io_error("write", &path, e))?;
+        self.taken.insert(rel.to_string());
+        Ok(())
+    }
+
+    fn remove(&mut self, rel: &str) -> Result<(), AuralisError> {
+        let path = self.abs(rel);
+        fs::remove_file(&path).map_err(|e| io_error("delete", &path, e))?;
+        self.taken.remove(rel);
+        Ok(())
+    }
+
+    fn modified_at(&self, rel: &str) -> Result<String, AuralisError> {
+        let path = self.abs(rel);
+        let modified = fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .map_err(|e| io_error("read", &path, e))?;
+        Ok(datetime::format_utc(DateTime::<Utc>::from(modified)))
+    }
+
+    /// `<folder>/<stem>.md`, or `<stem> 2.md`, `<stem> 3.md`, ... if that is taken.
+    fn free_path(&self, folder: &str, stem: &str) -> String {
+        let prefix = if folder.is_empty() { String::new() } else { format!("{folder}/") };
+        (1..)
+            .map(|n| match n {
+                1 => format!("{prefix}{stem}.md"),
+                n => format!("{prefix}{stem} {n}.md"),
+            })
+            .find(|rel| !self.taken.contains(rel) && !self.abs(rel).exists())
+            .unwrap_or_default()
+    }
+
+    fn area_name(&self, id: &str) -> Option<&str> {
+        self.areas.iter().find(|(a, _)| a == id).map(|(_, name)| name.as_str())
+    }
+
+    fn project_name(&self, id: &str) -> Option<&str> {
+        self.projects.iter().find(|(p, _, _)| p == id).map(|(_, _, name)| name.as_str())
+    }
+
+    // Front matter may name an area/project or give its id.
+    fn resolve_area(&mut self, rel: &str, value: &str) -> Option<String> {
+        let found = self
+            .areas
+            .iter()
+            .find(|(id, name)| id == value || name.eq_ignore_ascii_case(value))
+            .map(|(id, _)| id.clone());
+        if found.is_none() {
+            self.summary.warnings.push(format!("{rel}: unknown area '{value}'"));
+        }
+        found
+    }
+
+    fn resolve_project(&mut self, rel: &str, value: &str, area_id: Option<&str>) -> Option<String> {
+        let matches = |(id, _, name): &&(String, String, String)| id == value || name.eq_ignore_ascii_case(value);
+        let found = self
+            .projects
+            .iter()
+            .filter(matches)
+            .find(|(_, area, _)| area_id.is_none_or(|a| a == area))
+            .or_else(|| self.projects.iter().find(matches))
+            .map(|(id, _, _)| id.clone());
+        if found.is_none() {
+            self.summary.warnings.push(format!("{rel}: unknown project '{value}'"));
+        }
+        found
+    }
+
+    fn parse(&mut self, rel: &str, bytes: &[u8]) -> FileNote {
+        let doc = Document::parse(&String::from_utf8_lossy(bytes));
+        let title = doc
+            .get("title")
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| stem_of(rel))
+            .trim()
+            .to_string();
+        let content = doc.body.trim().to_string();
+        let area = doc.get("area").filter(|v| !v.is_empty()).map(String::from);
+        let project = doc.get("project").filter(|v| !v.is_empty()).map(String::from);
+        let created_at = doc
+            .get("created_at")
+            .and_then(|v| datetime::normalize(v).ok());
+
+        let area_id = area.and_then(|a| self.resolve_area(rel, &a));
+        let project_id = project.and_then(|p| self.resolve_project(rel, &p, area_id.as_deref()));
+
+        FileNote {
+            doc,
+            title,
+            content,
+            area_id,
+            project_id,
+            created_at,
+        }
+    }
+
+    /// `note` as file text, keeping any extra front matter already in `doc`.
+    fn render(&self, note: &NoteItem, mut doc: Document, stem: &str) -> String {
+        doc.set("id", Some(&note.id));
+        doc.set("title", (stem != note.title).then_some(note.title.as_str()));
+        doc.set("area", note.area_id.as_deref().and_then(|id| self.area_name(id)));
+        doc.set("project", note.project_id.as_deref().and_then(|id| self.project_name(id)));
+        doc.set("created_at", Some(&note.created_at));
+        doc.set("updated_at", Some(&note.updated_at));
+        doc.body = format!("\n{}\n", note.content);
+        doc.render()
+    }
+
+    fn record(&mut self, note_id: &str, rel: &str) -> Result<(), AuralisError> {
+        let file_hash = hash(&self.read(rel)?);
+        let note_updated_at: String = self.conn.query_row(
+            "SELECT updated_at FROM notes WHERE id = ?1",
+            params![note_id],
+            |r| r.get(0),
+        )?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO vault_files (note_id, path, note_updated_at, file_hash)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![note_id, rel, note_updated_at, file_hash],
+        )?;
+        Ok(())
+    }
+
+    fn forget(&self, note_id: &str) -> Result<(), AuralisError> {
+        self.conn.execute("DELETE FROM vault_files WHERE note_id = ?1", params![note_id])?;
+        Ok(())
+    }
+
+    fn delete_note(&mut self, note_id: &str) -> Result<(), AuralisError> {
+        self.conn.execute("DELETE FROM notes WHERE id = ?1", params![note_id])?;
+        self.forget(note_id)?;
+        self.summary.notes_deleted += 1;
+        Ok(())
+    }
+
+    /// Writes `note` to its file. `current` is where it lives now; a changed title
+    /// moves it to a matching name in the same folder.
+    fn export_note(&mut self, note: &NoteItem, current: Option<&str>) -> Result<(), AuralisError> {
+        let stem = stem_for(&note.title);
+        let rel = match current {
+            Some(rel) if stem_of(rel) == stem && self.abs(rel).exists() => rel.to_string(),
+            Some(rel) => self.free_path(folder_of(rel), &stem),
+            None => self.free_path("", &stem),
+        };
+
+        let existing = match current.filter(|c| self.abs(c).exists()) {
+            Some(c) => Document::parse(&String::from_utf8_lossy(&self.read(c)?)),
+            None => Document::default(),
+        };
+        let text = self.render(note, existing, stem_of(&rel));
+        self.write(&rel, &text)?;
+        if let Some(old) = current.filter(|c| *c != rel && self.abs(c).exists()) {
+            self.remove(old)?;
+        }
+
+        self.summary.files_written += 1;
+        self.record(&note.id, &rel)
+    }
+
+    /// Creates or updates note `id` from the file at `rel`. Files without the id in
+    /// their front matter get it written back so later passes can pair them up.
+    fn import_file(&mut self, id: &str, rel: &str, file: FileNote) -> Result<(), AuralisError> {
+        if file.content.is_empty() {
+            self.summary.warnings.push(format!("{rel}: skipped, the note has no content"));
+            return Ok(());
+        }
+        let modified = self.modified_at(rel)?;
+        let old_title: Option<String> = self
+            .conn
+            .query_row("SELECT title FROM notes WHERE id = ?1", params![id], |r| r.get(0))
+            .optional()?;
+
+        let updated = self.conn.execute(
+            "UPDATE notes SET title = ?1, content = ?2, area_id = ?3, project_id = ?4 WHERE id = ?5",
+            params![file.title, file.content, file.area_id, file.project_id, id],
+        )?;
+        if updated > 0 {
+            // The touch trigger just set it to now; the file's own time is more honest.
+            self.conn.execute(
+                "UPDATE notes SET updated_at = ?1 WHERE id = ?2",
+                params![modified, id],
+            )?;
+        } else {
+            self.conn.execute(
+                "INSERT INTO notes (id, title, content, area_id, project_id, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id,
+                    file.title,
+                    file.content,
+                    file.area_id,
+                    file.project_id,
+                    file.created_at.as_deref().unwrap_or(&modified),
+                    modified
+                ],
+            )?;
+        }
+        links::note_saved(self.conn, id, old_title.as_deref())?;
+        self.summary.notes_imported += 1;
+
+        if file.doc.get("id") != Some(id) {
+            let mut doc = file.doc;
+            doc.set("id", Some(id));
+            self.write(rel, &doc.render())?;
+        }
+        self.record(id, rel)
+    }
+
+    /// Saves the losing side of a conflict next to `rel`, marked so it is never
+    /// imported as a note of its own.
+    fn conflict_copy(&mut self, rel: &str, note_id: &str, mut doc: Document) -> Result<(), AuralisError> {
+        doc.set("id", None);
+        doc.set("conflict_of", Some(note_id));
+        let stamp = Local::now().format("%Y-%m-%d %H%M%S");
+        let copy = self.free_path(folder_of(rel), &format!("{} (conflict {stamp})", stem_of(rel)));
+        self.write(&copy, &doc.render())?;
+        self.summary.conflicts.push(copy);
+        Ok(())
+    }
+
+    /// Both sides changed since the last sync (or were never paired): the newer wins.
+    fn reconcile(&mut self, note: &NoteItem, rel: &str, bytes: &[u8]) -> Result<(), AuralisError> {
+        let file = self.parse(rel, bytes);
+        let same = file.title == note.title
+            && file.content == note.content
+            && file.area_id == note.area_id
+            && file.project_id == note.project_id;
+        if same {
+            return self.record(&note.id, rel);
+        }
+
+        if note.updated_at >= self.modified_at(rel)? {
+            self.conflict_copy(rel, &note.id, file.doc)?;
+            self.export_note(note, Some(rel))
+        } else {
+            let mine = Document::parse(&self.render(note, Document::default(), stem_of(rel)));
+            self.conflict_copy(rel, &note.id, mine)?;
+            self.import_file(&note.id, rel, file)
+        }
+    }
+
+    fn run(mut self) -> Result<VaultSyncSummary, AuralisError> {
+        let mut files = BTreeMap::new();
+        scan(self.root, self.root, &mut files)?;
+        self.taken = files.keys().cloned().collect();
+
+        let notes: BTreeMap<String, NoteItem> = notes::list(self.conn, None, None, None)?
+            .into_iter()
+            .map(|n| (n.id.clone(), n))
+            .collect();
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT note_id, path, note_updated_at, file_hash FROM vault_files")?;
+        let mut synced: Vec<Synced> = stmt
+            .query_map([], |r| {
+                Ok(Synced {
+                    note_id: r.get(0)?,
+                    path: r.get(1)?,
+                    note_updated_at: r.get(2)?,
+                    file_hash: r.get(3)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        drop(stmt);
+
+        // Files moved or renamed outside the app still carry their id; follow them.
+        let known: HashSet<String> = synced.iter().map(|s| s.path.clone()).collect();
+        let mut unpaired: HashMap<String, Vec<u8>> = HashMap::new();
+        for rel in files.keys().filter(|rel| !known.contains(*rel)) {
+            let bytes = self.read(rel)?;
+            let id = Document::parse(&String::from_utf8_lossy(&bytes)).get("id").map(String::from);
+            let moved = id.and_then(|id| {
+                synced
+                    .iter_mut()
+                    .find(|s| s.note_id == id && !files.contains_key(&s.path))
+            });
+            match moved {
+                Some(s) => s.path = rel.clone(),
+                None => {
+                    unpaired.insert(rel.clone(), bytes);
+                }
+            }
+        }
+
+        // Every paired file gone at once means the folder was swapped or emptied
+        // (an unmounted drive, a cloud folder not yet synced), not that each note
+        // was deleted by hand.
+        let all_gone = !synced.is_empty() && synced.iter().all(|s| !files.contains_key(&s.path));
+        if all_gone && synced.iter().any(|s| notes.contains_key(&s.note_id)) {
+            return Err(AuralisError::Io(format!(
+                "None of the synced notes have a file in {} any more; sync is paused so they are not deleted",
+                self.root.display()
+            )));
+        }
+
+        // Notes whose file was removed are deleted below; keep a copy first. This
+        // has to happen before the pass writes anything, as SQLite will not back up
+        // a database while its connection holds a write transaction.
+        let deletes = synced.iter().any(|s| {
+            !files.contains_key(&s.path) && notes.get(&s.note_id).is_some_and(|n| n.updated_at == s.note_updated_at)
+        });
+        if deletes {
+            backup::before(self.conn, self.backups, "vault-delete")?;
+        }
+
+        let mut paired_notes = HashSet::new();
+        for s in &synced {
+            paired_notes.insert(s.note_id.clone());
+            let note = notes.get(&s.note_id);
+            let bytes = match files.contains_key(&s.path) {
+                true => Some(self.read(&s.path)?),
+                false => None,
+            };
+
+            match (note, bytes) {
+                (None, None) => self.forget(&s.note_id)?,
+                (None, Some(bytes)) if hash(&bytes) == s.file_hash => {
+                    self.remove(&s.path)?;
+                    self.forget(&s.note_id)?;
+                    self.summary.files_deleted += 1;
+                }
+                (None, Some(bytes)) => {
+                    let file = self.parse(&s.path, &bytes);
+                    self.import_file(&s.note_id, &s.path, file)?;
+                }
+                (Some(note), None) if note.updated_at == s.note_updated_at => self.delete_note(&note.id)?,
+                (Some(note), None) => self.export_note(note, None)?,
+                (Some(note), Some(bytes)) => {
+                    let note_changed = note.updated_at != s.note_updated_at;
+                    let file_changed = hash(&bytes) != s.file_hash;
+                    match (note_changed, file_changed) {
+                        (false, false) => {
+                            // Only the path may have changed (a move outside the app).
+                            self.record(&note.id, &s.path)?;
+                        }
+                        (true, false) => self.export_note(note, Some(&s.path))?,
+                        (false, true) => {
+                            let file = self.parse(&s.path, &bytes);
+                            self.import_file(&note.id, &s.path, file)?;
+                        }
+                        (true, true) => self.reconcile(note, &s.path, &bytes)?,
+                    }
+                }
+            }
+        }
+
+        let mut unpaired: Vec<(String, Vec<u8>)> = unpaired.into_iter().collect();
+        unpaired.sort();
+        for (rel, bytes) in unpaired {
+            let file = self.parse(&rel, &bytes);
+            if file.doc.get("conflict_of").is_some() {
+                continue;
+            }
+            let id = file.doc.get("id").map(String::from);
+            match id {
+                Some(id) if notes.contains_key(&id) && !paired_notes.contains(&id) => {
+                    paired_notes.insert(id.clone());
+                    self.reconcile(&notes[&id], &rel, &bytes)?;
+                }
+                // An id no other note uses (e.g. a vault copied from another machine).
+                Some(id) if !notes.contains_key(&id) && !paired_notes.contains(&id) => {
+                    paired_notes.insert(id.clone());
+                    self.import_file(&id, &rel, file)?;
+                }
+                // No id, or a duplicated file still carrying another note's id.
+                _ => {
+                    let id = format!("note_{}", uuid::Uuid::new_v4());
+                    paired_notes.insert(id.clone());
+                    self.import_file(&id, &rel, file)?;
+                }
+            }
+        }
+
+        for note in notes.values().filter(|n| !paired_notes.contains(&n.id)) {
+            self.export_note(note, None)?;
+        }
+
+        Ok(self.summary)
+    }
+
+    /// One-way pass for when the notes table was swapped out wholesale: the old
+    /// pairings describe another database, so nothing in the vault can be trusted
+    /// to be newer. Files carrying a note's id are rewritten from it in place; the
+    /// rest are moved under `.replaced/` (hidden from sync) rather than deleted.
+    fn replace(mut self) -> Result<VaultSyncSummary, AuralisError> {
+        let mut files = BTreeMap::new();
+        scan(self.root, self.root, &mut files)?;
+        self.taken = files.keys().cloned().collect();
+        self.conn.execute("DELETE FROM vault_files", [])?;
+
+        let notes: BTreeMap<String, NoteItem> = notes::list(self.conn, None, None, None)?
+            .into_iter()
+            .map(|n| (n.id.clone(), n))
+            .collect();
+
+        let aside = format!(".replaced/{}", Local::now().format("%Y-%m-%d %H%M%S"));
+        let mut placed = HashSet::new();
+        let mut moved = 0;
+        for rel in files.keys() {
+            let bytes = self.read(rel)?;
+            let id = Document::parse(&String::from_utf8_lossy(&bytes)).get("id").map(String::from);
+            match id.and_then(|id| notes.get(&id)) {
+                Some(note) if placed.insert(note.id.clone()) => self.export_note(note, Some(rel))?,
+                _ => {
+                    let (from, to) = (self.abs(rel), self.abs(&format!("{aside}/{rel}")));
+                    if let Some(parent) = to.parent() {
+                        fs::create_dir_all(parent).map_err(|e| io_error("create", parent, e))?;
+                    }
+                    fs::rename(&from, &to).map_err(|e| io_error("move", &from, e))?;
+                    self.taken.remove(rel);
+                    moved += 1;
+                }
+            }
+        }
+        if moved > 0 {
+            self.summary
+                .warnings
+                .push(format!("Moved {moved} file(s) not in the restored notes to {aside}"));
+        }
+
+        for note in notes.values().filter(|n| !placed.contains(&n.id)) {
+            self.export_note(note, None)?;
+        }
+
+        Ok(self.summary)
+    }
+}
+
+// The vault must already exist: recreating a folder that went missing would
+// make every note look deleted on disk.
+fn ensure_dir(dir: &Path) -> Result<(), AuralisError> {
+    if dir.is_dir() {
+        return Ok(());
+    }
+    Err(AuralisError::Io(format!(
+        "The vault folder {} is missing; sync is paused until it is back",
+        dir.display()
+    )))
+}
+
+/// Runs one sync pass between the notes table and the vault at `dir`. Notes are
+/// only deleted after a safety backup in `backups`.
+pub fn sync(conn: &mut Connection, dir: &Path, backups: &Path) -> Result<VaultSyncSummary, AuralisError> {
+    ensure_dir(dir)?;
+
+    let tx = conn.transaction()?;
+    let summary = Sync::new(&tx, dir, backups)?.run()?;
+    crate::store::revisions::prune(&tx)?;
+    tx.commit()?;
+
+    Ok(summary)
+}
+
+/// Syncs with the configured vault, if there is one.
+pub fn sync_configured(conn: &mut Connection, backups: &Path) -> Result<Option<VaultSyncSummary>, AuralisError> {
+    match dir(conn)? {
+        Some(dir) => sync(conn, &dir, backups).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Rewrites the configured vault from the notes table, for use after the database
+/// was replaced (a backup restore). An ordinary [`sync`] would take the restored
+/// notes for edits and deletions made in the app and merge them with the files.
+pub fn replace_configured(conn: &mut Connection, backups: &Path) -> Result<Option<VaultSyncSummary>, AuralisError> {
+    let Some(dir) = dir(conn)? else {
+        return Ok(None);
+    };
+    ensure_dir(&dir)?;
+
+    let tx = conn.transaction()?;
+    let summary = Sync::new(&tx, &dir, backups)?.replace()?;
+    tx.commit()?;
+
+    Ok(Some(summary))
+}
+
+pub fn dir(conn: &Connection) -> Result<Option<PathBuf>, AuralisError> {
+    Ok(crate::store::settings::get(conn, DIR_SETTING)?.map(PathBuf::from))
+}
+
+/// Points sync at a new vault (or, with `None`, turns it off), creating the folder
+/// if needed. Pairings with the previous vault are dropped.
+pub fn set_dir(conn: &Connection, dir: Option<&Path>) -> Result<(), AuralisError> {
+    let value = match dir {
+        Some(dir) if !dir.is_absolute() => {
+            return Err(AuralisError::validation("Vault directory must be an absolute path"))
+        }
+        Some(dir) => {
+            fs::create_dir_all(dir).map_err(|e| io_error("create", dir, e))?;
+            Some(dir.to_string_lossy().into_owned())
+        }
+        None => None,
+    };
+    crate::store::settings::set(conn, DIR_SETTING, value.as_deref())?;
+    conn.execute("DELETE FROM vault_files", [])?;
+    Ok(())
+}
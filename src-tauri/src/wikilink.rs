@@ -0,0 +1,131 @@
+//! `[[wiki-links]]` in note content, in the Obsidian flavour:
+//!
+//! ```text
+//! [[Target]]  [[Target|shown text]]  [[Target#Heading]]  ![[Embedded]]
+//! [[project:Kitchen renovation]]  [[task:Call the plumber]]  [[note:Ideas]]
+//! ```
+//!
+//! A `note:`, `project:` or `task:` prefix pins the kind of target; without one the
+//! link is resolved by the store (see `store::links`). Links inside fenced code
+//! blocks and inline code spans are ignored.
+
+use crate::domain::LinkKind;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    /// The written `kind:` prefix, if any.
+    pub kind: Option<LinkKind>,
+    pub target: String,
+    pub heading: Option<String>,
+    pub label: Option<String>,
+    /// Byte range of the whole `[[...]]` in the text.
+    pub span: Range<usize>,
+    /// Byte range of `target` in the text.
+    pub target_span: Range<usize>,
+}
+
+fn parse_inner(inner: &str, start: usize, span: Range<usize>) -> Option<WikiLink> {
+    let (rest, label) = match inner.split_once('|') {
+        Some((rest, label)) => (rest, Some(label.trim().to_string()).filter(|l| !l.is_empty())),
+        None => (inner, None),
+    };
+    let (rest, heading) = match rest.split_once('#') {
+        Some((rest, heading)) => (rest, Some(heading.trim().to_string()).filter(|h| !h.is_empty())),
+        None => (rest, None),
+    };
+
+    let (kind, target, offset) = match rest.split_once(':') {
+        Some((prefix, target)) => match prefix.trim().to_ascii_lowercase().parse::<LinkKind>() {
+            Ok(kind) => (Some(kind), target, prefix.len() + 1),
+            Err(_) => (None, rest, 0),
+        },
+        None => (None, rest, 0),
+    };
+
+    let trimmed = target.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let lead = target.len() - target.trim_start().len();
+    let target_start = start + offset + lead;
+
+    Some(WikiLink {
+        kind,
+        target: trimmed.to_string(),
+        heading,
+        label,
+        span,
+        target_span: target_start..target_start + trimmed.len(),
+    })
+}
+
+// Links on one line, skipping `inline code`.
+fn parse_line(text: &str, base: usize, out: &mut Vec<WikiLink>) {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'`' {
+            match text[i + 1..].find('`') {
+                Some(end) => i += end + 2,
+                None => return,
+            }
+            continue;
+        }
+        if text[i..].starts_with("[[") {
+            let open = i + 2;
+            let close = text[open..].find("]]").map(|c| open + c);
+            match close {
+                // `[[a [[b]]`: the link starts at the innermost opener.
+                Some(close) if !text[open..close].contains("[[") => {
+                    let start = if i > 0 && bytes[i - 1] == b'!' { i - 1 } else { i };
+                    let span = base + start..base + close + 2;
+                    if let Some(link) = parse_inner(&text[open..close], base + open, span) {
+                        out.push(link);
+                    }
+                    i = close + 2;
+                }
+                Some(_) => i = open + text[open..].find("[[").unwrap_or(0),
+                None => return,
+            }
+            continue;
+        }
+        i += text[i..].chars().next().map_or(1, char::len_utf8);
+    }
+}
+
+pub fn parse(text: &str) -> Vec<WikiLink> {
+    let mut out = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        match fence {
+            Some(marker) if trimmed.starts_with(marker) => fence = None,
+            Some(_) => {}
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            None => parse_line(line, offset, &mut out),
+        }
+        offset += line.len();
+    }
+
+    out
+}
+
+/// Replaces the target of every link for which `replace` returns a new one,
+/// keeping each link's prefix, heading and label.
+pub fn rewrite(text: &str, mut replace: impl FnMut(&WikiLink) -> Option<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for link in parse(text) {
+        if let Some(new) = replace(&link) {
+            out.push_str(&text[last..link.target_span.start]);
+            out.push_str(&new);
+            last = link.target_span.end;
+        }
+    }
+    out.push_str(&text[last..]);
+    out
+}
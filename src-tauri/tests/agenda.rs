@@ -0,0 +1,167 @@
+mod common;
+
+use auralis_lib::domain::TaskStatus;
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::agenda::{self, Agenda, AgendaItem, AgendaReason};
+use auralis_lib::store::calendar::{self, CalendarEventInput};
+use auralis_lib::store::tasks::{self, TaskPatch};
+use common::db;
+use rusqlite::Connection;
+
+fn task(conn: &mut Connection, title: &str, due: Option<&str>, scheduled: Option<&str>) -> String {
+    let id = tasks::add(conn, title, None, None).unwrap();
+    tasks::update(
+        conn,
+        &id,
+        TaskPatch {
+            due_at: Some(due.map(str::to_string)),
+            scheduled_at: Some(scheduled.map(str::to_string)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    id
+}
+
+fn event(conn: &Connection, title: &str, start: &str, end: &str, recurrence: Option<&str>) {
+    let input = CalendarEventInput {
+        title: title.into(),
+        start_at: start.into(),
+        end_at: end.into(),
+        ..Default::default()
+    };
+    calendar::add(conn, input, recurrence.map(str::to_string)).unwrap();
+}
+
+// "title (due|scheduled|event|event...)" for each item of each day.
+fn summary(agenda: &Agenda) -> Vec<Vec<String>> {
+    agenda
+        .days
+        .iter()
+        .map(|day| {
+            day.items
+                .iter()
+                .map(|item| match item {
+                    AgendaItem::Task { reason, task, .. } => {
+                        let reason = if *reason == AgendaReason::Due { "due" } else { "scheduled" };
+                        format!("{} ({reason})", task.title)
+                    }
+                    AgendaItem::Event { continued, occurrence, .. } => {
+                        let kind = if *continued { "event..." } else { "event" };
+                        format!("{} ({kind})", occurrence.event.title)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn days_follow_the_requested_time_zone() {
+    let mut conn = db();
+    // 22:00 on the 10th in New York, already the 11th in UTC.
+    task(&mut conn, "Late call", Some("2024-05-11T02:00:00Z"), None);
+
+    let ny = agenda::agenda(&conn, "2024-05-10", Some("2024-05-11"), Some("America/New_York")).unwrap();
+    assert_eq!(ny.time_zone.as_deref(), Some("America/New_York"));
+    assert_eq!((ny.days[0].date.as_str(), ny.days[0].start_at.as_str()), ("2024-05-10", "2024-05-10T04:00:00.000Z"));
+    assert_eq!(summary(&ny), [vec!["Late call (due)"], vec![]]);
+
+    let utc = agenda::agenda(&conn, "2024-05-10", Some("2024-05-11"), Some("UTC")).unwrap();
+    assert_eq!(summary(&utc), [vec![], vec!["Late call (due)"]]);
+}
+
+#[test]
+fn day_boundaries_follow_daylight_saving_changes() {
+    let conn = db();
+    let day = agenda::agenda(&conn, "2024-03-10", None, Some("America/New_York")).unwrap();
+    assert_eq!(day.days.len(), 1);
+    // Clocks go forward that night, so the day is 23 hours long.
+    assert_eq!(day.days[0].start_at, "2024-03-10T05:00:00.000Z");
+    assert_eq!(day.days[0].end_at, "2024-03-11T04:00:00.000Z");
+}
+
+#[test]
+fn tasks_are_listed_on_their_due_and_scheduled_days() {
+    let mut conn = db();
+    task(&mut conn, "Both same day", Some("2024-05-10T17:00:00Z"), Some("2024-05-10T09:00:00Z"));
+    task(&mut conn, "Start then finish", Some("2024-05-11T17:00:00Z"), Some("2024-05-10T08:00:00Z"));
+    task(&mut conn, "Undated", None, None);
+    let done = task(&mut conn, "Already done", Some("2024-05-10T12:00:00Z"), None);
+    tasks::set_status(&mut conn, &done, TaskStatus::Done).unwrap();
+    let deferred = task(&mut conn, "Put off", Some("2024-05-10T12:00:00Z"), None);
+    tasks::set_status(&mut conn, &deferred, TaskStatus::Deferred).unwrap();
+
+    let agenda = agenda::agenda(&conn, "2024-05-10", Some("2024-05-11"), Some("UTC")).unwrap();
+    assert_eq!(
+        summary(&agenda),
+        [
+            vec!["Start then finish (scheduled)", "Both same day (due)"],
+            vec!["Start then finish (due)"],
+        ]
+    );
+    assert!(agenda.overdue.is_empty());
+}
+
+#[test]
+fn overdue_tasks_are_collected_before_the_range() {
+    let mut conn = db();
+    task(&mut conn, "Newer", Some("2024-05-09T12:00:00Z"), None);
+    task(&mut conn, "Older", Some("2024-05-01T12:00:00Z"), None);
+    task(&mut conn, "Only scheduled earlier", None, Some("2024-05-01T12:00:00Z"));
+    let done = task(&mut conn, "Done late", Some("2024-05-02T12:00:00Z"), None);
+    tasks::set_status(&mut conn, &done, TaskStatus::Done).unwrap();
+    let deferred = task(&mut conn, "Put off", Some("2024-05-03T12:00:00Z"), None);
+    tasks::set_status(&mut conn, &deferred, TaskStatus::Deferred).unwrap();
+
+    let agenda = agenda::agenda(&conn, "2024-05-10", None, Some("UTC")).unwrap();
+    let overdue: Vec<&str> = agenda.overdue.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(overdue, ["Older", "Newer"]);
+}
+
+#[test]
+fn events_are_merged_in_time_order() {
+    let mut conn = db();
+    task(&mut conn, "Prep slides", Some("2024-05-10T09:00:00Z"), None);
+    event(&conn, "Standup", "2024-05-10T09:00:00Z", "2024-05-10T09:15:00Z", Some("FREQ=DAILY"));
+    event(&conn, "Offsite", "2024-05-10T15:00:00Z", "2024-05-11T12:00:00Z", None);
+    event(&conn, "Lunch", "2024-05-10T12:00:00Z", "2024-05-10T13:00:00Z", None);
+
+    let agenda = agenda::agenda(&conn, "2024-05-10", Some("2024-05-11"), Some("UTC")).unwrap();
+    assert_eq!(
+        summary(&agenda),
+        [
+            vec!["Standup (event)", "Prep slides (due)", "Lunch (event)", "Offsite (event)"],
+            vec!["Offsite (event...)", "Standup (event)"],
+        ]
+    );
+    assert_eq!(agenda.days[1].items[0].at(), "2024-05-11T00:00:00.000Z");
+}
+
+#[test]
+fn repeating_events_keep_their_time_in_the_agenda_zone() {
+    let conn = db();
+    // 09:00 in New York, in winter.
+    event(&conn, "Standup", "2024-01-08T14:00:00Z", "2024-01-08T14:15:00Z", Some("FREQ=WEEKLY"));
+
+    // Still 09:00 there once clocks have gone forward.
+    let agenda = agenda::agenda(&conn, "2024-05-13", None, Some("America/New_York")).unwrap();
+    assert_eq!(agenda.days[0].items[0].at(), "2024-05-13T13:00:00.000Z");
+}
+
+#[test]
+fn invalid_ranges_are_rejected() {
+    let conn = db();
+    for (start, end, tz) in [
+        ("10/05/2024", None, None),
+        ("2024-05-10", Some("2024-05-09"), None),
+        ("2024-01-01", Some("2024-06-01"), None),
+        ("2024-05-10", None, Some("Mars/Olympus_Mons")),
+    ] {
+        let err = agenda::agenda(&conn, start, end, tz).map(|_| ()).unwrap_err();
+        assert!(matches!(err, AuralisError::Validation(_)), "{start} {end:?} {tz:?}");
+    }
+    // The system time zone is used without one.
+    let today = agenda::agenda(&conn, "2024-05-10", None, None).unwrap();
+    assert_eq!((today.time_zone, today.days.len()), (None, 1));
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use auralis_lib::backup::{self, BackupKind};
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::{notes, tasks};
+use common::{db, TempDir};
+use rusqlite::Connection;
+use std::fs;
+
+#[test]
+fn create_and_list() {
+    let conn = db();
+    let dir = TempDir::new();
+    assert!(backup::list(&dir.0.join("missing")).unwrap().is_empty());
+
+    let manual = backup::create(&conn, &dir.0, BackupKind::Manual).unwrap();
+    let safety = backup::before(&conn, &dir.0, "note-delete").unwrap();
+
+    assert_eq!(manual.kind, BackupKind::Manual);
+    assert_eq!(manual.reason, None);
+    assert!(manual.size_bytes > 0);
+    assert_eq!(safety.kind, BackupKind::Safety);
+    assert_eq!(safety.reason.as_deref(), Some("note-delete"));
+
+    let listed: Vec<String> = backup::list(&dir.0).unwrap().into_iter().map(|b| b.id).collect();
+    assert_eq!(listed, [safety.id, manual.id]);
+}
+
+#[test]
+fn each_kind_keeps_only_its_newest_copies() {
+    let conn = db();
+    let dir = TempDir::new();
+    let first = backup::create(&conn, &dir.0, BackupKind::Manual).unwrap();
+    for _ in 0..11 {
+        backup::create(&conn, &dir.0, BackupKind::Manual).unwrap();
+    }
+    backup::create(&conn, &dir.0, BackupKind::Daily).unwrap();
+
+    let all = backup::list(&dir.0).unwrap();
+    assert_eq!(all.iter().filter(|b| b.kind == BackupKind::Manual).count(), 10);
+    assert_eq!(all.iter().filter(|b| b.kind == BackupKind::Daily).count(), 1);
+    assert!(!all.iter().any(|b| b.id == first.id));
+}
+
+#[test]
+fn scheduled_backups_are_taken_once_per_period() {
+    let conn = db();
+    let dir = TempDir::new();
+
+    let kinds: Vec<BackupKind> = backup::run_scheduled(&conn, &dir.0).unwrap().iter().map(|b| b.kind).collect();
+    assert_eq!(kinds, [BackupKind::Daily, BackupKind::Weekly]);
+    assert!(backup::run_scheduled(&conn, &dir.0).unwrap().is_empty());
+}
+
+#[test]
+fn files_not_named_like_backups_are_ignored() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    let taken = backup::create(&conn, &dir.0, BackupKind::Manual).unwrap();
+    fs::copy(
+        dir.0.join(format!("{}.db", taken.id)),
+        dir.0.join("auralis-pre-migration-v3-20250101T120000Z.db"),
+    )
+    .unwrap();
+
+    let ids: Vec<String> = backup::list(&dir.0).unwrap().into_iter().map(|b| b.id).collect();
+    assert_eq!(ids, [taken.id]);
+    let legacy = "auralis-pre-migration-v3-20250101T120000Z";
+    assert!(matches!(backup::restore(&mut conn, &dir.0, legacy), Err(AuralisError::NotFound(_))));
+}
+
+#[test]
+fn restore_brings_back_the_backed_up_state() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    let kept = tasks::add(&conn, "Keep me", None, None).unwrap();
+    let snapshot = backup::create(&conn, &dir.0, BackupKind::Manual).unwrap();
+
+    let note = notes::add(&mut conn, "After the backup", "Gone once restored", None, None).unwrap();
+    conn.execute("DELETE FROM tasks WHERE id = ?1", [&kept]).unwrap();
+
+    let undo = backup::restore(&mut conn, &dir.0, &snapshot.id).unwrap();
+    assert_eq!(undo.kind, BackupKind::Safety);
+    assert_eq!(undo.reason.as_deref(), Some("restore"));
+
+    assert_eq!(tasks::get(&conn, &kept).unwrap().title, "Keep me");
+    assert!(matches!(notes::get(&conn, &note), Err(AuralisError::NotFound(_))));
+
+    // The safety copy undoes the restore.
+    backup::restore(&mut conn, &dir.0, &undo.id).unwrap();
+    assert!(notes::get(&conn, &note).is_ok());
+}
+
+#[test]
+fn restore_into_a_wal_database_file() {
+    let source = db();
+    let dir = TempDir::new();
+    let task = tasks::add(&source, "From the backup", None, None).unwrap();
+    let snapshot = backup::create(&source, &dir.0, BackupKind::Manual).unwrap();
+
+    let mut live = Connection::open(dir.0.join("live.db")).unwrap();
+    live.pragma_update(None, "journal_mode", "WAL").unwrap();
+    backup::restore(&mut live, &dir.0, &snapshot.id).unwrap();
+
+    assert_eq!(tasks::get(&live, &task).unwrap().title, "From the backup");
+    let mode: String = live.query_row("PRAGMA journal_mode", [], |r| r.get(0)).unwrap();
+    assert_eq!(mode, "wal");
+}
+
+#[test]
+fn damaged_backups_are_refused() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    let task = tasks::add(&conn, "Still here", None, None).unwrap();
+
+    let id = "auralis-20260101T000000000Z-manual";
+    let path = dir.0.join(format!("{id}.db"));
+    fs::write(&path, b"definitely not sqlite").unwrap();
+
+    assert!(matches!(backup::restore(&mut conn, &dir.0, id), Err(AuralisError::Validation(_))));
+    assert!(tasks::get(&conn, &task).is_ok());
+    // Checking it only ever reads the file.
+    assert_eq!(fs::read(&path).unwrap(), b"definitely not sqlite");
+    // Nothing was swapped in, so no safety backup was needed either.
+    assert_eq!(backup::list(&dir.0).unwrap().len(), 1);
+}
+
+#[test]
+fn unknown_ids_are_not_found() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    backup::create(&conn, &dir.0, BackupKind::Manual).unwrap();
+
+    for id in ["auralis-20260101T000000000Z-manual", "../auralis", "live"] {
+        assert!(matches!(backup::restore(&mut conn, &dir.0, id), Err(AuralisError::NotFound(_))));
+    }
+}
@@ -0,0 +1,40 @@
+use auralis_lib::db;
+use auralis_lib::domain::ProjectStatus;
+use auralis_lib::store::{projects, tasks};
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+
+/// A fresh database with every migration applied.
+#[allow(dead_code)]
+pub fn db() -> Connection {
+    db::open_in_memory().expect("open in-memory database")
+}
+
+/// An active project with one open task, returned as `(project_id, task_id)`.
+#[allow(dead_code)]
+pub fn active_project(conn: &Connection, name: &str) -> (String, String) {
+    let project = projects::add(conn, name, None).expect("add project");
+    let task = tasks::add(conn, "Next action", None, Some(&project)).expect("add task");
+    projects::set_status(conn, &project, ProjectStatus::Active).expect("activate project");
+    (project, task)
+}
+
+/// A fresh directory under the system temp dir, removed when dropped.
+#[allow(dead_code)]
+pub struct TempDir(pub PathBuf);
+
+#[allow(dead_code)]
+impl TempDir {
+    pub fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("auralis-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
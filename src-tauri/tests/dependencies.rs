@@ -0,0 +1,113 @@
+mod common;
+
+use auralis_lib::domain::TaskStatus;
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::tasks::{self, TaskItem};
+use auralis_lib::store::{dependencies, subtasks};
+use common::db;
+use rusqlite::Connection;
+
+fn titles(items: &[TaskItem]) -> Vec<&str> {
+    items.iter().map(|t| t.title.as_str()).collect()
+}
+
+fn blocked(conn: &Connection, id: &str) -> bool {
+    tasks::get(conn, id).unwrap().blocked
+}
+
+#[test]
+fn dependencies_are_listed_both_ways() {
+    let conn = db();
+    let paint = tasks::add(&conn, "Paint walls", None, None).unwrap();
+    let buy = tasks::add(&conn, "Buy paint", None, None).unwrap();
+    let tape = tasks::add(&conn, "Tape edges", None, None).unwrap();
+    let hang = tasks::add(&conn, "Hang pictures", None, None).unwrap();
+
+    dependencies::add(&conn, &paint, &buy).unwrap();
+    let deps = dependencies::add(&conn, &paint, &tape).unwrap();
+    assert_eq!(titles(&deps.depends_on), ["Buy paint", "Tape edges"]);
+    // Adding it again changes nothing.
+    assert_eq!(dependencies::add(&conn, &paint, &tape).unwrap().depends_on.len(), 2);
+
+    dependencies::add(&conn, &hang, &paint).unwrap();
+    let deps = dependencies::list(&conn, &paint).unwrap();
+    assert_eq!(titles(&deps.dependents), ["Hang pictures"]);
+
+    let deps = dependencies::remove(&conn, &paint, &tape).unwrap();
+    assert_eq!(titles(&deps.depends_on), ["Buy paint"]);
+    let err = dependencies::remove(&conn, &paint, &tape).map(|_| ()).unwrap_err();
+    assert!(matches!(err, AuralisError::NotFound(_)));
+    let err = dependencies::list(&conn, "task_missing").map(|_| ()).unwrap_err();
+    assert!(matches!(err, AuralisError::NotFound(_)));
+}
+
+#[test]
+fn cycles_are_refused() {
+    let mut conn = db();
+    let a = tasks::add(&conn, "A", None, None).unwrap();
+    let b = tasks::add(&conn, "B", None, None).unwrap();
+    let c = tasks::add(&conn, "C", None, None).unwrap();
+    dependencies::add(&conn, &b, &a).unwrap();
+    dependencies::add(&conn, &c, &b).unwrap();
+
+    for (task, on) in [(&a, &a), (&a, &b), (&a, &c)] {
+        let err = dependencies::add(&conn, task, on).map(|_| ()).unwrap_err();
+        assert!(matches!(err, AuralisError::Validation(_)), "{task} on {on}");
+    }
+
+    // A parent may wait on its subtask, but not the other way round.
+    let child = subtasks::add(&mut conn, &a, "A.1").unwrap();
+    let err = dependencies::add(&conn, &child, &a).map(|_| ()).unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+    dependencies::add(&conn, &a, &child).unwrap();
+}
+
+#[test]
+fn tasks_are_blocked_until_every_dependency_is_done() {
+    let mut conn = db();
+    let paint = tasks::add(&conn, "Paint walls", None, None).unwrap();
+    let buy = tasks::add(&conn, "Buy paint", None, None).unwrap();
+    let tape = tasks::add(&conn, "Tape edges", None, None).unwrap();
+    dependencies::add(&conn, &paint, &buy).unwrap();
+    dependencies::add(&conn, &paint, &tape).unwrap();
+
+    let listed: Vec<(String, bool)> = tasks::list(&conn, None, None)
+        .unwrap()
+        .into_iter()
+        .map(|t| (t.title, t.blocked))
+        .collect();
+    assert!(listed.contains(&("Paint walls".into(), true)));
+    assert!(listed.contains(&("Buy paint".into(), false)));
+    assert!(tasks::query(&conn, "paint").unwrap().iter().any(|t| t.blocked));
+
+    // Deferring a dependency does not release anything.
+    tasks::set_status(&mut conn, &buy, TaskStatus::Deferred).unwrap();
+    let change = tasks::set_status(&mut conn, &buy, TaskStatus::Done).unwrap();
+    assert!(change.unblocked.is_empty());
+    assert!(blocked(&conn, &paint));
+
+    let change = tasks::set_status(&mut conn, &tape, TaskStatus::Done).unwrap();
+    assert_eq!(titles(&change.unblocked), ["Paint walls"]);
+    assert!(!blocked(&conn, &paint));
+
+    // Reopening a dependency blocks its dependents again.
+    tasks::set_status(&mut conn, &tape, TaskStatus::Todo).unwrap();
+    assert!(blocked(&conn, &paint));
+}
+
+#[test]
+fn completing_a_tree_reports_what_it_unblocked() {
+    let mut conn = db();
+    let parent = tasks::add(&conn, "Renovate", None, None).unwrap();
+    let strip = subtasks::add(&mut conn, &parent, "Strip wallpaper").unwrap();
+    let after_strip = tasks::add(&conn, "Plaster", None, None).unwrap();
+    let after_parent = tasks::add(&conn, "Move back in", None, None).unwrap();
+    let done_already = tasks::add(&conn, "Old chore", None, None).unwrap();
+    dependencies::add(&conn, &after_strip, &strip).unwrap();
+    dependencies::add(&conn, &after_parent, &parent).unwrap();
+    dependencies::add(&conn, &done_already, &strip).unwrap();
+    tasks::set_status(&mut conn, &done_already, TaskStatus::Done).unwrap();
+
+    let change = subtasks::complete(&mut conn, &parent).unwrap();
+    assert_eq!(titles(&change.unblocked), ["Plaster", "Move back in"]);
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use auralis_lib::error::AuralisError;
+use auralis_lib::focus::{self, FocusConfig, FocusEvent, FocusPhase, FocusSession};
+use auralis_lib::store::{tasks, time_entries};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use common::db;
+
+fn at(minutes: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 5, 10, 9, 0, 0).unwrap() + Duration::minutes(minutes)
+}
+
+fn config() -> FocusConfig {
+    FocusConfig {
+        work_minutes: 25,
+        short_break_minutes: 5,
+        long_break_minutes: 15,
+        long_break_every: 2,
+        rounds: 3,
+    }
+}
+
+// Each event as a short line, e.g. "work->short_break @25", "worked 0-25", "completed".
+fn describe(events: &[FocusEvent]) -> Vec<String> {
+    let minute = |t: DateTime<Utc>| (t - at(0)).num_minutes();
+    events
+        .iter()
+        .map(|event| match event {
+            FocusEvent::PhaseChanged(change) => {
+                let phase = |p: FocusPhase| serde_json::to_value(p).unwrap().as_str().unwrap().to_string();
+                let when = DateTime::parse_from_rfc3339(&change.at).unwrap().with_timezone(&Utc);
+                format!("{}->{} @{}", phase(change.from), phase(change.to), minute(when))
+            }
+            FocusEvent::Worked { started_at, ended_at, .. } => {
+                format!("worked {}-{}", minute(*started_at), minute(*ended_at))
+            }
+            FocusEvent::Completed(summary) if summary.stopped_early => "stopped".to_string(),
+            FocusEvent::Completed(_) => "completed".to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn a_session_cycles_through_work_and_breaks() {
+    let mut session = FocusSession::new("task_x", "Write", config(), at(0)).unwrap();
+    assert!(session.advance(at(24)).is_empty());
+    assert_eq!(session.status(at(24)).remaining_seconds, 60);
+
+    assert_eq!(describe(&session.advance(at(26))), ["worked 0-25", "work->short_break @25"]);
+    let status = session.status(at(26));
+    assert_eq!((status.phase, status.round, status.rounds_completed), (FocusPhase::ShortBreak, 1, 1));
+
+    // Asleep for an hour: every phase that ran out is caught up on, in order.
+    assert_eq!(
+        describe(&session.advance(at(90))),
+        [
+            "short_break->work @30",
+            "worked 30-55",
+            "work->long_break @55",
+            "long_break->work @70",
+        ]
+    );
+    assert_eq!(describe(&session.advance(at(95))), ["worked 70-95", "completed"]);
+    assert!(session.is_finished());
+    assert_eq!(session.status(at(95)).focused_seconds, 75 * 60);
+    assert!(session.advance(at(200)).is_empty());
+}
+
+#[test]
+fn pausing_holds_the_clock() {
+    let mut session = FocusSession::new("task_x", "Write", config(), at(0)).unwrap();
+    assert_eq!(describe(&session.pause(at(10)).unwrap()), ["worked 0-10"]);
+    assert!(matches!(session.pause(at(11)).unwrap_err(), AuralisError::Validation(_)));
+
+    // Paused, nothing runs out.
+    assert!(session.advance(at(60)).is_empty());
+    assert_eq!(session.status(at(60)).remaining_seconds, 15 * 60);
+
+    session.resume(at(60)).unwrap();
+    assert!(matches!(session.resume(at(61)).unwrap_err(), AuralisError::Validation(_)));
+    assert_eq!(describe(&session.advance(at(75))), ["worked 60-75", "work->short_break @75"]);
+}
+
+#[test]
+fn skipping_and_stopping() {
+    let mut session = FocusSession::new("task_x", "Write", config(), at(0)).unwrap();
+    assert_eq!(describe(&session.skip(at(20))), ["worked 0-20", "work->short_break @20"]);
+    assert_eq!(describe(&session.skip(at(21))), ["short_break->work @21"]);
+
+    session.pause(at(30)).unwrap();
+    assert_eq!(describe(&session.stop(at(40))), ["stopped"]);
+    let status = session.status(at(40));
+    assert_eq!((status.rounds_completed, status.focused_seconds), (1, 29 * 60));
+    assert!(session.is_finished());
+}
+
+#[test]
+fn invalid_configurations_are_refused() {
+    for config in [
+        FocusConfig { work_minutes: 0, ..config() },
+        FocusConfig { long_break_minutes: 500, ..config() },
+        FocusConfig { rounds: 0, ..config() },
+        FocusConfig { long_break_every: 13, ..config() },
+    ] {
+        let err = FocusSession::new("task_x", "Write", config, at(0)).map(|_| ()).unwrap_err();
+        assert!(matches!(err, AuralisError::Validation(_)), "{config:?}");
+    }
+    // Omitted settings take the classic defaults.
+    let partial: FocusConfig = serde_json::from_str(r#"{"work_minutes": 50}"#).unwrap();
+    assert_eq!((partial.work_minutes, partial.short_break_minutes, partial.rounds), (50, 5, 4));
+}
+
+#[test]
+fn work_is_logged_as_time_entries() {
+    let conn = db();
+    let task = tasks::add(&conn, "Write", None, None).unwrap();
+    let mut session = FocusSession::new(&task, "Write", config(), at(0)).unwrap();
+
+    let mut events = session.pause(at(10)).unwrap();
+    session.resume(at(12)).unwrap();
+    events.extend(session.advance(at(60)));
+    let logged = focus::record(&conn, &session, &events).unwrap();
+
+    let spans: Vec<(i64, Option<&str>)> = logged.iter().map(|e| (e.seconds / 60, e.note.as_deref())).collect();
+    assert_eq!(
+        spans,
+        [(10, Some("Focus round 1")), (15, Some("Focus round 1")), (25, Some("Focus round 2"))]
+    );
+    assert_eq!(time_entries::list(&conn, Some(&task), None, None).unwrap().len(), 3);
+}
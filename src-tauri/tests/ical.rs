@@ -0,0 +1,84 @@
+mod common;
+
+use auralis_lib::store::calendar::{self, CalendarEventInput};
+use auralis_lib::store::ical;
+use chrono::{Local, NaiveDate, TimeZone, Utc};
+use common::db;
+use rusqlite::{params, Connection};
+
+fn calendar_of(items: &str) -> String {
+    format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{items}END:VCALENDAR\r\n")
+}
+
+fn todo(uid: &str, status: &str, own_status: &str) -> String {
+    format!(
+        "BEGIN:VTODO\r\nUID:{uid}\r\nSUMMARY:{uid}\r\nSTATUS:{status}\r\nX-AURALIS-STATUS:{own_status}\r\nEND:VTODO\r\n"
+    )
+}
+
+fn status_of(conn: &Connection, uid: &str) -> String {
+    conn.query_row("SELECT status FROM tasks WHERE ical_uid = ?1", params![uid], |r| r.get(0))
+        .unwrap()
+}
+
+#[test]
+fn oversized_durations_skip_the_event() {
+    let mut conn = db();
+    let text = calendar_of(
+        "BEGIN:VEVENT\r\nUID:huge\r\nSUMMARY:Forever\r\nDTSTART:20300101T090000Z\r\nDURATION:P99999999999999W\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:long\r\nSUMMARY:Longer\r\nDTSTART:20300101T090000Z\r\nDURATION:P9999999W\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:talk\r\nSUMMARY:Talk\r\nDTSTART:20300101T090000Z\r\nDURATION:PT1H30M\r\nEND:VEVENT\r\n",
+    );
+    let summary = ical::import(&mut conn, &text, None).unwrap();
+    assert_eq!(summary.events_created, 1);
+    assert_eq!(summary.warnings.len(), 2, "{:?}", summary.warnings);
+
+    let end: String = conn
+        .query_row("SELECT end_at FROM calendar_events WHERE ical_uid = 'talk'", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(end, "2030-01-01T10:30:00.000Z");
+}
+
+#[test]
+fn the_standard_status_wins_over_our_own() {
+    let mut conn = db();
+    let text = calendar_of(&[
+        todo("finished", "COMPLETED", "todo"),
+        todo("started", "IN-PROCESS", "deferred"),
+        todo("parked", "NEEDS-ACTION", "deferred"),
+        todo("reopened", "NEEDS-ACTION", "done"),
+    ]
+    .concat());
+    ical::import(&mut conn, &text, None).unwrap();
+
+    let statuses: Vec<String> = ["finished", "started", "parked", "reopened"]
+        .iter()
+        .map(|uid| status_of(&conn, uid))
+        .collect();
+    assert_eq!(statuses, ["done", "doing", "deferred", "todo"]);
+}
+
+#[test]
+fn floating_until_is_exported_in_utc() {
+    let mut conn = db();
+    let input = CalendarEventInput {
+        title: "Standup".into(),
+        start_at: "2030-06-03T09:00:00Z".into(),
+        end_at: "2030-06-03T09:15:00Z".into(),
+        ..Default::default()
+    };
+    calendar::add(&conn, input, Some("RRULE:FREQ=DAILY;UNTIL=20300607T090000".into())).unwrap();
+
+    let until = NaiveDate::from_ymd_opt(2030, 6, 7).unwrap().and_hms_opt(9, 0, 0).unwrap();
+    let until = Local.from_local_datetime(&until).unwrap().with_timezone(&Utc);
+    let text = ical::export(&conn).unwrap().text;
+    assert!(
+        text.contains(&format!("RRULE:FREQ=DAILY;UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))),
+        "{text}"
+    );
+
+    // And it reads back as the same series.
+    ical::import(&mut conn, &text, None).unwrap();
+    let found = calendar::list_range(&conn, "2030-06-01T00:00:00Z", "2030-06-30T00:00:00Z").unwrap();
+    assert_eq!(found.len(), 5);
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use auralis_lib::domain::LinkKind;
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::tasks::TaskPatch;
+use auralis_lib::store::{links, notes, projects, tasks, workspace};
+use common::db;
+use rusqlite::Connection;
+
+fn targets(conn: &Connection, note_id: &str) -> Vec<(Option<LinkKind>, Option<String>)> {
+    links::entity_links(conn, LinkKind::Note, note_id)
+        .unwrap()
+        .outgoing
+        .into_iter()
+        .map(|l| (l.target_kind, l.target_id))
+        .collect()
+}
+
+#[test]
+fn links_resolve_to_notes_projects_and_tasks() {
+    let mut conn = db();
+    let project = projects::add(&conn, "Kitchen", None).unwrap();
+    let task = tasks::add(&conn, "Call the plumber", None, Some(&project)).unwrap();
+    let ideas = notes::add(&mut conn, "Ideas", "tiles", None, None).unwrap();
+
+    let source = notes::add(
+        &mut conn,
+        "Log",
+        "See [[ideas]] and [[Kitchen|the kitchen]].\nNext: [[task:Call the plumber]], [[Nowhere]].",
+        None,
+        None,
+    )
+    .unwrap();
+
+    let out = links::entity_links(&conn, LinkKind::Note, &source).unwrap().outgoing;
+    assert_eq!(out.len(), 4);
+    assert_eq!(out[0].target_id.as_deref(), Some(ideas.as_str()));
+    assert_eq!(out[0].target_title.as_deref(), Some("Ideas"));
+    assert_eq!((out[1].target_kind, out[1].label.as_deref()), (Some(LinkKind::Project), Some("the kitchen")));
+    assert_eq!(out[2].kind, Some(LinkKind::Task));
+    assert_eq!(out[2].target_id.as_deref(), Some(task.as_str()));
+    assert_eq!((out[3].target_kind, out[3].target_id.as_deref()), (None, None));
+
+    let backlinks = links::entity_links(&conn, LinkKind::Task, &task).unwrap().backlinks;
+    assert_eq!(backlinks.len(), 1);
+    assert_eq!(backlinks[0].note_id, source);
+    assert_eq!(backlinks[0].contexts, ["Next: [[task:Call the plumber]], [[Nowhere]]."]);
+}
+
+#[test]
+fn links_in_code_are_ignored() {
+    let mut conn = db();
+    notes::add(&mut conn, "Target", "x", None, None).unwrap();
+    let source = notes::add(
+        &mut conn,
+        "Source",
+        "`[[Target]]` inline\n```\n[[Target]]\n```\n![[Target]] embedded",
+        None,
+        None,
+    )
+    .unwrap();
+
+    let out = links::entity_links(&conn, LinkKind::Note, &source).unwrap().outgoing;
+    assert_eq!(out.len(), 1);
+    assert_eq!(out[0].position, "`[[Target]]` inline\n```\n[[Target]]\n```\n".len() as i64);
+}
+
+#[test]
+fn editing_a_note_updates_its_links() {
+    let mut conn = db();
+    let a = notes::add(&mut conn, "A", "a", None, None).unwrap();
+    let b = notes::add(&mut conn, "B", "b", None, None).unwrap();
+    let source = notes::add(&mut conn, "Source", "[[A]]", None, None).unwrap();
+
+    notes::update(&mut conn, &source, "Source", "[[B]] only", None, None).unwrap();
+    assert_eq!(targets(&conn, &source), [(Some(LinkKind::Note), Some(b))]);
+    assert!(links::backlinks(&conn, LinkKind::Note, &a).unwrap().is_empty());
+}
+
+#[test]
+fn dangling_links_resolve_once_the_target_exists() {
+    let mut conn = db();
+    let source = notes::add(&mut conn, "Source", "[[Later]] and [[task:Write it]]", None, None).unwrap();
+    assert_eq!(targets(&conn, &source), [(None, None), (None, None)]);
+
+    let later = notes::add(&mut conn, "Later", "now here", None, None).unwrap();
+    let task = tasks::add(&conn, "Write it", None, None).unwrap();
+
+    assert_eq!(
+        targets(&conn, &source),
+        [(Some(LinkKind::Note), Some(later.clone())), (Some(LinkKind::Task), Some(task))]
+    );
+
+    // Deleting the target leaves the link dangling again.
+    notes::delete(&conn, &later).unwrap();
+    assert_eq!(targets(&conn, &source)[0], (None, None));
+}
+
+#[test]
+fn renaming_a_note_rewrites_links_to_it() {
+    let mut conn = db();
+    let target = notes::add(&mut conn, "Draft", "x", None, None).unwrap();
+    let source = notes::add(&mut conn, "Source", "Read [[draft#Intro|this]] and [[Other]].", None, None).unwrap();
+
+    notes::update(&mut conn, &target, "Final", "x", None, None).unwrap();
+
+    assert_eq!(
+        notes::get(&conn, &source).unwrap().content,
+        "Read [[Final#Intro|this]] and [[Other]]."
+    );
+    assert_eq!(targets(&conn, &source)[0], (Some(LinkKind::Note), Some(target)));
+}
+
+#[test]
+fn renaming_a_project_or_task_rewrites_links_to_it() {
+    let mut conn = db();
+    let project = projects::add(&conn, "Kitchen", None).unwrap();
+    let task = tasks::add(&conn, "Call plumber", None, None).unwrap();
+    // A note already has the project's new name, so the plain link needs a prefix.
+    notes::add(&mut conn, "Renovation", "unrelated", None, None).unwrap();
+    let source = notes::add(&mut conn, "Source", "[[Kitchen]] / [[task:Call plumber]]", None, None).unwrap();
+
+    projects::rename(&mut conn, &project, "Renovation").unwrap();
+    tasks::update(
+        &mut conn,
+        &task,
+        TaskPatch {
+            title: Some("Call the plumber".into()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        notes::get(&conn, &source).unwrap().content,
+        "[[project:Renovation]] / [[task:Call the plumber]]"
+    );
+    assert_eq!(
+        targets(&conn, &source),
+        [(Some(LinkKind::Project), Some(project)), (Some(LinkKind::Task), Some(task))]
+    );
+}
+
+#[test]
+fn workspace_import_rebuilds_links() {
+    let mut conn = db();
+    let target = notes::add(&mut conn, "Target", "x", None, None).unwrap();
+    let source = notes::add(&mut conn, "Source", "[[Target]]", None, None).unwrap();
+    let doc = workspace::export(&conn).unwrap();
+
+    let mut other = db();
+    workspace::import(&mut other, &doc, workspace::ImportMode::Replace).unwrap();
+    assert_eq!(targets(&other, &source), [(Some(LinkKind::Note), Some(target.clone()))]);
+
+    workspace::import(&mut conn, &doc, workspace::ImportMode::Replace).unwrap();
+    assert_eq!(targets(&conn, &source), [(Some(LinkKind::Note), Some(target))]);
+}
+
+#[test]
+fn entity_links_requires_an_existing_entity() {
+    let conn = db();
+    let err = links::entity_links(&conn, LinkKind::Project, "project_missing").unwrap_err();
+    assert!(matches!(err, AuralisError::NotFound(_)));
+}
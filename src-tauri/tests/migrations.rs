@@ -0,0 +1,94 @@
+mod common;
+
+use auralis_lib::backup;
+use auralis_lib::migrations::{self, Migration, MIGRATIONS};
+use common::TempDir;
+use rusqlite::Connection;
+
+fn tables(conn: &Connection) -> Vec<String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name")
+        .unwrap();
+    stmt.query_map([], |r| r.get(0)).unwrap().map(Result::unwrap).collect()
+}
+
+fn foreign_keys(conn: &Connection) -> bool {
+    conn.query_row("PRAGMA foreign_keys", [], |r| r.get(0)).unwrap()
+}
+
+#[test]
+fn a_fresh_database_reaches_the_latest_version() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    migrations::migrate(&mut conn, None).unwrap();
+
+    assert_eq!(migrations::current_version(&conn).unwrap(), migrations::latest_version());
+    assert_eq!(migrations::latest_version(), MIGRATIONS.len() as i64);
+    assert!(tables(&conn).contains(&"project_templates".to_string()));
+
+    // Running it again has nothing left to do.
+    migrations::migrate(&mut conn, None).unwrap();
+    assert_eq!(migrations::current_version(&conn).unwrap(), migrations::latest_version());
+}
+
+#[test]
+fn an_older_database_is_upgraded_step_by_step() {
+    let dir = TempDir::new();
+    let mut conn = Connection::open_in_memory().unwrap();
+    migrations::migrate_with(&mut conn, &MIGRATIONS[..3], None).unwrap();
+    assert_eq!(migrations::current_version(&conn).unwrap(), 3);
+    conn.execute(
+        "INSERT INTO tasks (id, area_id, title) VALUES ('task_old', 'area_admin_life', 'From v3')",
+        [],
+    )
+    .unwrap();
+
+    migrations::migrate(&mut conn, Some(&dir.0)).unwrap();
+    assert_eq!(migrations::current_version(&conn).unwrap(), migrations::latest_version());
+    let title: String = conn
+        .query_row("SELECT title FROM tasks WHERE id = 'task_old'", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(title, "From v3");
+    assert!(foreign_keys(&conn));
+
+    // The database was copied as it stood before the upgrade.
+    let taken = backup::list(&dir.0).unwrap();
+    assert_eq!(taken.len(), 1);
+    assert_eq!(taken[0].reason.as_deref(), Some("migration-v3"));
+}
+
+#[test]
+fn each_step_applies_on_top_of_the_one_before() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    for n in 1..=MIGRATIONS.len() {
+        migrations::migrate_with(&mut conn, &MIGRATIONS[..n], None).unwrap();
+        assert_eq!(migrations::current_version(&conn).unwrap(), MIGRATIONS[n - 1].version);
+    }
+}
+
+#[test]
+fn a_database_newer_than_the_app_is_refused() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.pragma_update(None, "user_version", migrations::latest_version() + 1).unwrap();
+
+    let err = migrations::migrate(&mut conn, None).unwrap_err();
+    assert!(err.contains("newer than this version"), "{err}");
+    assert_eq!(migrations::current_version(&conn).unwrap(), migrations::latest_version() + 1);
+}
+
+#[test]
+fn a_failing_step_is_rolled_back() {
+    let mut steps = MIGRATIONS[..2].to_vec();
+    steps.push(Migration {
+        version: 3,
+        name: "broken",
+        sql: "CREATE TABLE half_done (id TEXT); INSERT INTO no_such_table VALUES (1);",
+    });
+    let mut conn = Connection::open_in_memory().unwrap();
+
+    let err = migrations::migrate_with(&mut conn, &steps, None).unwrap_err();
+    assert!(err.contains("Migration 3 (broken) failed"), "{err}");
+    // The steps before it stay applied; nothing of the broken one does.
+    assert_eq!(migrations::current_version(&conn).unwrap(), 2);
+    assert!(!tables(&conn).contains(&"half_done".to_string()));
+    assert!(foreign_keys(&conn));
+}
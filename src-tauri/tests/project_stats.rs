@@ -0,0 +1,87 @@
+mod common;
+
+use auralis_lib::domain::{ProjectStatus, TaskStatus};
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::project_stats::{self, ProjectHealth};
+use auralis_lib::store::projects::{self, ProjectPatch};
+use auralis_lib::store::tasks::{self, TaskPatch};
+use chrono::{TimeZone, Utc};
+use common::db;
+use rusqlite::{params, Connection};
+
+fn backdate(conn: &Connection, table: &str, column: &str, id: &str, at: &str) {
+    conn.execute(&format!("UPDATE {table} SET {column} = ?1 WHERE id = ?2"), params![at, id])
+        .unwrap();
+}
+
+fn active_project(conn: &Connection, name: &str, created_at: &str) -> (String, String) {
+    let project = projects::add(conn, name, None).unwrap();
+    let task = tasks::add(conn, &format!("{name}: next step"), None, Some(&project)).unwrap();
+    projects::set_status(conn, &project, ProjectStatus::Active).unwrap();
+    backdate(conn, "projects", "created_at", &project, created_at);
+    (project, task)
+}
+
+#[test]
+fn stats_count_tasks_and_judge_health() {
+    let mut conn = db();
+    let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+
+    // Work left, an overdue task and a recent completion.
+    let (launch, next) = active_project(&conn, "Launch", "2024-05-01T00:00:00.000Z");
+    let patch = TaskPatch {
+        due_at: Some(Some("2024-05-25T09:00:00Z".into())),
+        ..Default::default()
+    };
+    tasks::update(&mut conn, &next, patch).unwrap();
+    let shipped = tasks::add(&conn, "Ship beta", None, Some(&launch)).unwrap();
+    tasks::set_status(&mut conn, &shipped, TaskStatus::Done).unwrap();
+    backdate(&conn, "tasks", "completed_at", &shipped, "2024-05-30T08:00:00.000Z");
+    let later = tasks::add(&conn, "Press kit", None, Some(&launch)).unwrap();
+    tasks::set_status(&mut conn, &later, TaskStatus::Deferred).unwrap();
+    tasks::add(&conn, "Slides", None, Some(&launch)).unwrap();
+
+    // Nothing done since it was created two months ago.
+    let (garden, _) = active_project(&conn, "Garden", "2024-04-01T00:00:00.000Z");
+    // Young, with a deadline a month out.
+    let (shed, _) = active_project(&conn, "Shed", "2024-05-30T00:00:00.000Z");
+    let patch = ProjectPatch {
+        deadline_at: Some(Some("2024-07-01T12:00:00Z".into())),
+        ..Default::default()
+    };
+    projects::update(&mut conn, &shed, patch, false).unwrap();
+    let idea = projects::add(&conn, "Someday", None).unwrap();
+
+    let ids = [launch.clone(), garden.clone(), shed.clone(), idea.clone()];
+    let stats = project_stats::many_at(&conn, &ids, now).unwrap();
+    let ids: Vec<&str> = stats.iter().map(|s| s.project_id.as_str()).collect();
+    assert_eq!(ids, [&launch, &garden, &shed, &idea]);
+    let health: Vec<ProjectHealth> = stats.iter().map(|s| s.health).collect();
+    assert_eq!(
+        health,
+        [ProjectHealth::AtRisk, ProjectHealth::Stalled, ProjectHealth::OnTrack, ProjectHealth::Paused]
+    );
+
+    let launch = &stats[0];
+    assert_eq!(
+        (launch.open_tasks, launch.done_tasks, launch.deferred_tasks, launch.percent_complete),
+        (2, 1, 1, 25)
+    );
+    assert_eq!((launch.overdue_tasks, launch.days_since_last_completion), (1, Some(2)));
+    assert_eq!((stats[1].days_since_last_completion, stats[1].percent_complete), (None, 0));
+    assert_eq!(stats[2].days_to_deadline, Some(30));
+
+    // A deadline close by puts a project at risk; a passed one counts down below zero.
+    let patch = ProjectPatch {
+        deadline_at: Some(Some("2024-05-31T00:00:00Z".into())),
+        ..Default::default()
+    };
+    projects::update(&mut conn, &shed, patch, false).unwrap();
+    let shed_stats = project_stats::many_at(&conn, std::slice::from_ref(&shed), now).unwrap();
+    assert_eq!((shed_stats[0].days_to_deadline, shed_stats[0].health), (Some(-2), ProjectHealth::AtRisk));
+
+    assert_eq!(project_stats::get(&conn, &garden).unwrap().open_tasks, 1);
+    assert!(project_stats::many(&conn, &[]).unwrap().is_empty());
+    let err = project_stats::get(&conn, "project_missing").map(|_| ()).unwrap_err();
+    assert!(matches!(err, AuralisError::NotFound(_)));
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use auralis_lib::domain::{Priority, ProjectStatus};
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::project_templates::{self, ProjectTemplateInput, TemplateTaskInput};
+use auralis_lib::store::{areas, notes, projects};
+use chrono::{Local, TimeZone, Utc};
+use common::db;
+use rusqlite::Connection;
+
+fn task(title: &str, due_offset_days: Option<i64>) -> TemplateTaskInput {
+    TemplateTaskInput {
+        title: title.into(),
+        due_offset_days,
+        ..Default::default()
+    }
+}
+
+fn trip_prep() -> ProjectTemplateInput {
+    ProjectTemplateInput {
+        name: "Trip prep".into(),
+        note: Some("Packing list:\n- passport".into()),
+        tasks: vec![
+            TemplateTaskInput {
+                priority: Some(Priority::High),
+                estimate_minutes: Some(30),
+                ..task("Book flights", Some(-21))
+            },
+            task("Pack", Some(-1)),
+            task("Water the plants", None),
+        ],
+    }
+}
+
+fn local_midnight(y: i32, m: u32, d: u32) -> String {
+    let at = Local.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap().with_timezone(&Utc);
+    at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+fn project_count(conn: &Connection) -> i64 {
+    conn.query_row("SELECT COUNT(1) FROM projects", [], |r| r.get(0)).unwrap()
+}
+
+#[test]
+fn templates_are_saved_whole() {
+    let mut conn = db();
+    let template = project_templates::save(&mut conn, None, trip_prep()).unwrap();
+    let titles: Vec<&str> = template.tasks.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, ["Book flights", "Pack", "Water the plants"]);
+    assert_eq!(template.tasks[0].priority, Priority::High);
+    assert_eq!(template.tasks[1].priority, Priority::Normal);
+
+    for input in [
+        ProjectTemplateInput { name: " ".into(), ..trip_prep() },
+        ProjectTemplateInput { tasks: Vec::new(), ..trip_prep() },
+        ProjectTemplateInput { tasks: vec![task("Far off", Some(5000))], ..trip_prep() },
+        ProjectTemplateInput { name: "TRIP PREP".into(), ..trip_prep() },
+    ] {
+        let err = project_templates::save(&mut conn, None, input).map(|_| ()).unwrap_err();
+        assert!(matches!(err, AuralisError::Validation(_)));
+    }
+
+    // Saving over a template replaces its tasks.
+    let input = ProjectTemplateInput {
+        name: "Monthly close".into(),
+        note: None,
+        tasks: vec![task("Reconcile accounts", Some(3))],
+    };
+    let replaced = project_templates::save(&mut conn, Some(&template.id), input).unwrap();
+    assert_eq!((replaced.id.as_str(), replaced.tasks.len(), replaced.note), (template.id.as_str(), 1, None));
+    project_templates::save(&mut conn, None, trip_prep()).unwrap();
+    let names: Vec<String> = project_templates::list(&conn).unwrap().into_iter().map(|t| t.name).collect();
+    assert_eq!(names, ["Monthly close", "Trip prep"]);
+
+    project_templates::delete(&conn, &template.id).unwrap();
+    let err = project_templates::get(&conn, &template.id).map(|_| ()).unwrap_err();
+    assert!(matches!(err, AuralisError::NotFound(_)));
+}
+
+#[test]
+fn a_template_becomes_an_active_project() {
+    let mut conn = db();
+    let travel = areas::add(&conn, "Travel").unwrap();
+    let template = project_templates::save(&mut conn, None, trip_prep()).unwrap();
+
+    let instance =
+        project_templates::instantiate(&mut conn, &template.id, Some(&travel), Some("Lisbon"), "2024-06-22").unwrap();
+    assert_eq!(instance.project.name, "Lisbon");
+    assert_eq!((instance.project.status, instance.project.area_id.as_str()), (ProjectStatus::Active, travel.as_str()));
+
+    let created: Vec<(&str, Option<&str>, Priority, Option<i64>)> = instance
+        .tasks
+        .iter()
+        .map(|t| (t.title.as_str(), t.due_at.as_deref(), t.priority, t.estimate_minutes))
+        .collect();
+    let (flights, pack) = (local_midnight(2024, 6, 1), local_midnight(2024, 6, 21));
+    assert_eq!(
+        created,
+        [
+            ("Book flights", Some(flights.as_str()), Priority::High, Some(30)),
+            ("Pack", Some(pack.as_str()), Priority::Normal, None),
+            ("Water the plants", None, Priority::Normal, None),
+        ]
+    );
+    assert!(instance.tasks.iter().all(|t| t.area_id == travel));
+
+    let note = notes::get(&conn, instance.note_id.as_deref().unwrap()).unwrap();
+    assert_eq!((note.title.as_str(), note.project_id.as_deref()), ("Lisbon", Some(instance.project.id.as_str())));
+
+    // Named after the template by default; a clash leaves nothing behind.
+    let again = project_templates::instantiate(&mut conn, &template.id, None, None, "2024-07-01").unwrap();
+    assert_eq!(again.project.name, "Trip prep");
+    let before = project_count(&conn);
+    let err = project_templates::instantiate(&mut conn, &template.id, None, None, "2024-08-01")
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+    assert_eq!(project_count(&conn), before);
+
+    for (area, anchor) in [(Some("area_missing"), "2024-06-22"), (None, "22/06/2024")] {
+        let result = project_templates::instantiate(&mut conn, &template.id, area, Some("Porto"), anchor);
+        assert!(result.is_err(), "{area:?} {anchor}");
+    }
+    assert_eq!(project_count(&conn), before);
+
+    // The project outlives its template.
+    project_templates::delete(&conn, &template.id).unwrap();
+    assert!(projects::get(&conn, &instance.project.id).is_ok());
+}
@@ -0,0 +1,116 @@
+use auralis_lib::recurrence::Recurrence;
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use chrono_tz::America::New_York;
+
+#[test]
+fn malformed_rules_are_rejected() {
+    for text in [
+        "FREQ=WEEKLY;BYDAY=€",
+        "FREQ=WEEKLY;BYDAY=1€",
+        "FREQ=WEEKLY;BYDAY=M",
+        "FREQ=MONTHLY;BYDAY=0MO",
+        "FREQ=DAILY;COUNT=3;UNTIL=20240101T000000Z",
+        "FREQ=HOURLY",
+        "FREQ=DAILY;INTERVAL=100000000",
+        "BYDAY=MO",
+    ] {
+        assert!(Recurrence::parse(text).is_err(), "{text}");
+    }
+}
+
+fn at(text: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+}
+
+fn expand(text: &str, start: &str) -> Vec<String> {
+    expand_in(text, start, Utc)
+}
+
+fn expand_in<Tz: TimeZone>(text: &str, start: &str, tz: Tz) -> Vec<String> {
+    Recurrence::parse(text)
+        .unwrap()
+        .occurrences(at(start), tz)
+        .map(|t| t.format("%Y-%m-%dT%H:%MZ").to_string())
+        .collect()
+}
+
+#[test]
+fn byday_ordinals_pick_days_within_the_month() {
+    assert_eq!(
+        expand("FREQ=MONTHLY;BYDAY=-1FR;COUNT=3", "2024-01-26T15:00:00Z"),
+        ["2024-01-26T15:00Z", "2024-02-23T15:00Z", "2024-03-29T15:00Z"]
+    );
+    assert_eq!(
+        expand("FREQ=MONTHLY;BYDAY=2TU;COUNT=2", "2024-01-09T08:00:00Z"),
+        ["2024-01-09T08:00Z", "2024-02-13T08:00Z"]
+    );
+}
+
+#[test]
+fn a_series_ends_where_the_calendar_does() {
+    for freq in ["DAILY", "WEEKLY", "MONTHLY", "YEARLY"] {
+        let rule = Recurrence::parse(&format!("FREQ={freq};INTERVAL=10000")).unwrap();
+        let last = rule.occurrences(at("2024-01-01T09:00:00Z"), Utc).last().unwrap();
+        assert!(last.year() > 200_000, "{freq}: {last}");
+    }
+}
+
+#[test]
+fn count_until_and_exdates_bound_a_series() {
+    // UNTIL is inclusive.
+    assert_eq!(
+        expand("RRULE:FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20240110T090000Z", "2024-01-01T09:00:00Z"),
+        ["2024-01-01T09:00Z", "2024-01-03T09:00Z", "2024-01-08T09:00Z", "2024-01-10T09:00Z"]
+    );
+    // An excluded date still uses up one of the COUNT.
+    assert_eq!(
+        expand("RRULE:FREQ=DAILY;COUNT=4\nEXDATE:20240102T090000Z", "2024-01-01T09:00:00Z"),
+        ["2024-01-01T09:00Z", "2024-01-03T09:00Z", "2024-01-04T09:00Z"]
+    );
+    assert_eq!(
+        expand("RRULE:FREQ=DAILY;INTERVAL=2;COUNT=3\nEXDATE;VALUE=DATE:20240105", "2024-01-01T09:00:00Z"),
+        ["2024-01-01T09:00Z", "2024-01-03T09:00Z"]
+    );
+}
+
+#[test]
+fn wall_clock_time_holds_across_dst() {
+    // 09:00 in New York, on either side of the March change.
+    assert_eq!(
+        expand_in("FREQ=DAILY;COUNT=3", "2024-03-09T14:00:00Z", New_York),
+        ["2024-03-09T14:00Z", "2024-03-10T13:00Z", "2024-03-11T13:00Z"]
+    );
+}
+
+#[test]
+fn splitting_divides_count_and_exdates() {
+    let rule = Recurrence::parse("RRULE:FREQ=DAILY;COUNT=5\nEXDATE:20240102T090000Z,20240104T090000Z").unwrap();
+    let start = at("2024-01-01T09:00:00Z");
+    let (head, tail) = rule.split_at(start, at("2024-01-03T09:00:00Z"), Utc);
+    let (head, tail) = (head.unwrap(), tail.unwrap());
+
+    assert_eq!(head.exdates.len(), 1);
+    assert_eq!(head.occurrences(start, Utc).count(), 1);
+    assert_eq!(tail.rule.count, Some(3));
+    let tail_starts: Vec<DateTime<Utc>> = tail.occurrences(at("2024-01-03T09:00:00Z"), Utc).collect();
+    assert_eq!(tail_starts, [at("2024-01-03T09:00:00Z"), at("2024-01-05T09:00:00Z")]);
+
+    // Splitting at the first occurrence leaves no head; past the last, no tail.
+    assert!(rule.split_at(start, start, Utc).0.is_none());
+    assert!(rule.split_at(start, at("2024-01-09T09:00:00Z"), Utc).1.is_none());
+}
+
+#[test]
+fn shifting_moves_until_and_exdates() {
+    let mut rule = Recurrence::parse("RRULE:FREQ=DAILY;UNTIL=20240103T090000Z\nEXDATE:20240102T090000Z").unwrap();
+    rule.shift(Duration::hours(2));
+    assert_eq!(
+        rule.to_string(),
+        "RRULE:FREQ=DAILY;UNTIL=20240103T110000Z\nEXDATE:20240102T110000Z"
+    );
+    assert_eq!(Recurrence::parse(&rule.to_string()).unwrap(), rule);
+    assert_eq!(
+        expand(&rule.to_string(), "2024-01-01T11:00:00Z"),
+        ["2024-01-01T11:00Z", "2024-01-03T11:00Z"]
+    );
+}
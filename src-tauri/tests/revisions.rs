@@ -0,0 +1,145 @@
+mod common;
+
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::{notes, revisions};
+use common::db;
+use rusqlite::{params, Connection};
+
+fn edit(conn: &mut Connection, id: &str, content: &str) {
+    notes::update(conn, id, "Plan", content, None, None).unwrap();
+}
+
+fn revision_count(conn: &Connection, note_id: &str) -> i64 {
+    conn.query_row(
+        "SELECT COUNT(1) FROM note_revisions WHERE note_id = ?1",
+        params![note_id],
+        |r| r.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn every_edit_keeps_the_previous_version() {
+    let mut conn = db();
+    let id = notes::add(&mut conn, "Plan", "v1", None, None).unwrap();
+    assert!(revisions::history(&conn, &id).unwrap().is_empty());
+
+    edit(&mut conn, &id, "v2");
+    edit(&mut conn, &id, "v3");
+    // Saving without changes does not add a revision.
+    edit(&mut conn, &id, "v3");
+
+    let history = revisions::history(&conn, &id).unwrap();
+    let contents: Vec<&str> = history.iter().map(|r| r.content.as_str()).collect();
+    assert_eq!(contents, ["v2", "v1"]);
+    assert!(history.iter().all(|r| r.reason == "update"));
+
+    let err = revisions::history(&conn, "note_missing").unwrap_err();
+    assert!(matches!(err, AuralisError::NotFound(_)));
+}
+
+#[test]
+fn diff_compares_revisions_and_the_current_version() {
+    let mut conn = db();
+    let id = notes::add(&mut conn, "Plan", "a\nb\nc", None, None).unwrap();
+    edit(&mut conn, &id, "a\nB\nc\nd");
+
+    let old = revisions::history(&conn, &id).unwrap()[0].id;
+    let diff = revisions::diff(&conn, &id, Some(old), None).unwrap();
+    assert_eq!(diff.from.revision_id, Some(old));
+    assert_eq!(diff.to.revision_id, None);
+
+    let lines: Vec<String> = diff
+        .lines
+        .iter()
+        .map(|l| format!("{:?} {}", l.op, l.text))
+        .collect();
+    assert_eq!(lines, ["Equal a", "Delete b", "Insert B", "Equal c", "Insert d"]);
+
+    let err = revisions::diff(&conn, "note_other", Some(old), None).unwrap_err();
+    assert!(matches!(err, AuralisError::NotFound(_)));
+}
+
+#[test]
+fn restore_brings_back_an_old_version_and_can_be_undone() {
+    let mut conn = db();
+    let id = notes::add(&mut conn, "Plan", "good", Some("area_admin_life"), None).unwrap();
+    notes::update(&mut conn, &id, "Oops", "bad", None, None).unwrap();
+
+    let good = revisions::history(&conn, &id).unwrap()[0].id;
+    let note = revisions::restore(&mut conn, &id, good).unwrap();
+    assert_eq!((note.title.as_str(), note.content.as_str()), ("Plan", "good"));
+    assert_eq!(note.area_id.as_deref(), Some("area_admin_life"));
+
+    // The replaced version is now the newest revision.
+    assert_eq!(revisions::history(&conn, &id).unwrap()[0].content, "bad");
+}
+
+#[test]
+fn deleted_notes_can_be_restored() {
+    let mut conn = db();
+    let id = notes::add(&mut conn, "Plan", "keep me", None, None).unwrap();
+    let created_at = notes::get(&conn, &id).unwrap().created_at;
+    notes::delete(&conn, &id).unwrap();
+
+    let deleted = revisions::deleted(&conn).unwrap();
+    assert_eq!(deleted.len(), 1);
+    assert_eq!((deleted[0].note_id.as_str(), deleted[0].reason.as_str()), (id.as_str(), "delete"));
+
+    let note = revisions::restore(&mut conn, &id, deleted[0].id).unwrap();
+    assert_eq!(note.content, "keep me");
+    assert_eq!(note.created_at, created_at);
+    assert!(revisions::deleted(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn a_restore_drops_an_area_that_no_longer_exists() {
+    let mut conn = db();
+    conn.execute("INSERT INTO areas (id, name, active) VALUES ('area_gone', 'Gone', 1)", [])
+        .unwrap();
+    let id = notes::add(&mut conn, "Plan", "text", Some("area_gone"), None).unwrap();
+    notes::delete(&conn, &id).unwrap();
+    conn.execute("DELETE FROM areas WHERE id = 'area_gone'", []).unwrap();
+
+    let rev = revisions::deleted(&conn).unwrap()[0].id;
+    assert_eq!(revisions::restore(&mut conn, &id, rev).unwrap().area_id, None);
+}
+
+#[test]
+fn old_revisions_are_pruned_beyond_the_newest() {
+    let mut conn = db();
+    let id = notes::add(&mut conn, "Plan", "v0", None, None).unwrap();
+    for i in 1..=25 {
+        edit(&mut conn, &id, &format!("v{i}"));
+    }
+    // Fresh revisions are all kept, however many there are.
+    assert_eq!(revision_count(&conn, &id), 25);
+
+    conn.execute("UPDATE note_revisions SET created_at = '2020-01-01T00:00:00.000Z'", [])
+        .unwrap();
+    revisions::prune(&conn).unwrap();
+
+    let history = revisions::history(&conn, &id).unwrap();
+    assert_eq!(history.len(), 20);
+    assert_eq!(history[0].content, "v24");
+}
+
+#[test]
+fn history_of_a_long_deleted_note_is_pruned() {
+    let mut conn = db();
+    let recent = notes::add(&mut conn, "Recent", "a", None, None).unwrap();
+    let old = notes::add(&mut conn, "Old", "b", None, None).unwrap();
+    edit(&mut conn, &old, "b2");
+    notes::delete(&conn, &old).unwrap();
+    notes::delete(&conn, &recent).unwrap();
+
+    conn.execute(
+        "UPDATE note_revisions SET created_at = '2020-01-01T00:00:00.000Z' WHERE note_id = ?1",
+        params![old],
+    )
+    .unwrap();
+    revisions::prune(&conn).unwrap();
+
+    assert_eq!(revision_count(&conn, &old), 0);
+    assert_eq!(revision_count(&conn, &recent), 1);
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use auralis_lib::domain::{Priority, TagTarget, TaskStatus};
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::tasks::TaskPatch;
+use auralis_lib::store::{projects, smart_lists, tags, tasks};
+use chrono::{NaiveDate, NaiveDateTime};
+use common::db;
+use rusqlite::Connection;
+
+// Friday 10 May 2024, 09:00 local.
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 5, 10).unwrap().and_hms_opt(9, 0, 0).unwrap()
+}
+
+fn task(conn: &mut Connection, title: &str, due: Option<&str>, priority: Priority) -> String {
+    let id = tasks::add(conn, title, None, None).unwrap();
+    tasks::update(
+        conn,
+        &id,
+        TaskPatch {
+            due_at: Some(due.map(str::to_string)),
+            priority: Some(priority),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    id
+}
+
+fn titles(conn: &Connection, query: &str) -> Vec<String> {
+    tasks::query_at(conn, query, now())
+        .unwrap()
+        .into_iter()
+        .map(|t| t.title)
+        .collect()
+}
+
+fn sample(conn: &mut Connection) {
+    task(conn, "Overdue", Some("2024-05-08T17:00"), Priority::Normal);
+    task(conn, "Earlier today", Some("2024-05-10T08:00"), Priority::Low);
+    task(conn, "Later today", Some("2024-05-10T18:00"), Priority::High);
+    task(conn, "Next week", Some("2024-05-16T12:00"), Priority::High);
+    task(conn, "Next month", Some("2024-06-10T12:00"), Priority::Normal);
+    task(conn, "Someday", None, Priority::Low);
+}
+
+#[test]
+fn dates_compare_against_days_relative_to_today() {
+    let mut conn = db();
+    sample(&mut conn);
+
+    assert_eq!(titles(&conn, "due:today sort:due"), ["Earlier today", "Later today"]);
+    assert_eq!(titles(&conn, "due<today"), ["Overdue"]);
+    assert_eq!(titles(&conn, "due<now sort:due"), ["Overdue", "Earlier today"]);
+    assert_eq!(
+        titles(&conn, "due<+7d sort:due"),
+        ["Overdue", "Earlier today", "Later today", "Next week"]
+    );
+    assert_eq!(titles(&conn, "due>=+6d due<=2024-06-10 sort:-due"), ["Next month", "Next week"]);
+    assert_eq!(titles(&conn, "due>+1w"), ["Next month"]);
+    assert_eq!(titles(&conn, "due:none"), ["Someday"]);
+    assert_eq!(titles(&conn, "due:tomorrow,2024-05-08"), ["Overdue"]);
+    // A negated comparison also matches tasks with no date at all.
+    assert_eq!(titles(&conn, "-due<=today sort:due"), ["Next week", "Next month", "Someday"]);
+}
+
+#[test]
+fn status_priority_and_sorting() {
+    let mut conn = db();
+    sample(&mut conn);
+    let done = tasks::query_at(&conn, "Overdue", now()).unwrap()[0].id.clone();
+    tasks::set_status(&mut conn, &done, TaskStatus::Done).unwrap();
+
+    assert_eq!(titles(&conn, "status:done"), ["Overdue"]);
+    assert_eq!(titles(&conn, "status:todo,doing priority:high sort:due"), ["Later today", "Next week"]);
+    assert_eq!(
+        titles(&conn, "-status:done sort:-priority,title"),
+        ["Earlier today", "Someday", "Next month", "Later today", "Next week"]
+    );
+    // Tasks without a due date sort last either way.
+    assert_eq!(titles(&conn, "priority:low sort:-due"), ["Earlier today", "Someday"]);
+    assert_eq!(titles(&conn, "completed:any"), ["Overdue"]);
+}
+
+#[test]
+fn areas_projects_tags_and_title_words() {
+    let conn = db();
+    let project = projects::add(&conn, "Kitchen Remodel", None).unwrap();
+    let call = tasks::add(&conn, "Call plumber", None, Some(&project)).unwrap();
+    let email = tasks::add(&conn, "Email 100% refund", None, None).unwrap();
+    tasks::add(&conn, "Buy milk", None, None).unwrap();
+    tags::add(&conn, TagTarget::Task, &call, "@phone").unwrap();
+    tags::add(&conn, TagTarget::Task, &email, "waiting").unwrap();
+
+    assert_eq!(titles(&conn, "area:\"admin / life\" tag:@PHONE"), ["Call plumber"]);
+    assert_eq!(titles(&conn, "project:\"kitchen remodel\""), ["Call plumber"]);
+    assert_eq!(titles(&conn, &format!("project:{project}")), ["Call plumber"]);
+    assert_eq!(titles(&conn, "project:none sort:title"), ["Buy milk", "Email 100% refund"]);
+    assert_eq!(titles(&conn, "tag:none"), ["Buy milk"]);
+    assert_eq!(titles(&conn, "tag:@phone,waiting sort:title"), ["Call plumber", "Email 100% refund"]);
+    assert_eq!(titles(&conn, "-tag:waiting sort:title"), ["Buy milk", "Call plumber"]);
+
+    assert_eq!(titles(&conn, "PLUMBER"), ["Call plumber"]);
+    assert_eq!(titles(&conn, "\"call plumber\""), ["Call plumber"]);
+    assert_eq!(titles(&conn, "100%"), ["Email 100% refund"]);
+    assert!(titles(&conn, "0%r").is_empty());
+    assert_eq!(titles(&conn, "-milk -plumber"), ["Email 100% refund"]);
+    // Values never reach the SQL text.
+    assert!(titles(&conn, "area:\"x' OR 1=1 --\"").is_empty());
+}
+
+#[test]
+fn invalid_queries_are_rejected() {
+    let conn = db();
+    for query in [
+        "status:waiting",
+        "priority:urgent",
+        "status<todo",
+        "due:now",
+        "due<someday",
+        "due<+3x",
+        "due:+é",
+        "due<-€",
+        "due<+",
+        "due<--9223372036854775808d",
+        "due<+2000000000000000000w",
+        "sort:size",
+        "-sort:due",
+        "tag:",
+        "area:\"Admin",
+    ] {
+        let err = tasks::query_at(&conn, query, now()).map(|_| ()).unwrap_err();
+        assert!(matches!(err, AuralisError::Validation(_)), "{query}");
+    }
+    // Empty means everything.
+    assert!(tasks::query_at(&conn, "  ", now()).unwrap().is_empty());
+}
+
+#[test]
+fn default_lists_are_seeded_and_can_be_run() {
+    let conn = db();
+    let names: Vec<String> = smart_lists::list(&conn).unwrap().into_iter().map(|l| l.name).collect();
+    assert_eq!(names, ["Today", "Overdue", "Waiting"]);
+
+    let waiting = tasks::add(&conn, "Hear back from bank", None, None).unwrap();
+    tasks::add(&conn, "Unrelated", None, None).unwrap();
+    tags::add(&conn, TagTarget::Task, &waiting, "waiting").unwrap();
+
+    let run: Vec<String> = smart_lists::run(&conn, "smart_list_waiting").unwrap().into_iter().map(|t| t.id).collect();
+    assert_eq!(run, [waiting]);
+    for list in smart_lists::list(&conn).unwrap() {
+        smart_lists::run(&conn, &list.id).unwrap();
+    }
+}
+
+#[test]
+fn saving_reordering_and_deleting_lists() {
+    let mut conn = db();
+    let phone = smart_lists::save(&conn, None, "Calls", "tag:@phone -status:done").unwrap();
+    assert_eq!(phone.position, 3);
+
+    let err = smart_lists::save(&conn, None, "today", "due:today").unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+    let err = smart_lists::save(&conn, None, "Broken", "status:nope").unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+    let err = smart_lists::save(&conn, Some("smart_list_missing"), "Gone", "").unwrap_err();
+    assert!(matches!(err, AuralisError::NotFound(_)));
+
+    let renamed = smart_lists::save(&conn, Some(&phone.id), "Phone calls", "tag:@phone").unwrap();
+    assert_eq!((renamed.name.as_str(), renamed.query.as_str()), ("Phone calls", "tag:@phone"));
+    // Saving under its own name is fine.
+    smart_lists::save(&conn, Some(&phone.id), "Phone calls", "tag:@phone sort:due").unwrap();
+
+    let order: Vec<String> = smart_lists::reorder(&mut conn, &[phone.id.clone(), "smart_list_waiting".into()])
+        .unwrap()
+        .into_iter()
+        .map(|l| l.name)
+        .collect();
+    assert_eq!(order, ["Phone calls", "Waiting", "Today", "Overdue"]);
+
+    smart_lists::delete(&conn, &phone.id).unwrap();
+    assert!(matches!(smart_lists::run(&conn, &phone.id).map(|_| ()).unwrap_err(), AuralisError::NotFound(_)));
+}
@@ -0,0 +1,456 @@
+mod common;
+
+use auralis_lib::domain::{InboxSource, InboxState, Priority, ProjectStatus, TaskStatus};
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::calendar::{self, CalendarEventInput};
+use auralis_lib::store::projects::ProjectPatch;
+use auralis_lib::store::{areas, inbox, notes, projects, search, tasks};
+use common::db;
+use rusqlite::params;
+
+#[test]
+fn migrations_seed_the_default_area() {
+    let conn = db();
+    let all = areas::list(&conn, true).unwrap();
+    assert!(all.iter().any(|a| a.id == "area_admin_life"));
+}
+
+#[test]
+fn areas_add_list_and_deactivate() {
+    let conn = db();
+    let id = areas::add(&conn, "  Health ").unwrap();
+
+    let area = areas::list(&conn, false).unwrap().into_iter().find(|a| a.id == id).unwrap();
+    assert_eq!(area.name, "Health");
+    assert_eq!(area.active, 1);
+
+    areas::set_active(&conn, &id, false).unwrap();
+    assert!(!areas::list(&conn, true).unwrap().iter().any(|a| a.id == id));
+    assert!(areas::list(&conn, false).unwrap().iter().any(|a| a.id == id));
+
+    assert!(matches!(areas::add(&conn, "   "), Err(AuralisError::Validation(_))));
+    assert!(matches!(
+        areas::set_active(&conn, "area_missing", true),
+        Err(AuralisError::NotFound(_))
+    ));
+}
+
+#[test]
+fn projects_start_paused_and_filter_by_status() {
+    let conn = db();
+    let id = projects::add(&conn, "Garden", None).unwrap();
+
+    let project = projects::get(&conn, &id).unwrap();
+    assert_eq!(project.name, "Garden");
+    assert_eq!(project.status, ProjectStatus::Paused);
+    assert_eq!(project.area_id, "area_admin_life");
+
+    tasks::add(&conn, "Buy seeds", None, Some(&id)).unwrap();
+    projects::set_status(&conn, &id, ProjectStatus::Active).unwrap();
+
+    let active = projects::list(&conn, Some(ProjectStatus::Active), None).unwrap();
+    assert_eq!(active.len(), 1);
+    assert!(projects::list(&conn, Some(ProjectStatus::Paused), None).unwrap().is_empty());
+    assert_eq!(projects::list(&conn, None, None).unwrap().len(), 1);
+
+    assert!(matches!(projects::get(&conn, "project_missing"), Err(AuralisError::NotFound(_))));
+}
+
+#[test]
+fn projects_can_be_edited_and_moved_between_areas() {
+    let mut conn = db();
+    let work = areas::add(&conn, "Work").unwrap();
+    let garden = projects::add(&conn, "Garden", None).unwrap();
+    let seeds = tasks::add(&conn, "Buy seeds", None, Some(&garden)).unwrap();
+    projects::add(&conn, "Garden", Some(&work)).unwrap();
+    let shed = projects::add(&conn, "Shed", None).unwrap();
+
+    let patch = ProjectPatch {
+        goal: Some(Some("  Grow tomatoes ".into())),
+        deadline_at: Some(Some("2024-09-01".into())),
+        priority: Some(Some(Priority::High)),
+        ..Default::default()
+    };
+    let project = projects::update(&mut conn, &garden, patch, false).unwrap();
+    assert_eq!(project.goal.as_deref(), Some("Grow tomatoes"));
+    assert!(project.deadline_at.as_deref().unwrap().starts_with("2024-09-01"));
+    assert_eq!(project.priority, Some(Priority::High));
+    let listed = projects::list(&conn, None, None).unwrap();
+    assert_eq!(listed.iter().find(|p| p.id == garden).unwrap().goal.as_deref(), Some("Grow tomatoes"));
+
+    // Names are unique within an area, for renames and moves alike.
+    for patch in [
+        ProjectPatch { name: Some("Garden".into()), ..Default::default() },
+        ProjectPatch { area_id: Some(work.clone()), ..Default::default() },
+    ] {
+        let target = if patch.name.is_some() { &shed } else { &garden };
+        match projects::update(&mut conn, target, patch, false).unwrap_err() {
+            AuralisError::Validation(message) => assert!(message.contains("already exists"), "{message}"),
+            other => panic!("{other:?}"),
+        }
+    }
+    let patch = ProjectPatch { area_id: Some("area_missing".into()), ..Default::default() };
+    assert!(matches!(projects::update(&mut conn, &garden, patch, false), Err(AuralisError::NotFound(_))));
+
+    // A move leaves the tasks behind unless asked to take them along.
+    let patch = ProjectPatch { area_id: Some(work.clone()), ..Default::default() };
+    let moved = projects::update(&mut conn, &shed, patch, false).unwrap();
+    assert_eq!(moved.area_id, work);
+    let patch = ProjectPatch {
+        name: Some("Allotment".into()),
+        area_id: Some(work.clone()),
+        goal: Some(None),
+        ..Default::default()
+    };
+    let moved = projects::update(&mut conn, &garden, patch, true).unwrap();
+    assert_eq!((moved.name.as_str(), moved.goal), ("Allotment", None));
+    assert_eq!(tasks::get(&conn, &seeds).unwrap().area_id, work);
+}
+
+#[test]
+fn tasks_add_list_and_complete() {
+    let mut conn = db();
+    let id = tasks::add(&conn, "Water plants", None, None).unwrap();
+
+    let task = tasks::get(&conn, &id).unwrap();
+    assert_eq!(task.title, "Water plants");
+    assert_eq!(task.status, TaskStatus::Todo);
+    assert_eq!(task.priority, Priority::Normal);
+    assert_eq!(task.area_id, "area_admin_life");
+
+    assert_eq!(tasks::set_status(&mut conn, &id, TaskStatus::Done).unwrap().next_id, None);
+    let task = tasks::get(&conn, &id).unwrap();
+    assert_eq!(task.status, TaskStatus::Done);
+    assert!(task.completed_at.is_some());
+
+    tasks::set_status(&mut conn, &id, TaskStatus::Todo).unwrap();
+    assert!(tasks::get(&conn, &id).unwrap().completed_at.is_none());
+
+    assert_eq!(tasks::list(&conn, Some(TaskStatus::Todo), None).unwrap().len(), 1);
+    assert!(tasks::list(&conn, Some(TaskStatus::Done), None).unwrap().is_empty());
+    assert!(matches!(
+        tasks::set_status(&mut conn, "task_missing", TaskStatus::Done),
+        Err(AuralisError::NotFound(_))
+    ));
+}
+
+#[test]
+fn tasks_move_between_projects() {
+    let conn = db();
+    let project = projects::add(&conn, "Garden", None).unwrap();
+    let id = tasks::add(&conn, "Dig beds", None, None).unwrap();
+
+    tasks::set_project(&conn, &id, Some(&project)).unwrap();
+    assert_eq!(tasks::list_by_project(&conn, &project).unwrap().len(), 1);
+
+    tasks::set_project(&conn, &id, None).unwrap();
+    assert!(tasks::list_by_project(&conn, &project).unwrap().is_empty());
+}
+
+#[test]
+fn task_update_applies_only_given_fields() {
+    let mut conn = db();
+    let id = tasks::add(&conn, "Draft report", None, None).unwrap();
+
+    let patch = tasks::TaskPatch {
+        priority: Some(Priority::High),
+        due_at: Some(Some("2030-01-15T09:00:00Z".to_string())),
+        estimate_minutes: Some(Some(45)),
+        notes: Some(Some("Use last year's template".to_string())),
+        ..Default::default()
+    };
+    let task = tasks::update(&mut conn, &id, patch).unwrap();
+    assert_eq!(task.title, "Draft report");
+    assert_eq!(task.priority, Priority::High);
+    assert_eq!(task.due_at.as_deref(), Some("2030-01-15T09:00:00.000Z"));
+    assert_eq!(task.estimate_minutes, Some(45));
+
+    let clear = tasks::TaskPatch {
+        due_at: Some(None),
+        ..Default::default()
+    };
+    let task = tasks::update(&mut conn, &id, clear).unwrap();
+    assert_eq!(task.due_at, None);
+    assert_eq!(task.estimate_minutes, Some(45));
+
+    let huge = tasks::TaskPatch {
+        estimate_minutes: Some(Some(tasks::MAX_ESTIMATE_MINUTES + 1)),
+        ..Default::default()
+    };
+    let err = tasks::update(&mut conn, &id, huge).map(|_| ()).unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+}
+
+#[test]
+fn completing_a_repeating_task_creates_the_next_instance() {
+    let mut conn = db();
+    let id = tasks::add(&conn, "Take out bins", None, None).unwrap();
+    tasks::update(
+        &mut conn,
+        &id,
+        tasks::TaskPatch {
+            due_at: Some(Some("2030-01-15T09:00:00Z".to_string())),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    tasks::set_recurrence(&conn, &id, Some("RRULE:FREQ=WEEKLY")).unwrap();
+
+    let next = tasks::set_status(&mut conn, &id, TaskStatus::Done).unwrap().next_id.expect("next instance");
+    let next = tasks::get(&conn, &next).unwrap();
+    assert_eq!(next.title, "Take out bins");
+    assert_eq!(next.status, TaskStatus::Todo);
+    assert_eq!(next.due_at.as_deref(), Some("2030-01-22T09:00:00.000Z"));
+    assert!(next.recurrence.is_some());
+}
+
+#[test]
+fn quick_add_resolves_areas_and_projects() {
+    let conn = db();
+    let area = areas::add(&conn, "Health").unwrap();
+    let project = projects::add(&conn, "Checkups", Some(&area)).unwrap();
+
+    let result = tasks::quick_add(&conn, "Call dentist !high #health +checkups ~30m").unwrap();
+    assert!(result.parsed.warnings.is_empty());
+
+    let task = tasks::get(&conn, &result.id).unwrap();
+    assert_eq!(task.title, "Call dentist");
+    assert_eq!(task.priority, Priority::High);
+    assert_eq!(task.area_id, area);
+    assert_eq!(task.project_id.as_deref(), Some(project.as_str()));
+    assert_eq!(task.estimate_minutes, Some(30));
+
+    // A tilde with no amount is just part of the title.
+    let parsed = tasks::quick_add_parse(&conn, "Sketch ~ layout").unwrap();
+    assert_eq!((parsed.title.as_str(), parsed.estimate_minutes), ("Sketch ~ layout", None));
+
+    // Neither is an amount too large to be an estimate.
+    let parsed = tasks::quick_add_parse(&conn, "Sketch ~99999999999999h").unwrap();
+    assert_eq!((parsed.title.as_str(), parsed.estimate_minutes), ("Sketch ~99999999999999h", None));
+}
+
+#[test]
+fn notes_crud() {
+    let mut conn = db();
+    let project = projects::add(&conn, "Garden", None).unwrap();
+    let id = notes::add(&mut conn, "Soil", "Loamy", None, Some(&project)).unwrap();
+
+    assert_eq!(notes::list(&conn, None, Some(&project), None).unwrap().len(), 1);
+    assert!(notes::list(&conn, Some("area_admin_life"), None, None).unwrap().is_empty());
+
+    notes::update(&mut conn, &id, "Soil test", "pH 6.5", None, None).unwrap();
+    let note = notes::get(&conn, &id).unwrap();
+    assert_eq!(note.title, "Soil test");
+    assert_eq!(note.content, "pH 6.5");
+    assert_eq!(note.project_id, None);
+
+    notes::delete(&conn, &id).unwrap();
+    assert!(matches!(notes::get(&conn, &id), Err(AuralisError::NotFound(_))));
+    assert!(matches!(notes::delete(&conn, &id), Err(AuralisError::NotFound(_))));
+}
+
+#[test]
+fn inbox_items_convert_to_tasks_once() {
+    let mut conn = db();
+    let id = inbox::add(&conn, "Renew passport !high\nForms are in the drawer", InboxSource::Text).unwrap();
+    assert_eq!(inbox::list(&conn, Some(InboxState::Unprocessed)).unwrap().len(), 1);
+
+    let task_id = inbox::convert_to_task(&mut conn, &id).unwrap();
+    let task = tasks::get(&conn, &task_id).unwrap();
+    assert_eq!(task.title, "Renew passport");
+    assert_eq!(task.priority, Priority::High);
+    assert_eq!(task.notes.as_deref(), Some("Forms are in the drawer"));
+
+    assert!(inbox::list(&conn, Some(InboxState::Unprocessed)).unwrap().is_empty());
+    assert!(matches!(
+        inbox::convert_to_task(&mut conn, &id),
+        Err(AuralisError::Validation(_))
+    ));
+
+    inbox::set_state(&conn, &id, InboxState::Archived).unwrap();
+    assert_eq!(inbox::list(&conn, Some(InboxState::Archived)).unwrap().len(), 1);
+}
+
+fn event(title: &str, start_at: &str, end_at: &str) -> CalendarEventInput {
+    CalendarEventInput {
+        title: title.to_string(),
+        start_at: start_at.to_string(),
+        end_at: end_at.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn calendar_events_crud_and_range() {
+    let conn = db();
+    let id = calendar::add(
+        &conn,
+        event("Standup", "2030-03-04T09:00:00Z", "2030-03-04T09:15:00Z"),
+        None,
+    )
+    .unwrap();
+
+    let found = calendar::list_range(&conn, "2030-03-04T00:00:00Z", "2030-03-05T00:00:00Z").unwrap();
+    assert_eq!(found.len(), 1);
+    assert!(!found[0].recurring);
+    assert!(calendar::list_range(&conn, "2030-03-05T00:00:00Z", "2030-03-06T00:00:00Z")
+        .unwrap()
+        .is_empty());
+
+    calendar::update(
+        &conn,
+        &id,
+        event("Standup (moved)", "2030-03-04T10:00:00Z", "2030-03-04T10:15:00Z"),
+        None,
+    )
+    .unwrap();
+    let stored = calendar::get(&conn, &id).unwrap();
+    assert_eq!(stored.title, "Standup (moved)");
+    assert_eq!(stored.start_at, "2030-03-04T10:00:00.000Z");
+
+    assert!(matches!(
+        calendar::add(&conn, event("Backwards", "2030-03-04T10:00:00Z", "2030-03-04T09:00:00Z"), None),
+        Err(AuralisError::Validation(_))
+    ));
+
+    calendar::delete(&conn, &id).unwrap();
+    assert!(matches!(calendar::get(&conn, &id), Err(AuralisError::NotFound(_))));
+}
+
+#[test]
+fn calendar_series_expand_within_range() {
+    let conn = db();
+    calendar::add(
+        &conn,
+        event("Gym", "2030-03-04T18:00:00Z", "2030-03-04T19:00:00Z"),
+        Some("RRULE:FREQ=DAILY;COUNT=5".to_string()),
+    )
+    .unwrap();
+
+    let found = calendar::list_range(&conn, "2030-03-05T00:00:00Z", "2030-03-07T00:00:00Z").unwrap();
+    assert_eq!(found.len(), 2);
+    assert!(found.iter().all(|o| o.recurring));
+}
+
+fn occurrence_starts(conn: &rusqlite::Connection) -> Vec<String> {
+    calendar::list_range(conn, "2030-06-01T00:00:00Z", "2030-06-30T00:00:00Z")
+        .unwrap()
+        .into_iter()
+        .map(|o| o.occurrence_start)
+        .collect()
+}
+
+const DAILY_STANDUP: &str = "RRULE:FREQ=DAILY;UNTIL=20300607T090000Z\nEXDATE:20300605T090000Z";
+
+#[test]
+fn moving_a_whole_series_moves_its_end_and_exceptions() {
+    let mut conn = db();
+    let id = calendar::add(
+        &conn,
+        event("Standup", "2030-06-03T09:00:00Z", "2030-06-03T09:15:00Z"),
+        Some(DAILY_STANDUP.to_string()),
+    )
+    .unwrap();
+
+    calendar::update_occurrence(
+        &mut conn,
+        &id,
+        "2030-06-04T09:00:00Z",
+        "all",
+        event("Standup", "2030-06-04T11:00:00Z", "2030-06-04T11:15:00Z"),
+    )
+    .unwrap();
+    assert_eq!(
+        occurrence_starts(&conn),
+        [
+            "2030-06-03T11:00:00.000Z",
+            "2030-06-04T11:00:00.000Z",
+            "2030-06-06T11:00:00.000Z",
+            "2030-06-07T11:00:00.000Z",
+        ]
+    );
+}
+
+#[test]
+fn moving_following_occurrences_carries_their_end_and_exceptions() {
+    let mut conn = db();
+    let id = calendar::add(
+        &conn,
+        event("Standup", "2030-06-03T09:00:00Z", "2030-06-03T09:15:00Z"),
+        Some(DAILY_STANDUP.to_string()),
+    )
+    .unwrap();
+
+    let tail = calendar::update_occurrence(
+        &mut conn,
+        &id,
+        "2030-06-04T09:00:00Z",
+        "following",
+        event("Standup", "2030-06-04T10:00:00Z", "2030-06-04T10:15:00Z"),
+    )
+    .unwrap();
+    assert_eq!(
+        occurrence_starts(&conn),
+        [
+            "2030-06-03T09:00:00.000Z",
+            "2030-06-04T10:00:00.000Z",
+            "2030-06-06T10:00:00.000Z",
+            "2030-06-07T10:00:00.000Z",
+        ]
+    );
+    // Each half keeps only the exceptions that fall within it.
+    let head = calendar::get(&conn, &id).unwrap().recurrence.unwrap();
+    assert!(!head.contains("EXDATE"), "{head}");
+    let tail = calendar::get(&conn, &tail).unwrap().recurrence.unwrap();
+    assert!(tail.contains("EXDATE:20300605T100000Z"), "{tail}");
+}
+
+#[test]
+fn time_blocks_use_the_task_estimate() {
+    let mut conn = db();
+    let task = tasks::add(&conn, "Write essay", None, None).unwrap();
+    tasks::update(
+        &mut conn,
+        &task,
+        tasks::TaskPatch {
+            estimate_minutes: Some(Some(90)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let id = calendar::block_from_task(&conn, &task, "2030-03-04T09:00:00Z", None).unwrap();
+    let block = calendar::get(&conn, &id).unwrap();
+    assert_eq!(block.event_type, "block");
+    assert_eq!(block.task_id.as_deref(), Some(task.as_str()));
+    assert_eq!(block.end_at, "2030-03-04T10:30:00.000Z");
+
+    // An estimate saved before it was bounded fails cleanly.
+    conn.execute("UPDATE tasks SET estimate_minutes = ?1 WHERE id = ?2", params![i64::MAX, task])
+        .unwrap();
+    let err = calendar::block_from_task(&conn, &task, "2030-03-04T09:00:00Z", None).unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+}
+
+#[test]
+fn search_finds_every_kind() {
+    let mut conn = db();
+    notes::add(&mut conn, "Harbour walk", "Route past the lighthouse", None, None).unwrap();
+    tasks::add(&conn, "Book lighthouse tour", None, None).unwrap();
+    projects::add(&conn, "Lighthouse photos", None).unwrap();
+    inbox::add(&conn, "lighthouse opening hours?", InboxSource::Text).unwrap();
+
+    let hits = search::search(&conn, "lighthouse", None, None).unwrap();
+    let mut kinds: Vec<&str> = hits.iter().map(|h| h.kind.as_str()).collect();
+    kinds.sort();
+    assert_eq!(kinds, ["inbox", "note", "project", "task"]);
+
+    let notes_only = search::search(&conn, "lightho", Some(vec!["note".into()]), None).unwrap();
+    assert_eq!(notes_only.len(), 1);
+    assert!(notes_only[0].snippet.contains("<mark>"));
+
+    assert!(matches!(
+        search::search(&conn, "x", Some(vec!["event".into()]), None),
+        Err(AuralisError::Validation(_))
+    ));
+}
@@ -0,0 +1,212 @@
+mod common;
+
+use auralis_lib::domain::TaskStatus;
+use auralis_lib::error::{AuralisError, Rule};
+use auralis_lib::store::subtasks::{self, Progress, TaskNode};
+use auralis_lib::store::tasks::{self, TaskPatch};
+use auralis_lib::store::{checklists, dependencies, projects};
+use common::db;
+use rusqlite::Connection;
+
+fn status(conn: &Connection, id: &str) -> TaskStatus {
+    tasks::get(conn, id).unwrap().status
+}
+
+fn outline(nodes: &[TaskNode]) -> Vec<String> {
+    let mut lines = Vec::new();
+    fn walk(nodes: &[TaskNode], indent: usize, lines: &mut Vec<String>) {
+        for node in nodes {
+            let Progress { done, total } = node.progress;
+            lines.push(format!("{}{} {done}/{total}", "  ".repeat(indent), node.task.title));
+            walk(&node.children, indent + 1, lines);
+        }
+    }
+    walk(nodes, 0, &mut lines);
+    lines
+}
+
+#[test]
+fn subtasks_follow_their_parent_and_nest_three_deep() {
+    let mut conn = db();
+    let area = auralis_lib::store::areas::add(&conn, "Money").unwrap();
+    let project = projects::add(&conn, "Taxes", Some(&area)).unwrap();
+    let parent = tasks::add(&conn, "Prepare tax return", Some(&area), Some(&project)).unwrap();
+
+    let child = subtasks::add(&mut conn, &parent, "Collect receipts").unwrap();
+    let item = tasks::get(&conn, &child).unwrap();
+    assert_eq!(item.parent_task_id.as_deref(), Some(parent.as_str()));
+    assert_eq!((item.area_id.as_str(), item.project_id.as_deref()), (area.as_str(), Some(project.as_str())));
+
+    let grandchild = subtasks::add(&mut conn, &child, "Medical").unwrap();
+    let err = subtasks::add(&mut conn, &grandchild, "Pharmacy").unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+    assert!(matches!(subtasks::add(&mut conn, "task_missing", "x").unwrap_err(), AuralisError::NotFound(_)));
+}
+
+#[test]
+fn a_parent_cannot_close_over_open_subtasks() {
+    let mut conn = db();
+    let parent = tasks::add(&conn, "Prepare tax return", None, None).unwrap();
+    let child = subtasks::add(&mut conn, &parent, "Collect receipts").unwrap();
+    let grandchild = subtasks::add(&mut conn, &child, "Medical").unwrap();
+    let deferred = subtasks::add(&mut conn, &parent, "Ask accountant").unwrap();
+    tasks::set_status(&mut conn, &deferred, TaskStatus::Deferred).unwrap();
+
+    let err = tasks::set_status(&mut conn, &parent, TaskStatus::Done).unwrap_err();
+    assert_eq!(err.rule(), Some(Rule::OpenSubtasks));
+
+    subtasks::complete(&mut conn, &parent).unwrap();
+    for id in [&parent, &child, &grandchild] {
+        assert_eq!(status(&conn, id), TaskStatus::Done);
+    }
+    assert_eq!(status(&conn, &deferred), TaskStatus::Deferred);
+
+    // Reopening a subtask reopens everything above it.
+    tasks::set_status(&mut conn, &grandchild, TaskStatus::Doing).unwrap();
+    assert_eq!(status(&conn, &child), TaskStatus::Todo);
+    assert_eq!(status(&conn, &parent), TaskStatus::Todo);
+    assert!(tasks::get(&conn, &parent).unwrap().completed_at.is_none());
+}
+
+#[test]
+fn moving_tasks_between_parents() {
+    let mut conn = db();
+    let a = tasks::add(&conn, "A", None, None).unwrap();
+    let b = subtasks::add(&mut conn, &a, "B").unwrap();
+    let c = subtasks::add(&mut conn, &b, "C").unwrap();
+    let other = tasks::add(&conn, "Other", None, None).unwrap();
+
+    let invalid = |conn: &mut Connection, id: &str, parent: &str| {
+        let err = subtasks::set_parent(conn, id, Some(parent)).map(|_| ()).unwrap_err();
+        assert!(matches!(err, AuralisError::Validation(_)), "{id} under {parent}");
+    };
+    invalid(&mut conn, &a, &a);
+    invalid(&mut conn, &a, &c);
+    // B and C together are two levels, so they only fit under a top-level task.
+    invalid(&mut conn, &other, &c);
+    let nested = subtasks::add(&mut conn, &other, "Nested").unwrap();
+    invalid(&mut conn, &b, &nested);
+
+    // Nor under a task it, or a subtask of it, waits on.
+    dependencies::add(&conn, &c, &other).unwrap();
+    invalid(&mut conn, &b, &other);
+    assert_eq!(tasks::get(&conn, &b).unwrap().parent_task_id.as_deref(), Some(a.as_str()));
+    dependencies::remove(&conn, &c, &other).unwrap();
+
+    let project = projects::add(&conn, "Home", None).unwrap();
+    tasks::set_project(&conn, &other, Some(&project)).unwrap();
+    subtasks::set_parent(&mut conn, &b, Some(&other)).unwrap();
+    assert_eq!(tasks::get(&conn, &c).unwrap().project_id.as_deref(), Some(project.as_str()));
+
+    let top = subtasks::set_parent(&mut conn, &b, None).unwrap();
+    assert_eq!(top.parent_task_id, None);
+}
+
+#[test]
+fn subtasks_stay_in_their_parent_project() {
+    let mut conn = db();
+    let home = projects::add(&conn, "Home", None).unwrap();
+    let work = projects::add(&conn, "Work", None).unwrap();
+    let parent = tasks::add(&conn, "Parent", None, Some(&home)).unwrap();
+    let child = subtasks::add(&mut conn, &parent, "Child").unwrap();
+    let grandchild = subtasks::add(&mut conn, &child, "Grandchild").unwrap();
+
+    let err = tasks::set_project(&conn, &child, Some(&work)).unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+    let patch = TaskPatch {
+        project_id: Some(None),
+        ..Default::default()
+    };
+    assert!(matches!(tasks::update(&mut conn, &child, patch).map(|_| ()).unwrap_err(), AuralisError::Validation(_)));
+
+    tasks::update(
+        &mut conn,
+        &parent,
+        TaskPatch {
+            project_id: Some(Some(work.clone())),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(tasks::get(&conn, &grandchild).unwrap().project_id.as_deref(), Some(work.as_str()));
+}
+
+#[test]
+fn the_tree_rolls_up_progress() {
+    let mut conn = db();
+    let project = projects::add(&conn, "Taxes", None).unwrap();
+    let parent = tasks::add(&conn, "Prepare tax return", None, Some(&project)).unwrap();
+    let receipts = subtasks::add(&mut conn, &parent, "Collect receipts").unwrap();
+    let medical = subtasks::add(&mut conn, &receipts, "Medical").unwrap();
+    subtasks::add(&mut conn, &parent, "File").unwrap();
+    tasks::add(&conn, "Unrelated", None, None).unwrap();
+
+    let step = checklists::add(&conn, &receipts, "Pharmacy").unwrap();
+    checklists::add(&conn, &receipts, "Dentist").unwrap();
+    checklists::update(&conn, &step.id, None, Some(true)).unwrap();
+    tasks::set_status(&mut conn, &medical, TaskStatus::Done).unwrap();
+
+    let tree = subtasks::tree(&conn, Some(&project)).unwrap();
+    assert_eq!(
+        outline(&tree),
+        ["Prepare tax return 2/5", "  Collect receipts 2/3", "    Medical 0/0", "  File 0/0"]
+    );
+    assert_eq!(tree[0].children[0].checklist, Progress { done: 1, total: 2 });
+
+    assert_eq!(outline(&subtasks::tree(&conn, None).unwrap()), ["Unrelated 0/0"]);
+    assert!(matches!(subtasks::tree(&conn, Some("project_missing")).map(|_| ()).unwrap_err(), AuralisError::NotFound(_)));
+}
+
+#[test]
+fn checklist_items_can_be_edited_ticked_and_reordered() {
+    let mut conn = db();
+    let task = tasks::add(&conn, "Pack", None, None).unwrap();
+    let other = tasks::add(&conn, "Other", None, None).unwrap();
+    let passport = checklists::add(&conn, &task, " Passport ").unwrap();
+    let charger = checklists::add(&conn, &task, "Charger").unwrap();
+    let socks = checklists::add(&conn, &task, "Socks").unwrap();
+    let foreign = checklists::add(&conn, &other, "x").unwrap();
+    assert_eq!((passport.text.as_str(), passport.position, socks.position), ("Passport", 0, 2));
+
+    assert!(matches!(checklists::add(&conn, &task, "  ").unwrap_err(), AuralisError::Validation(_)));
+    assert!(matches!(checklists::add(&conn, "task_missing", "x").unwrap_err(), AuralisError::NotFound(_)));
+
+    let ticked = checklists::update(&conn, &charger.id, Some("Phone charger"), Some(true)).unwrap();
+    assert!(ticked.done && ticked.completed_at.is_some());
+    assert_eq!(ticked.text, "Phone charger");
+    let unticked = checklists::update(&conn, &charger.id, None, Some(false)).unwrap();
+    assert!(!unticked.done && unticked.completed_at.is_none());
+
+    let order: Vec<String> = checklists::reorder(&mut conn, &task, std::slice::from_ref(&socks.id))
+        .unwrap()
+        .into_iter()
+        .map(|i| i.text)
+        .collect();
+    assert_eq!(order, ["Socks", "Passport", "Phone charger"]);
+    let err = checklists::reorder(&mut conn, &task, &[foreign.id]).unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+
+    checklists::delete(&conn, &passport.id).unwrap();
+    assert_eq!(checklists::list(&conn, &task).unwrap().len(), 2);
+    assert!(matches!(checklists::delete(&conn, &passport.id).unwrap_err(), AuralisError::NotFound(_)));
+}
+
+#[test]
+fn repeating_tasks_carry_their_checklist_and_parent_forward() {
+    let mut conn = db();
+    let parent = tasks::add(&conn, "Household", None, None).unwrap();
+    let weekly = subtasks::add(&mut conn, &parent, "Weekly clean").unwrap();
+    tasks::set_recurrence(&conn, &weekly, Some("FREQ=WEEKLY")).unwrap();
+    let step = checklists::add(&conn, &weekly, "Vacuum").unwrap();
+    checklists::update(&conn, &step.id, None, Some(true)).unwrap();
+
+    let next = tasks::set_status(&mut conn, &weekly, TaskStatus::Done).unwrap().next_id.unwrap();
+    assert_eq!(tasks::get(&conn, &next).unwrap().parent_task_id.as_deref(), Some(parent.as_str()));
+    let items = checklists::list(&conn, &next).unwrap();
+    assert_eq!(items.len(), 1);
+    assert!(!items[0].done);
+
+    // Completing the parent ends the series instead of starting another round.
+    assert_eq!(subtasks::complete(&mut conn, &parent).unwrap().next_id, None);
+    assert_eq!(status(&conn, &next), TaskStatus::Done);
+}
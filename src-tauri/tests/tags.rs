@@ -0,0 +1,148 @@
+mod common;
+
+use auralis_lib::domain::{InboxSource, TagTarget};
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::{inbox, notes, projects, tags, tasks};
+use common::db;
+use rusqlite::Connection;
+
+fn names(conn: &Connection, kind: TagTarget, id: &str) -> Vec<String> {
+    tags::for_entity(conn, kind, id)
+        .unwrap()
+        .into_iter()
+        .map(|t| t.name)
+        .collect()
+}
+
+#[test]
+fn tags_are_shared_across_entity_kinds() {
+    let mut conn = db();
+    let task = tasks::add(&conn, "Call bank", None, None).unwrap();
+    let note = notes::add(&mut conn, "Bank", "account numbers", None, None).unwrap();
+
+    let phone = tags::add(&conn, TagTarget::Task, &task, "@phone").unwrap();
+    // Same tag whatever the case or a leading '#'.
+    let again = tags::add(&conn, TagTarget::Note, &note, "#@Phone").unwrap();
+    tags::add(&conn, TagTarget::Task, &task, "waiting").unwrap();
+    tags::add(&conn, TagTarget::Task, &task, "@phone").unwrap();
+
+    assert_eq!(again.id, phone.id);
+    assert_eq!(again.usage_count, 2);
+    assert_eq!(names(&conn, TagTarget::Task, &task), ["@phone", "waiting"]);
+
+    let all: Vec<(String, i64)> = tags::list(&conn).unwrap().into_iter().map(|t| (t.name, t.usage_count)).collect();
+    assert_eq!(all, [("@phone".to_string(), 2), ("waiting".to_string(), 1)]);
+}
+
+#[test]
+fn adding_validates_the_name_and_the_entity() {
+    let conn = db();
+    let task = tasks::add(&conn, "Call bank", None, None).unwrap();
+
+    for bad in ["", "  # ", "a,b"] {
+        let err = tags::add(&conn, TagTarget::Task, &task, bad).unwrap_err();
+        assert!(matches!(err, AuralisError::Validation(_)), "{bad:?}");
+    }
+    let err = tags::add(&conn, TagTarget::Note, &task, "@phone").unwrap_err();
+    assert!(matches!(err, AuralisError::NotFound(_)));
+}
+
+#[test]
+fn lists_filter_by_tag_name_or_id() {
+    let mut conn = db();
+    let tagged = tasks::add(&conn, "Buy stamps", None, None).unwrap();
+    tasks::add(&conn, "Write letter", None, None).unwrap();
+    let errands = tags::add(&conn, TagTarget::Task, &tagged, "@errands").unwrap();
+
+    let project = projects::add(&conn, "Move house", None).unwrap();
+    projects::add(&conn, "Other", None).unwrap();
+    tags::add(&conn, TagTarget::Project, &project, "@errands").unwrap();
+
+    let note = notes::add(&mut conn, "Shops", "open till 6", None, None).unwrap();
+    notes::add(&mut conn, "Other", "x", None, None).unwrap();
+    tags::add(&conn, TagTarget::Note, &note, "@errands").unwrap();
+
+    let ids: Vec<String> = tasks::list(&conn, None, Some("@ERRANDS")).unwrap().into_iter().map(|t| t.id).collect();
+    assert_eq!(ids, [tagged]);
+    assert_eq!(tasks::list(&conn, None, Some(&errands.id)).unwrap().len(), 1);
+    assert_eq!(tasks::list(&conn, None, None).unwrap().len(), 2);
+
+    let projects: Vec<String> = projects::list(&conn, None, Some("@errands")).unwrap().into_iter().map(|p| p.id).collect();
+    assert_eq!(projects, [project]);
+
+    let notes: Vec<String> = notes::list(&conn, None, None, Some("@errands")).unwrap().into_iter().map(|n| n.id).collect();
+    assert_eq!(notes, [note]);
+    assert!(notes::list(&conn, Some("area_admin_life"), None, Some("@errands")).unwrap().is_empty());
+    assert!(tasks::list(&conn, None, Some("nope")).unwrap().is_empty());
+}
+
+#[test]
+fn remove_rename_and_delete() {
+    let conn = db();
+    let task = tasks::add(&conn, "Call bank", None, None).unwrap();
+    let tag = tags::add(&conn, TagTarget::Task, &task, "phone").unwrap();
+
+    let renamed = tags::rename(&conn, &tag.id, "@phone").unwrap();
+    assert_eq!(renamed.name, "@phone");
+    assert_eq!(names(&conn, TagTarget::Task, &task), ["@phone"]);
+
+    tags::remove(&conn, TagTarget::Task, &task, &tag.id).unwrap();
+    assert!(names(&conn, TagTarget::Task, &task).is_empty());
+    assert!(matches!(
+        tags::remove(&conn, TagTarget::Task, &task, &tag.id).unwrap_err(),
+        AuralisError::NotFound(_)
+    ));
+    // The tag outlives its last use until deleted.
+    assert_eq!(tags::get(&conn, &tag.id).unwrap().usage_count, 0);
+
+    tags::add(&conn, TagTarget::Task, &task, "@phone").unwrap();
+    tags::delete(&conn, &tag.id).unwrap();
+    assert!(tags::list(&conn).unwrap().is_empty());
+    assert!(names(&conn, TagTarget::Task, &task).is_empty());
+}
+
+#[test]
+fn renaming_onto_an_existing_name_asks_for_a_merge() {
+    let conn = db();
+    let task = tasks::add(&conn, "Call bank", None, None).unwrap();
+    let a = tags::add(&conn, TagTarget::Task, &task, "phone").unwrap();
+    tags::add(&conn, TagTarget::Task, &task, "@phone").unwrap();
+
+    let err = tags::rename(&conn, &a.id, "@PHONE").unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+    // A change of case alone is a plain rename.
+    assert_eq!(tags::rename(&conn, &a.id, "Phone").unwrap().name, "Phone");
+}
+
+#[test]
+fn merge_moves_every_use_onto_the_target() {
+    let mut conn = db();
+    let both = tasks::add(&conn, "Call bank", None, None).unwrap();
+    let only_source = notes::add(&mut conn, "Numbers", "x", None, None).unwrap();
+    let source = tags::add(&conn, TagTarget::Task, &both, "calls").unwrap();
+    let target = tags::add(&conn, TagTarget::Task, &both, "@phone").unwrap();
+    tags::add(&conn, TagTarget::Note, &only_source, "calls").unwrap();
+
+    let merged = tags::merge(&conn, &source.id, &target.id).unwrap();
+    assert_eq!(merged.usage_count, 2);
+    assert_eq!(names(&conn, TagTarget::Task, &both), ["@phone"]);
+    assert_eq!(names(&conn, TagTarget::Note, &only_source), ["@phone"]);
+    assert!(matches!(tags::get(&conn, &source.id).unwrap_err(), AuralisError::NotFound(_)));
+    assert!(tags::merge(&conn, &target.id, &target.id).is_err());
+}
+
+#[test]
+fn deleted_entities_lose_their_tags_and_conversions_keep_them() {
+    let mut conn = db();
+    let item = inbox::add(&conn, "call the bank", InboxSource::Text).unwrap();
+    tags::add(&conn, TagTarget::Inbox, &item, "@phone").unwrap();
+
+    let task = inbox::convert_to_task(&mut conn, &item).unwrap();
+    assert_eq!(names(&conn, TagTarget::Task, &task), ["@phone"]);
+
+    let note = notes::add(&mut conn, "Scratch", "x", None, None).unwrap();
+    tags::add(&conn, TagTarget::Note, &note, "@phone").unwrap();
+    notes::delete(&conn, &note).unwrap();
+    // Inbox item and task still carry it; the note no longer counts.
+    assert_eq!(tags::list(&conn).unwrap()[0].usage_count, 2);
+}
@@ -0,0 +1,259 @@
+mod common;
+
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::time_entries::{self, ReportGroup, TimeEntry, TimeEntryInput, TimeEntryPatch, TimeReport};
+use auralis_lib::store::tasks::{self, TaskPatch};
+use auralis_lib::store::{areas, projects};
+use chrono::{Duration, Utc};
+use common::db;
+use rusqlite::{params, Connection};
+
+fn entry(conn: &Connection, task_id: &str, started_at: &str, ended_at: &str) -> TimeEntry {
+    let input = TimeEntryInput {
+        task_id: task_id.into(),
+        started_at: started_at.into(),
+        ended_at: ended_at.into(),
+        note: None,
+    };
+    time_entries::add(conn, input).unwrap()
+}
+
+fn estimate(conn: &mut Connection, task_id: &str, minutes: i64) {
+    let patch = TaskPatch {
+        estimate_minutes: Some(Some(minutes)),
+        ..Default::default()
+    };
+    tasks::update(conn, task_id, patch).unwrap();
+}
+
+// (label, actual minutes, estimated minutes, tasks) for each row.
+fn rows(report: &TimeReport) -> Vec<(&str, i64, Option<i64>, usize)> {
+    report
+        .rows
+        .iter()
+        .map(|r| (r.label.as_str(), r.actual_seconds / 60, r.estimate_minutes, r.task_count))
+        .collect()
+}
+
+#[test]
+fn only_one_timer_runs_at_a_time() {
+    let mut conn = db();
+    let write = tasks::add(&conn, "Write report", None, None).unwrap();
+    let email = tasks::add(&conn, "Answer email", None, None).unwrap();
+
+    let first = time_entries::start(&mut conn, &write).unwrap();
+    assert!(first.ended_at.is_none());
+    assert_eq!(time_entries::start(&mut conn, &write).unwrap().id, first.id);
+
+    let second = time_entries::start(&mut conn, &email).unwrap();
+    assert!(time_entries::get(&conn, &first.id).unwrap().ended_at.is_some());
+    assert_eq!(time_entries::running(&conn).unwrap().unwrap().id, second.id);
+
+    // The schema refuses a second open entry even without the store.
+    let sneaky = conn.execute(
+        "INSERT INTO time_entries (id, task_id, started_at) VALUES ('time_x', ?1, '2024-01-01T00:00:00.000Z')",
+        params![write],
+    );
+    assert!(sneaky.is_err());
+
+    let err = time_entries::stop(&conn, Some("2000-01-01T00:00:00Z")).unwrap_err();
+    assert!(matches!(err, AuralisError::Validation(_)));
+    let stopped = time_entries::stop(&conn, None).unwrap();
+    assert_eq!((stopped.id, stopped.task_title.as_str()), (second.id, "Answer email"));
+    assert!(time_entries::running(&conn).unwrap().is_none());
+    assert!(matches!(time_entries::stop(&conn, None).unwrap_err(), AuralisError::NotFound(_)));
+}
+
+#[test]
+fn a_timer_left_running_is_recovered_as_interrupted() {
+    let mut conn = db();
+    let task = tasks::add(&conn, "Write report", None, None).unwrap();
+    let timer = time_entries::start(&mut conn, &task).unwrap();
+
+    // A quick restart is not an interruption.
+    let now = Utc::now();
+    let recovered = time_entries::recover_at(&conn, now).unwrap().unwrap();
+    assert!(recovered.interrupted_at.is_none());
+
+    // The app was last seen two hours ago, an hour into the session.
+    conn.execute(
+        "UPDATE time_entries
+         SET started_at = ?1, last_seen_at = ?2
+         WHERE id = ?3",
+        params![
+            (now - Duration::hours(3)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            (now - Duration::hours(2)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            timer.id
+        ],
+    )
+    .unwrap();
+    let recovered = time_entries::recover_at(&conn, now).unwrap().unwrap();
+    let last_seen = recovered.interrupted_at.clone().expect("interrupted");
+
+    // Heartbeats from the new session leave the mark alone.
+    time_entries::heartbeat(&conn).unwrap();
+    assert_eq!(time_entries::running(&conn).unwrap().unwrap().interrupted_at, Some(last_seen.clone()));
+
+    let stopped = time_entries::stop(&conn, Some(&last_seen)).unwrap();
+    assert_eq!(stopped.seconds, 60 * 60);
+    assert!(stopped.interrupted_at.is_none());
+}
+
+#[test]
+fn resuming_counts_the_gap() {
+    let mut conn = db();
+    let task = tasks::add(&conn, "Write report", None, None).unwrap();
+    let timer = time_entries::start(&mut conn, &task).unwrap();
+    conn.execute(
+        "UPDATE time_entries SET started_at = '2024-01-01T09:00:00.000Z', last_seen_at = '2024-01-01T10:00:00.000Z'
+         WHERE id = ?1",
+        params![timer.id],
+    )
+    .unwrap();
+    time_entries::recover(&conn).unwrap();
+
+    let resumed = time_entries::resume(&conn).unwrap();
+    assert!(resumed.interrupted_at.is_none());
+    assert!(resumed.ended_at.is_none());
+    assert!(time_entries::recover(&conn).unwrap().unwrap().interrupted_at.is_none());
+}
+
+#[test]
+fn entries_can_be_added_edited_and_deleted_by_hand() {
+    let mut conn = db();
+    let write = tasks::add(&conn, "Write report", None, None).unwrap();
+    let email = tasks::add(&conn, "Answer email", None, None).unwrap();
+
+    let input = TimeEntryInput {
+        task_id: write.clone(),
+        started_at: "2024-05-10T09:00:00Z".into(),
+        ended_at: "2024-05-10T10:30:00Z".into(),
+        note: Some("  first draft ".into()),
+    };
+    let manual = time_entries::add(&conn, input).unwrap();
+    assert_eq!((manual.seconds, manual.note.as_deref()), (90 * 60, Some("first draft")));
+
+    for (task_id, start, end) in [
+        (write.as_str(), "2024-05-10T10:00:00Z", "2024-05-10T09:00:00Z"),
+        (write.as_str(), "2024-05-10T09:00:00Z", "2999-01-01T00:00:00Z"),
+        (write.as_str(), "yesterday-ish", "2024-05-10T09:00:00Z"),
+    ] {
+        let input = TimeEntryInput {
+            task_id: task_id.into(),
+            started_at: start.into(),
+            ended_at: end.into(),
+            note: None,
+        };
+        let err = time_entries::add(&conn, input).unwrap_err();
+        assert!(matches!(err, AuralisError::Validation(_)), "{start} {end}");
+    }
+    let input = TimeEntryInput {
+        task_id: "task_missing".into(),
+        started_at: "2024-05-10T09:00:00Z".into(),
+        ended_at: "2024-05-10T10:00:00Z".into(),
+        note: None,
+    };
+    assert!(matches!(time_entries::add(&conn, input).unwrap_err(), AuralisError::NotFound(_)));
+
+    let moved = time_entries::update(
+        &conn,
+        &manual.id,
+        TimeEntryPatch {
+            task_id: Some(email.clone()),
+            ended_at: Some("2024-05-10T09:45:00Z".into()),
+            note: Some(None),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!((moved.task_id.as_str(), moved.seconds, moved.note), (email.as_str(), 45 * 60, None));
+    let patch = TimeEntryPatch {
+        started_at: Some("2024-05-10T10:00:00Z".into()),
+        ..Default::default()
+    };
+    assert!(matches!(time_entries::update(&conn, &manual.id, patch).unwrap_err(), AuralisError::Validation(_)));
+
+    // Ending the running timer by hand stops it.
+    let timer = time_entries::start(&mut conn, &write).unwrap();
+    let patch = TimeEntryPatch {
+        ended_at: Some(timer.started_at.clone()),
+        ..Default::default()
+    };
+    time_entries::update(&conn, &timer.id, patch).unwrap();
+    assert!(time_entries::running(&conn).unwrap().is_none());
+
+    let listed: Vec<String> = time_entries::list(&conn, Some(&email), None, None)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(listed, std::slice::from_ref(&manual.id));
+    let in_may = time_entries::list(&conn, None, Some("2024-05-10T09:30:00Z"), Some("2024-05-11T00:00:00Z")).unwrap();
+    assert_eq!(in_may.len(), 1);
+
+    time_entries::delete(&conn, &manual.id).unwrap();
+    assert!(matches!(time_entries::delete(&conn, &manual.id).unwrap_err(), AuralisError::NotFound(_)));
+}
+
+#[test]
+fn reports_compare_actual_and_estimated_time() {
+    let mut conn = db();
+    let work = areas::add(&conn, "Work").unwrap();
+    let launch = projects::add(&conn, "Launch", Some(&work)).unwrap();
+    let slides = tasks::add(&conn, "Slides", Some(&work), Some(&launch)).unwrap();
+    let demo = tasks::add(&conn, "Demo", Some(&work), Some(&launch)).unwrap();
+    let taxes = tasks::add(&conn, "Taxes", None, None).unwrap();
+    estimate(&mut conn, &slides, 60);
+    estimate(&mut conn, &demo, 30);
+
+    entry(&conn, &slides, "2024-05-10T09:00:00Z", "2024-05-10T10:30:00Z");
+    entry(&conn, &demo, "2024-05-10T23:30:00Z", "2024-05-11T00:30:00Z");
+    entry(&conn, &taxes, "2024-05-11T08:00:00Z", "2024-05-11T08:45:00Z");
+    // Partly before the range: only the part inside counts.
+    entry(&conn, &taxes, "2024-05-09T23:00:00Z", "2024-05-10T00:15:00Z");
+    entry(&conn, &taxes, "2024-05-01T08:00:00Z", "2024-05-01T09:00:00Z");
+
+    let report = |group| time_entries::report(&conn, "2024-05-10", Some("2024-05-12"), group, Some("UTC")).unwrap();
+
+    let by_task = report(ReportGroup::Task);
+    assert_eq!(by_task.total_seconds, (90 + 60 + 45 + 15) * 60);
+    assert_eq!(
+        rows(&by_task),
+        [("Slides", 90, Some(60), 1), ("Demo", 60, Some(30), 1), ("Taxes", 60, None, 1)]
+    );
+
+    let by_project = report(ReportGroup::Project);
+    assert_eq!(rows(&by_project), [("Launch", 150, Some(90), 2), ("No project", 60, None, 1)]);
+    assert_eq!(by_project.rows[1].key, None);
+
+    let by_area = report(ReportGroup::Area);
+    assert_eq!(rows(&by_area), [("Work", 150, Some(90), 2), ("Admin / Life", 60, None, 1)]);
+
+    let by_day = report(ReportGroup::Day);
+    assert_eq!(
+        rows(&by_day),
+        [
+            ("2024-05-10", 135, Some(90), 3),
+            ("2024-05-11", 75, Some(30), 2),
+            ("2024-05-12", 0, None, 0),
+        ]
+    );
+
+    // The same entries, with days in New York.
+    let ny = time_entries::report(&conn, "2024-05-10", None, ReportGroup::Day, Some("America/New_York")).unwrap();
+    assert_eq!(rows(&ny), [("2024-05-10", 150, Some(90), 2)]);
+}
+
+#[test]
+fn invalid_report_ranges_are_rejected() {
+    let conn = db();
+    for (start, end, tz) in [
+        ("10/05/2024", None, None),
+        ("2024-05-10", Some("2024-05-09"), None),
+        ("2023-01-01", Some("2024-06-01"), None),
+        ("2024-05-10", None, Some("Mars/Olympus_Mons")),
+    ] {
+        let err = time_entries::report(&conn, start, end, ReportGroup::Task, tz).unwrap_err();
+        assert!(matches!(err, AuralisError::Validation(_)), "{start} {end:?} {tz:?}");
+    }
+}
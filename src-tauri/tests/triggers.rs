@@ -0,0 +1,288 @@
+//! Every trigger in the schema, exercised through the store where it has an
+//! entry point and through plain SQL where it does not.
+
+mod common;
+
+use auralis_lib::domain::{InboxSource, ProjectStatus, TaskStatus};
+use auralis_lib::error::{AuralisError, Rule};
+use auralis_lib::store::{dependencies, inbox, notes, projects, tasks};
+use common::{active_project, db};
+use rusqlite::{params, Connection};
+
+fn assert_next_action_rule<T: std::fmt::Debug>(result: Result<T, AuralisError>) {
+    match result {
+        Err(e @ AuralisError::RuleViolation { .. }) => {
+            assert_eq!(e.rule(), Some(Rule::NextActionRequired), "{e}");
+        }
+        other => panic!("expected a next-action rule violation, got {other:?}"),
+    }
+}
+
+fn delete_task(conn: &Connection, id: &str) -> Result<usize, AuralisError> {
+    Ok(conn.execute("DELETE FROM tasks WHERE id = ?1", params![id])?)
+}
+
+fn index_rows(conn: &Connection, kind: &str, id: &str) -> Vec<(String, String)> {
+    let mut stmt = conn
+        .prepare("SELECT title, body FROM search_index WHERE kind = ?1 AND entity_id = ?2")
+        .unwrap();
+    stmt.query_map(params![kind, id], |r| Ok((r.get(0)?, r.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+// trg_projects_prevent_active_without_next_action
+
+#[test]
+fn activating_a_project_without_open_tasks_is_refused() {
+    let mut conn = db();
+    let project = projects::add(&conn, "Empty", None).unwrap();
+    assert_next_action_rule(projects::set_status(&conn, &project, ProjectStatus::Active));
+
+    // A finished task does not count as a next action either.
+    let task = tasks::add(&conn, "Already done", None, Some(&project)).unwrap();
+    tasks::set_status(&mut conn, &task, TaskStatus::Done).unwrap();
+    assert_next_action_rule(projects::set_status(&conn, &project, ProjectStatus::Active));
+
+    tasks::set_status(&mut conn, &task, TaskStatus::Doing).unwrap();
+    projects::set_status(&conn, &project, ProjectStatus::Active).unwrap();
+}
+
+#[test]
+fn pausing_or_completing_a_project_is_always_allowed() {
+    let conn = db();
+    let project = projects::add(&conn, "Empty", None).unwrap();
+    projects::set_status(&conn, &project, ProjectStatus::Completed).unwrap();
+    projects::set_status(&conn, &project, ProjectStatus::Paused).unwrap();
+}
+
+// trg_projects_prevent_active_on_insert_without_next_action
+
+#[test]
+fn inserting_an_active_project_is_refused() {
+    let conn = db();
+    let result: Result<usize, AuralisError> = conn
+        .execute(
+            "INSERT INTO projects (id, area_id, name, status)
+             VALUES ('project_x', 'area_admin_life', 'Straight to active', 'active')",
+            [],
+        )
+        .map_err(Into::into);
+    assert_next_action_rule(result);
+}
+
+// trg_tasks_prevent_closing_last_open_task_in_active_project
+
+#[test]
+fn closing_the_last_open_task_of_an_active_project_is_refused() {
+    let mut conn = db();
+    let (project, task) = active_project(&conn, "Launch");
+
+    assert_next_action_rule(tasks::set_status(&mut conn, &task, TaskStatus::Done));
+    assert_next_action_rule(tasks::set_status(&mut conn, &task, TaskStatus::Deferred));
+    assert_eq!(tasks::get(&conn, &task).unwrap().status, TaskStatus::Todo);
+
+    // Moving between open states is fine.
+    tasks::set_status(&mut conn, &task, TaskStatus::Doing).unwrap();
+
+    // With a second open task the first one can be closed.
+    tasks::add(&conn, "Follow-up", None, Some(&project)).unwrap();
+    tasks::set_status(&mut conn, &task, TaskStatus::Done).unwrap();
+}
+
+#[test]
+fn closing_tasks_of_a_paused_project_is_allowed() {
+    let mut conn = db();
+    let (project, task) = active_project(&conn, "Launch");
+    projects::set_status(&conn, &project, ProjectStatus::Paused).unwrap();
+    tasks::set_status(&mut conn, &task, TaskStatus::Done).unwrap();
+}
+
+#[test]
+fn completing_a_repeating_last_task_keeps_the_project_valid() {
+    let mut conn = db();
+    let (project, task) = active_project(&conn, "Chores");
+    tasks::set_recurrence(&conn, &task, Some("RRULE:FREQ=DAILY")).unwrap();
+
+    let change = tasks::set_status(&mut conn, &task, TaskStatus::Done).unwrap();
+    assert!(change.next_id.is_some());
+
+    let open: Vec<_> = tasks::list_by_project(&conn, &project)
+        .unwrap()
+        .into_iter()
+        .filter(|t| t.status == TaskStatus::Todo)
+        .collect();
+    assert_eq!(open.len(), 1);
+}
+
+// trg_tasks_prevent_deleting_last_open_task_in_active_project
+
+#[test]
+fn deleting_the_last_open_task_of_an_active_project_is_refused() {
+    let conn = db();
+    let (project, task) = active_project(&conn, "Launch");
+    assert_next_action_rule(delete_task(&conn, &task));
+
+    let other = tasks::add(&conn, "Follow-up", None, Some(&project)).unwrap();
+    assert_eq!(delete_task(&conn, &task).unwrap(), 1);
+    assert_next_action_rule(delete_task(&conn, &other));
+}
+
+// The next-action rules count only unblocked tasks (0011_task_dependencies.sql)
+
+#[test]
+fn a_blocked_task_is_not_a_next_action() {
+    let mut conn = db();
+    let project = projects::add(&conn, "Move", None).unwrap();
+    let pack = tasks::add(&conn, "Pack boxes", None, Some(&project)).unwrap();
+    let elsewhere = tasks::add(&conn, "Book van", None, None).unwrap();
+    dependencies::add(&conn, &pack, &elsewhere).unwrap();
+    assert_next_action_rule(projects::set_status(&conn, &project, ProjectStatus::Active));
+
+    tasks::set_status(&mut conn, &elsewhere, TaskStatus::Done).unwrap();
+    projects::set_status(&conn, &project, ProjectStatus::Active).unwrap();
+
+    // Reopening the van booking would block the project's only next action.
+    assert_next_action_rule(tasks::set_status(&mut conn, &elsewhere, TaskStatus::Todo));
+}
+
+#[test]
+fn blocking_the_last_next_action_is_refused() {
+    let mut conn = db();
+    let (project, task) = active_project(&conn, "Move");
+    let other = tasks::add(&conn, "Book van", None, None).unwrap();
+    assert_next_action_rule(dependencies::add(&conn, &task, &other));
+
+    // Waiting on a task of the same project leaves that one as the next action.
+    let first = tasks::add(&conn, "Measure rooms", None, Some(&project)).unwrap();
+    dependencies::add(&conn, &task, &first).unwrap();
+    assert_next_action_rule(tasks::set_status(&mut conn, &first, TaskStatus::Deferred));
+
+    // Completing it unblocks the other, which takes over.
+    tasks::set_status(&mut conn, &first, TaskStatus::Done).unwrap();
+    assert!(!tasks::get(&conn, &task).unwrap().blocked);
+}
+
+#[test]
+fn deleting_a_blocker_frees_its_dependents() {
+    let conn = db();
+    let (project, first) = active_project(&conn, "Move");
+    let second = tasks::add(&conn, "Load van", None, Some(&project)).unwrap();
+    dependencies::add(&conn, &second, &first).unwrap();
+
+    assert_eq!(delete_task(&conn, &first).unwrap(), 1);
+    assert!(!tasks::get(&conn, &second).unwrap().blocked);
+}
+
+// trg_notes_touch_updated_at
+
+#[test]
+fn editing_a_note_touches_updated_at() {
+    let mut conn = db();
+    let id = notes::add(&mut conn, "Soil", "Loamy", None, None).unwrap();
+    conn.execute(
+        "UPDATE notes SET updated_at = '2000-01-01T00:00:00.000Z' WHERE id = ?1",
+        params![id],
+    )
+    .unwrap();
+    assert_eq!(notes::get(&conn, &id).unwrap().updated_at, "2000-01-01T00:00:00.000Z");
+
+    notes::update(&mut conn, &id, "Soil", "Clay", None, None).unwrap();
+    let note = notes::get(&conn, &id).unwrap();
+    assert!(note.updated_at.as_str() > "2000-01-01T00:00:00.000Z");
+    assert!(note.updated_at >= note.created_at);
+}
+
+// trg_*_search_insert / _update / _delete
+
+#[test]
+fn note_search_index_follows_inserts_updates_and_deletes() {
+    let mut conn = db();
+    let id = notes::add(&mut conn, "Soil", "Loamy", None, None).unwrap();
+    assert_eq!(index_rows(&conn, "note", &id), [("Soil".into(), "Loamy".into())]);
+
+    notes::update(&mut conn, &id, "Soil test", "pH 6.5", None, None).unwrap();
+    assert_eq!(index_rows(&conn, "note", &id), [("Soil test".into(), "pH 6.5".into())]);
+
+    // Each entity's FTS row sits at the rowid handed out for it.
+    let keyed: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM search_index s JOIN search_index_rows r ON r.id = s.rowid
+                           WHERE r.kind = 'note' AND r.entity_id = ?1 AND s.entity_id = ?1)",
+            params![id],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert!(keyed);
+
+    notes::delete(&conn, &id).unwrap();
+    assert!(index_rows(&conn, "note", &id).is_empty());
+    let left: i64 = conn.query_row("SELECT COUNT(1) FROM search_index_rows", [], |r| r.get(0)).unwrap();
+    assert_eq!(left, 0);
+}
+
+#[test]
+fn task_search_index_follows_inserts_updates_and_deletes() {
+    let mut conn = db();
+    let id = tasks::add(&conn, "Book tour", None, None).unwrap();
+    assert_eq!(index_rows(&conn, "task", &id), [("Book tour".into(), String::new())]);
+
+    tasks::update(
+        &mut conn,
+        &id,
+        tasks::TaskPatch {
+            title: Some("Book boat tour".into()),
+            notes: Some(Some("Morning slot".into())),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        index_rows(&conn, "task", &id),
+        [("Book boat tour".into(), "Morning slot".into())]
+    );
+
+    delete_task(&conn, &id).unwrap();
+    assert!(index_rows(&conn, "task", &id).is_empty());
+}
+
+#[test]
+fn project_search_index_follows_inserts_updates_and_deletes() {
+    let conn = db();
+    let id = projects::add(&conn, "Garden", None).unwrap();
+    assert_eq!(index_rows(&conn, "project", &id), [("Garden".into(), String::new())]);
+
+    conn.execute(
+        "UPDATE projects SET name = 'Vegetable garden', goal = 'Tomatoes by July' WHERE id = ?1",
+        params![id],
+    )
+    .unwrap();
+    assert_eq!(
+        index_rows(&conn, "project", &id),
+        [("Vegetable garden".into(), "Tomatoes by July".into())]
+    );
+
+    conn.execute("DELETE FROM projects WHERE id = ?1", params![id]).unwrap();
+    assert!(index_rows(&conn, "project", &id).is_empty());
+}
+
+#[test]
+fn inbox_search_index_follows_inserts_updates_and_deletes() {
+    let conn = db();
+    let id = inbox::add(&conn, "Call the vet", InboxSource::Voice).unwrap();
+    assert_eq!(index_rows(&conn, "inbox", &id), [(String::new(), "Call the vet".into())]);
+
+    conn.execute(
+        "UPDATE inbox_items SET content = 'Call the vet about jabs' WHERE id = ?1",
+        params![id],
+    )
+    .unwrap();
+    assert_eq!(
+        index_rows(&conn, "inbox", &id),
+        [(String::new(), "Call the vet about jabs".into())]
+    );
+
+    conn.execute("DELETE FROM inbox_items WHERE id = ?1", params![id]).unwrap();
+    assert!(index_rows(&conn, "inbox", &id).is_empty());
+}
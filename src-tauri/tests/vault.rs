@@ -0,0 +1,294 @@
+mod common;
+
+use auralis_lib::backup::{self, BackupKind};
+use auralis_lib::store::notes;
+use auralis_lib::error::AuralisError;
+use auralis_lib::vault::{self, VaultSyncSummary};
+use common::{db, TempDir};
+use rusqlite::Connection;
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+fn read(dir: &Path, rel: &str) -> String {
+    fs::read_to_string(dir.join(rel)).unwrap()
+}
+
+fn md_files(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|n| n.ends_with(".md"))
+        .collect();
+    names.sort();
+    names
+}
+
+// Marks a file as edited `secs` from now, so it is newer or older than a note.
+fn touch(dir: &Path, rel: &str, secs: i64) {
+    let at = match secs {
+        s if s >= 0 => SystemTime::now() + Duration::from_secs(s as u64),
+        s => SystemTime::now() - Duration::from_secs(s.unsigned_abs()),
+    };
+    File::options()
+        .write(true)
+        .open(dir.join(rel))
+        .unwrap()
+        .set_modified(at)
+        .unwrap();
+}
+
+// One pass, with safety backups kept in the vault's hidden `.backups` folder.
+fn sync(conn: &mut Connection, dir: &Path) -> VaultSyncSummary {
+    vault::sync(conn, dir, &dir.join(".backups")).unwrap()
+}
+
+// Note timestamps have millisecond precision; keep successive edits apart.
+fn tick() {
+    std::thread::sleep(Duration::from_millis(5));
+}
+
+#[test]
+fn notes_are_written_with_front_matter() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    let id = notes::add(&mut conn, "Trip plan", "Pack the tent.", Some("area_admin_life"), None).unwrap();
+
+    let summary = sync(&mut conn, &dir.0);
+    assert_eq!(summary.files_written, 1);
+
+    let text = read(&dir.0, "Trip plan.md");
+    assert!(text.starts_with("---\n"));
+    assert!(text.contains(&format!("id: {id}\n")));
+    assert!(text.contains("area: Admin / Life\n"));
+    assert!(text.contains("created_at: "));
+    assert!(text.ends_with("---\n\nPack the tent.\n"));
+    assert!(!text.contains("title:"), "the file name already is the title");
+
+    // Nothing changed since: a second pass is a no-op.
+    assert!(sync(&mut conn, &dir.0).is_empty());
+}
+
+#[test]
+fn external_edits_update_the_note() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    let id = notes::add(&mut conn, "Ideas", "One", None, None).unwrap();
+    sync(&mut conn, &dir.0);
+
+    let edited = read(&dir.0, "Ideas.md").replace("One", "One\n\nTwo");
+    fs::write(dir.0.join("Ideas.md"), edited).unwrap();
+
+    let summary = sync(&mut conn, &dir.0);
+    assert_eq!(summary.notes_imported, 1);
+    assert_eq!(notes::get(&conn, &id).unwrap().content, "One\n\nTwo");
+    assert!(sync(&mut conn, &dir.0).is_empty());
+}
+
+#[test]
+fn renaming_a_note_renames_its_file() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    let id = notes::add(&mut conn, "Draft", "Body", None, None).unwrap();
+    sync(&mut conn, &dir.0);
+
+    tick();
+    notes::update(&mut conn, &id, "Final: v2", "Body", None, None).unwrap();
+    sync(&mut conn, &dir.0);
+
+    assert_eq!(md_files(&dir.0), ["Final- v2.md"]);
+    // The name had to change, so the real title is kept in front matter.
+    assert!(read(&dir.0, "Final- v2.md").contains("title: \"Final: v2\"\n"));
+}
+
+#[test]
+fn new_files_are_imported_and_given_an_id() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.0.join("Reading")).unwrap();
+    fs::write(dir.0.join("Reading/Books.md"), "---\narea: admin / life\ntags: [books]\n---\nDune\n").unwrap();
+    fs::write(dir.0.join("Plain.md"), "No front matter here.\n").unwrap();
+    fs::create_dir_all(dir.0.join(".obsidian")).unwrap();
+    fs::write(dir.0.join(".obsidian/ignored.md"), "Not a note").unwrap();
+
+    let summary = sync(&mut conn, &dir.0);
+    assert_eq!(summary.notes_imported, 2);
+
+    let all = notes::list(&conn, None, None, None).unwrap();
+    let books = all.iter().find(|n| n.title == "Books").unwrap();
+    assert_eq!(books.content, "Dune");
+    assert_eq!(books.area_id.as_deref(), Some("area_admin_life"));
+    assert!(all.iter().any(|n| n.title == "Plain" && n.content == "No front matter here."));
+
+    // The id is written back; front matter the app does not know about survives.
+    let text = read(&dir.0, "Reading/Books.md");
+    assert!(text.contains(&format!("id: {}\n", books.id)));
+    assert!(text.contains("tags: [books]\n"));
+    assert!(sync(&mut conn, &dir.0).is_empty());
+}
+
+#[test]
+fn deletions_propagate_both_ways() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    let in_app = notes::add(&mut conn, "Deleted in app", "a", None, None).unwrap();
+    let on_disk = notes::add(&mut conn, "Deleted on disk", "b", None, None).unwrap();
+    sync(&mut conn, &dir.0);
+
+    notes::delete(&conn, &in_app).unwrap();
+    fs::remove_file(dir.0.join("Deleted on disk.md")).unwrap();
+
+    let summary = sync(&mut conn, &dir.0);
+    assert_eq!((summary.files_deleted, summary.notes_deleted), (1, 1));
+    assert!(md_files(&dir.0).is_empty());
+    assert!(notes::get(&conn, &on_disk).is_err());
+
+    // The note deleted along with its file is in a safety backup.
+    let taken = backup::list(&dir.0.join(".backups")).unwrap();
+    assert_eq!(taken.len(), 1);
+    assert_eq!(taken[0].reason.as_deref(), Some("vault-delete"));
+}
+
+#[test]
+fn a_missing_or_emptied_vault_deletes_nothing() {
+    let mut conn = db();
+    let (root, backups) = (TempDir::new(), TempDir::new());
+    let dir = root.0.join("vault");
+    vault::set_dir(&conn, Some(&dir)).unwrap();
+    notes::add(&mut conn, "One", "a", None, None).unwrap();
+    notes::add(&mut conn, "Two", "b", None, None).unwrap();
+    vault::sync_configured(&mut conn, &backups.0).unwrap();
+
+    // An unmounted drive: the folder is not recreated and the notes stay.
+    fs::rename(&dir, root.0.join("elsewhere")).unwrap();
+    let err = vault::sync_configured(&mut conn, &backups.0).map(|_| ()).unwrap_err();
+    assert!(matches!(err, AuralisError::Io(_)));
+    assert!(!dir.exists());
+
+    // An empty folder in its place looks the same.
+    fs::create_dir(&dir).unwrap();
+    let err = vault::sync_configured(&mut conn, &backups.0).map(|_| ()).unwrap_err();
+    assert!(matches!(err, AuralisError::Io(_)));
+    assert_eq!(notes::list(&conn, None, None, None).unwrap().len(), 2);
+    assert!(md_files(&dir).is_empty());
+
+    // Once the files are back, sync picks up where it left off.
+    fs::remove_dir(&dir).unwrap();
+    fs::rename(root.0.join("elsewhere"), &dir).unwrap();
+    assert!(vault::sync_configured(&mut conn, &backups.0).unwrap().unwrap().is_empty());
+}
+
+#[test]
+fn a_file_moved_outside_the_app_keeps_its_note() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    let id = notes::add(&mut conn, "Moving", "Body", None, None).unwrap();
+    sync(&mut conn, &dir.0);
+
+    fs::create_dir_all(dir.0.join("Archive")).unwrap();
+    fs::rename(dir.0.join("Moving.md"), dir.0.join("Archive/Moving.md")).unwrap();
+    let summary = sync(&mut conn, &dir.0);
+
+    assert_eq!(summary.notes_deleted, 0);
+    assert_eq!(notes::list(&conn, None, None, None).unwrap().len(), 1);
+
+    // Later edits in the app follow the file to its new folder.
+    tick();
+    notes::update(&mut conn, &id, "Moving", "Updated", None, None).unwrap();
+    sync(&mut conn, &dir.0);
+    assert!(read(&dir.0, "Archive/Moving.md").ends_with("\nUpdated\n"));
+}
+
+#[test]
+fn a_conflict_keeps_the_newer_side_and_copies_the_other() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    let id = notes::add(&mut conn, "Shared", "original", None, None).unwrap();
+    sync(&mut conn, &dir.0);
+
+    tick();
+    notes::update(&mut conn, &id, "Shared", "edited in app", None, None).unwrap();
+    let text = read(&dir.0, "Shared.md").replace("original", "edited on disk");
+    fs::write(dir.0.join("Shared.md"), text).unwrap();
+    touch(&dir.0, "Shared.md", 60);
+
+    let summary = sync(&mut conn, &dir.0);
+    assert_eq!(summary.conflicts.len(), 1);
+    assert_eq!(notes::get(&conn, &id).unwrap().content, "edited on disk");
+
+    let copy = read(&dir.0, &summary.conflicts[0]);
+    assert!(summary.conflicts[0].starts_with("Shared (conflict "));
+    assert!(copy.contains(&format!("conflict_of: {id}\n")));
+    assert!(copy.ends_with("\nedited in app\n"));
+    assert!(!copy.contains("\nid: "));
+
+    // The copy is left for the user to merge, not imported as another note.
+    assert!(sync(&mut conn, &dir.0).is_empty());
+    assert_eq!(notes::list(&conn, None, None, None).unwrap().len(), 1);
+}
+
+#[test]
+fn the_app_wins_a_conflict_when_its_edit_is_newer() {
+    let mut conn = db();
+    let dir = TempDir::new();
+    let id = notes::add(&mut conn, "Shared", "original", None, None).unwrap();
+    sync(&mut conn, &dir.0);
+
+    let text = read(&dir.0, "Shared.md").replace("original", "edited on disk");
+    fs::write(dir.0.join("Shared.md"), text).unwrap();
+    touch(&dir.0, "Shared.md", -60);
+    tick();
+    notes::update(&mut conn, &id, "Shared", "edited in app", None, None).unwrap();
+
+    let summary = sync(&mut conn, &dir.0);
+    assert_eq!(summary.conflicts.len(), 1);
+    assert!(read(&dir.0, "Shared.md").ends_with("\nedited in app\n"));
+    assert!(read(&dir.0, &summary.conflicts[0]).ends_with("\nedited on disk\n"));
+}
+
+#[test]
+fn set_dir_requires_an_absolute_path() {
+    let conn = db();
+    assert!(vault::set_dir(&conn, Some(Path::new("relative/vault"))).is_err());
+
+    let dir = TempDir::new();
+    vault::set_dir(&conn, Some(&dir.0)).unwrap();
+    assert_eq!(vault::dir(&conn).unwrap().as_deref(), Some(dir.0.as_path()));
+
+    vault::set_dir(&conn, None).unwrap();
+    assert_eq!(vault::dir(&conn).unwrap(), None);
+}
+
+#[test]
+fn a_restored_backup_replaces_the_vault() {
+    let mut conn = db();
+    let (vault_dir, backups) = (TempDir::new(), TempDir::new());
+    vault::set_dir(&conn, Some(&vault_dir.0)).unwrap();
+    let kept = notes::add(&mut conn, "Kept", "As backed up.", None, None).unwrap();
+    vault::sync_configured(&mut conn, &backups.0).unwrap();
+    let snapshot = backup::create(&conn, &backups.0, BackupKind::Manual).unwrap();
+
+    tick();
+    notes::update(&mut conn, &kept, "Kept", "Edited later.", None, None).unwrap();
+    notes::add(&mut conn, "Added later", "x", None, None).unwrap();
+    fs::write(vault_dir.0.join("Dropped in.md"), "From another app.\n").unwrap();
+    vault::sync_configured(&mut conn, &backups.0).unwrap();
+    assert_eq!(notes::list(&conn, None, None, None).unwrap().len(), 3);
+
+    backup::restore(&mut conn, &backups.0, &snapshot.id).unwrap();
+    let summary = vault::replace_configured(&mut conn, &backups.0).unwrap().unwrap();
+    assert_eq!(summary.files_written, 1);
+    assert_eq!(summary.warnings.len(), 1, "{:?}", summary.warnings);
+
+    // The restored notes stand: a sync afterwards neither re-imports nor deletes.
+    assert!(vault::sync_configured(&mut conn, &backups.0).unwrap().unwrap().is_empty());
+    let notes = notes::list(&conn, None, None, None).unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].content, "As backed up.");
+    assert_eq!(md_files(&vault_dir.0), ["Kept.md"]);
+    assert!(read(&vault_dir.0, "Kept.md").ends_with("\nAs backed up.\n"));
+
+    // The files that were replaced are set aside, not lost.
+    let aside = fs::read_dir(vault_dir.0.join(".replaced")).unwrap().next().unwrap().unwrap().path();
+    assert_eq!(md_files(&aside), ["Added later.md", "Dropped in.md"]);
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use auralis_lib::domain::{InboxSource, InboxState, ReviewStep, TaskStatus};
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::calendar::{self, CalendarEventInput};
+use auralis_lib::store::tasks::{self, TaskPatch};
+use auralis_lib::store::weekly_review::{self, ReviewItem, ReviewState};
+use auralis_lib::store::{inbox, projects};
+use chrono::{Duration, Utc};
+use common::{active_project, db};
+use rusqlite::{params, Connection};
+
+fn stamp(offset: Duration) -> String {
+    (Utc::now() + offset).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+fn event(conn: &Connection, title: &str, in_days: i64) -> String {
+    let input = CalendarEventInput {
+        title: title.into(),
+        start_at: stamp(Duration::days(in_days)),
+        end_at: stamp(Duration::days(in_days) + Duration::hours(1)),
+        ..Default::default()
+    };
+    calendar::add(conn, input, None).unwrap()
+}
+
+fn listed(state: &ReviewState) -> Vec<(String, bool)> {
+    state
+        .items
+        .iter()
+        .map(|item| match item {
+            ReviewItem::Inbox { item } => (item.content.clone(), false),
+            ReviewItem::Project { project, reviewed, .. } => (project.name.clone(), *reviewed),
+            ReviewItem::Task { task, reviewed } => (task.title.clone(), *reviewed),
+            ReviewItem::Event { occurrence, reviewed } => (occurrence.event.title.clone(), *reviewed),
+        })
+        .collect()
+}
+
+fn is_validation<T>(result: Result<T, AuralisError>) -> bool {
+    matches!(result.map(|_| ()), Err(AuralisError::Validation(_)))
+}
+
+#[test]
+fn a_review_walks_every_step_in_order() {
+    let mut conn = db();
+    let note = inbox::add(&conn, "Call the plumber", InboxSource::Text).unwrap();
+    let (launch, _) = active_project(&conn, "Launch");
+    projects::add(&conn, "Someday", None).unwrap();
+    let old = tasks::add(&conn, "Sort the garage", None, None).unwrap();
+    conn.execute(
+        "UPDATE tasks SET updated_at = ?1 WHERE id = ?2",
+        params![stamp(-Duration::days(60)), old],
+    )
+    .unwrap();
+    let later = tasks::add(&conn, "Learn the cello", None, None).unwrap();
+    tasks::set_status(&mut conn, &later, TaskStatus::Deferred).unwrap();
+    let dentist = event(&conn, "Dentist", 2);
+    event(&conn, "Conference", 40);
+
+    // Inbox: cleared by processing, not by ticking.
+    let state = weekly_review::start(&conn, None).unwrap();
+    assert_eq!((state.review.step, state.remaining), (ReviewStep::Inbox, 1));
+    assert!(is_validation(weekly_review::advance(&conn)));
+    assert!(is_validation(weekly_review::mark(&conn, &note, true)));
+    inbox::set_state(&conn, &note, InboxState::Processed).unwrap();
+
+    // Active projects only.
+    let state = weekly_review::advance(&conn).unwrap();
+    assert_eq!(state.review.step, ReviewStep::Projects);
+    assert_eq!(listed(&state), [("Launch".to_string(), false)]);
+    assert!(is_validation(weekly_review::advance(&conn)));
+    let err = weekly_review::mark(&conn, &old, true).map(|_| ()).unwrap_err();
+    assert!(matches!(err, AuralisError::NotFound(_)));
+    let state = weekly_review::mark(&conn, &launch, true).unwrap();
+    assert_eq!((listed(&state), state.remaining), (vec![("Launch".to_string(), true)], 0));
+
+    // Stale tasks drop off once edited.
+    let state = weekly_review::advance(&conn).unwrap();
+    assert_eq!(listed(&state), [("Sort the garage".to_string(), false)]);
+    weekly_review::mark(&conn, &old, true).unwrap();
+    assert_eq!(weekly_review::mark(&conn, &old, false).unwrap().remaining, 1);
+    let patch = TaskPatch {
+        title: Some("Sort the garage this weekend".into()),
+        ..Default::default()
+    };
+    let touched = tasks::update(&mut conn, &old, patch).unwrap();
+    assert!(touched.updated_at > stamp(-Duration::minutes(1)));
+    assert_eq!(weekly_review::current(&conn).unwrap().unwrap().remaining, 0);
+
+    let state = weekly_review::advance(&conn).unwrap();
+    assert_eq!(listed(&state), [("Learn the cello".to_string(), false)]);
+    weekly_review::mark(&conn, &later, true).unwrap();
+
+    // The next two weeks of the calendar.
+    let state = weekly_review::advance(&conn).unwrap();
+    assert_eq!(listed(&state), [("Dentist".to_string(), false)]);
+    weekly_review::mark(&conn, &dentist, true).unwrap();
+
+    let state = weekly_review::advance(&conn).unwrap();
+    assert_eq!(state.review.step, ReviewStep::Done);
+    assert!(state.review.completed_at.is_some());
+    assert!(weekly_review::current(&conn).unwrap().is_none());
+
+    let history = weekly_review::history(&conn, None).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!((history[0].id.as_str(), history[0].reviewed_items), (state.review.id.as_str(), 3));
+}
+
+#[test]
+fn a_review_in_progress_is_resumed_or_abandoned() {
+    let conn = db();
+    assert!(is_validation(weekly_review::start(&conn, Some(0))));
+    assert!(matches!(weekly_review::advance(&conn).map(|_| ()), Err(AuralisError::NotFound(_))));
+
+    let first = weekly_review::start(&conn, Some(10)).unwrap();
+    weekly_review::advance(&conn).unwrap();
+    let resumed = weekly_review::start(&conn, Some(90)).unwrap();
+    assert_eq!(resumed.review.id, first.review.id);
+    assert_eq!((resumed.review.step, resumed.review.stale_days), (ReviewStep::Projects, 10));
+
+    // The schema allows only one review in progress.
+    let second = conn.execute("INSERT INTO weekly_reviews (id) VALUES ('review_x')", []);
+    assert!(second.is_err());
+
+    weekly_review::abandon(&conn).unwrap();
+    assert!(weekly_review::current(&conn).unwrap().is_none());
+    assert!(weekly_review::history(&conn, None).unwrap().is_empty());
+    assert!(matches!(weekly_review::abandon(&conn), Err(AuralisError::NotFound(_))));
+}
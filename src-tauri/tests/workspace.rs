@@ -0,0 +1,216 @@
+mod common;
+
+use auralis_lib::domain::{InboxSource, ProjectStatus, TagTarget, TaskStatus};
+use auralis_lib::error::AuralisError;
+use auralis_lib::store::calendar::{self, CalendarEventInput};
+use auralis_lib::store::time_entries::{self, TimeEntryInput};
+use auralis_lib::store::workspace::{self, ImportMode};
+use auralis_lib::store::{areas, checklists, dependencies, inbox, notes, projects, tags, tasks};
+use common::{active_project, db};
+use rusqlite::{params, Connection};
+
+/// A workspace with a bit of everything in it.
+fn populated() -> Connection {
+    let mut conn = db();
+    let area = areas::add(&conn, "Health").unwrap();
+    let (project, task) = active_project(&conn, "Marathon");
+    let stretch = tasks::add(&conn, "Stretch", Some(&area), None).unwrap();
+    tags::add(&conn, TagTarget::Task, &stretch, "@home").unwrap();
+    checklists::add(&conn, &stretch, "Hamstrings").unwrap();
+    dependencies::add(&conn, &stretch, &task).unwrap();
+    let session = TimeEntryInput {
+        task_id: stretch.clone(),
+        started_at: "2024-01-06T07:00:00Z".into(),
+        ended_at: "2024-01-06T07:20:00Z".into(),
+        note: None,
+    };
+    time_entries::add(&conn, session).unwrap();
+    notes::add(&mut conn, "Plan", "Week 1: 3 runs", Some(&area), Some(&project)).unwrap();
+    inbox::add(&conn, "buy gels", InboxSource::Voice).unwrap();
+    calendar::add(
+        &conn,
+        CalendarEventInput {
+            title: "Long run".into(),
+            start_at: "2030-04-06T07:00:00Z".into(),
+            end_at: "2030-04-06T09:00:00Z".into(),
+            task_id: Some(task),
+            ..Default::default()
+        },
+        Some("RRULE:FREQ=WEEKLY".into()),
+    )
+    .unwrap();
+    conn
+}
+
+fn dump(conn: &Connection) -> serde_json::Value {
+    let mut doc = serde_json::to_value(workspace::export(conn).unwrap()).unwrap();
+    doc.as_object_mut().unwrap().remove("exported_at");
+    doc
+}
+
+#[test]
+fn export_includes_every_table_and_column() {
+    let conn = populated();
+    let doc = workspace::export(&conn).unwrap();
+
+    let counts: Vec<(&str, usize)> = doc.tables.iter().map(|(t, rows)| (t.as_str(), rows.len())).collect();
+    assert_eq!(
+        counts,
+        [
+            ("areas", 2),
+            ("calendar_events", 1),
+            ("checklist_items", 1),
+            ("entity_tags", 1),
+            ("inbox_items", 1),
+            ("notes", 1),
+            ("project_template_tasks", 0),
+            ("project_templates", 0),
+            ("projects", 1),
+            ("smart_lists", 3),
+            ("tags", 1),
+            ("task_dependencies", 1),
+            ("tasks", 2),
+            ("time_entries", 1),
+            ("weekly_review_items", 0),
+            ("weekly_reviews", 0),
+        ]
+    );
+
+    let task = &doc.tables["tasks"][0];
+    for column in ["id", "area_id", "project_id", "status", "recurrence", "ical_uid", "created_at"] {
+        assert!(task.contains_key(column), "missing tasks.{column}");
+    }
+    assert_eq!(doc.tables["projects"][0]["status"], "active");
+}
+
+#[test]
+fn replace_round_trips_into_an_empty_workspace() {
+    let source = populated();
+    let doc = workspace::export(&source).unwrap();
+
+    let mut target = db();
+    let stale = tasks::add(&target, "Only on this machine", None, None).unwrap();
+    let summary = workspace::import(&mut target, &doc, ImportMode::Replace).unwrap();
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert_eq!(summary.tables["tasks"].created, 2);
+    assert_eq!(dump(&target), dump(&source));
+    assert!(matches!(tasks::get(&target, &stale), Err(AuralisError::NotFound(_))));
+}
+
+#[test]
+fn replace_keeps_the_fallback_area() {
+    let mut conn = populated();
+    let mut doc = workspace::export(&conn).unwrap();
+    for rows in doc.tables.values_mut() {
+        rows.clear();
+    }
+
+    workspace::import(&mut conn, &doc, ImportMode::Replace).unwrap();
+    let remaining = areas::list(&conn, false).unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, "area_admin_life");
+    tasks::add(&conn, "Still works", None, None).unwrap();
+}
+
+#[test]
+fn merge_updates_by_id_and_keeps_other_rows() {
+    let mut source = populated();
+    let doc_before = workspace::export(&source).unwrap();
+
+    let mut target = db();
+    workspace::import(&mut target, &doc_before, ImportMode::Merge).unwrap();
+    let local = notes::add(&mut target, "Local only", "Not in the export", None, None).unwrap();
+
+    // Change something on the source and merge again.
+    let task = doc_before.tables["tasks"][1]["id"].as_str().unwrap().to_string();
+    tasks::set_status(&mut source, &task, TaskStatus::Done).unwrap();
+    let summary = workspace::import(&mut target, &workspace::export(&source).unwrap(), ImportMode::Merge).unwrap();
+
+    assert_eq!(summary.tables["tasks"].created, 0);
+    assert_eq!(summary.tables["tasks"].updated, 2);
+    assert_eq!(tasks::get(&target, &task).unwrap().status, TaskStatus::Done);
+    assert!(notes::get(&target, &local).is_ok());
+}
+
+#[test]
+fn merge_keeps_exported_note_timestamps() {
+    let source = populated();
+    source
+        .execute("UPDATE notes SET updated_at = '2001-02-03T04:05:06.000Z'", [])
+        .unwrap();
+    let doc = workspace::export(&source).unwrap();
+
+    let mut target = db();
+    workspace::import(&mut target, &doc, ImportMode::Merge).unwrap();
+    // Second pass goes through UPDATE, where the touch trigger fires.
+    workspace::import(&mut target, &doc, ImportMode::Merge).unwrap();
+
+    let id = doc.tables["notes"][0]["id"].as_str().unwrap();
+    assert_eq!(notes::get(&target, id).unwrap().updated_at, "2001-02-03T04:05:06.000Z");
+}
+
+#[test]
+fn active_projects_without_an_open_task_stay_paused() {
+    let source = populated();
+    let mut doc = workspace::export(&source).unwrap();
+    let project = doc.tables["projects"][0]["id"].as_str().unwrap().to_string();
+    for task in doc.tables.get_mut("tasks").unwrap() {
+        if task["project_id"].as_str() == Some(project.as_str()) {
+            task.insert("status".into(), "done".into());
+        }
+    }
+
+    let mut target = db();
+    let summary = workspace::import(&mut target, &doc, ImportMode::Replace).unwrap();
+
+    assert_eq!(projects::get(&target, &project).unwrap().status, ProjectStatus::Paused);
+    assert_eq!(summary.warnings.len(), 1);
+    assert!(summary.warnings[0].contains(&project));
+}
+
+#[test]
+fn unknown_columns_are_ignored_with_a_warning() {
+    let source = populated();
+    let mut doc = workspace::export(&source).unwrap();
+    doc.tables.get_mut("inbox_items").unwrap()[0].insert("mood".into(), "curious".into());
+
+    let mut target = db();
+    let summary = workspace::import(&mut target, &doc, ImportMode::Merge).unwrap();
+    assert_eq!(summary.warnings, ["Ignored unknown column inbox_items.mood"]);
+}
+
+#[test]
+fn failed_imports_roll_back() {
+    let source = populated();
+    let mut doc = workspace::export(&source).unwrap();
+    // Points at an area that exists nowhere.
+    doc.tables.get_mut("tasks").unwrap()[0].insert("area_id".into(), "area_missing".into());
+
+    let mut target = db();
+    let before = tasks::add(&target, "Untouched", None, None).unwrap();
+    assert!(workspace::import(&mut target, &doc, ImportMode::Replace).is_err());
+
+    assert!(tasks::get(&target, &before).is_ok());
+    let count: i64 = target.query_row("SELECT COUNT(1) FROM projects", params![], |r| r.get(0)).unwrap();
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn newer_or_foreign_documents_are_refused() {
+    let mut conn = db();
+    let mut doc = workspace::export(&conn).unwrap();
+
+    doc.schema_version += 1;
+    assert!(matches!(
+        workspace::import(&mut conn, &doc, ImportMode::Merge),
+        Err(AuralisError::Validation(_))
+    ));
+
+    doc.schema_version -= 1;
+    doc.format = "something-else".into();
+    assert!(matches!(
+        workspace::import(&mut conn, &doc, ImportMode::Merge),
+        Err(AuralisError::Validation(_))
+    ));
+}
@@ -0,0 +1,27 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { CalendarOccurrence } from "./calendar";
+import type { TaskItem } from "./tasks";
+
+export type AgendaItem =
+  | { kind: "task"; at: string; reason: "due" | "scheduled"; task: TaskItem }
+  // continued: a multi-day occurrence carried over from an earlier day
+  | { kind: "event"; at: string; continued: boolean; occurrence: CalendarOccurrence };
+
+export type AgendaDay = {
+  date: string; // YYYY-MM-DD in the agenda's time zone
+  start_at: string;
+  end_at: string;
+  items: AgendaItem[];
+};
+
+export type Agenda = {
+  time_zone: string | null;
+  overdue: TaskItem[];
+  days: AgendaDay[];
+};
+
+// start/end are YYYY-MM-DD, inclusive; timeZone is an IANA name, e.g.
+// Intl.DateTimeFormat().resolvedOptions().timeZone.
+export function agenda(start: string, end?: string, timeZone?: string) {
+  return invoke<Agenda>("agenda", { start, end: end ?? null, timeZone: timeZone ?? null });
+}
@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type BackupKind = "manual" | "daily" | "weekly" | "safety";
+
+export type BackupInfo = {
+  id: string;
+  kind: BackupKind;
+  reason: string | null; // what a safety backup was taken before, e.g. "note-delete"
+  created_at: string;
+  size_bytes: number;
+};
+
+export function backupCreate() {
+  return invoke<BackupInfo>("backup_create");
+}
+
+export function backupList() {
+  return invoke<BackupInfo[]>("backup_list");
+}
+
+// Returns the safety backup taken of the state being replaced.
+export function backupRestore(id: string) {
+  return invoke<BackupInfo>("backup_restore", { id });
+}
@@ -0,0 +1,108 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type CalendarEventType = "event" | "block";
+
+export type CalendarEventItem = {
+  id: string;
+  title: string;
+  start_at: string;
+  end_at: string;
+  type: CalendarEventType;
+  task_id: string | null;
+  area_id: string | null;
+  location: string | null;
+  recurrence: string | null;
+  created_at: string;
+};
+
+export type CalendarOccurrence = CalendarEventItem & {
+  occurrence_start: string;
+  occurrence_end: string;
+  recurring: boolean;
+};
+
+export type OccurrenceScope = "this" | "following" | "all";
+
+export type CalendarEventInput = {
+  title: string;
+  startAt: string;
+  endAt: string;
+  eventType?: CalendarEventType;
+  taskId?: string | null;
+  areaId?: string | null;
+  location?: string | null;
+  recurrence?: string | null;
+};
+
+export function calendarEventAdd(input: CalendarEventInput) {
+  return invoke<string>("calendar_event_add", {
+    ...input,
+    eventType: input.eventType ?? null,
+    taskId: input.taskId ?? null,
+    areaId: input.areaId ?? null,
+    location: input.location ?? null,
+    recurrence: input.recurrence ?? null,
+  });
+}
+
+export function calendarEventUpdate(id: string, input: CalendarEventInput) {
+  return invoke<void>("calendar_event_update", {
+    id,
+    ...input,
+    eventType: input.eventType ?? null,
+    taskId: input.taskId ?? null,
+    areaId: input.areaId ?? null,
+    location: input.location ?? null,
+    recurrence: input.recurrence ?? null,
+  });
+}
+
+export function calendarEventDelete(id: string) {
+  return invoke<void>("calendar_event_delete", { id });
+}
+
+export function calendarEventGet(id: string) {
+  return invoke<CalendarEventItem>("calendar_event_get", { id });
+}
+
+export function calendarListRange(start: string, end: string) {
+  return invoke<CalendarOccurrence[]>("calendar_list_range", { start, end });
+}
+
+export function calendarEventUpdateOccurrence(
+  id: string,
+  occurrenceStart: string,
+  scope: OccurrenceScope,
+  changes: Omit<CalendarEventInput, "recurrence">
+) {
+  return invoke<string>("calendar_event_update_occurrence", {
+    id,
+    occurrenceStart,
+    scope,
+    changes: {
+      title: changes.title,
+      start_at: changes.startAt,
+      end_at: changes.endAt,
+      event_type: changes.eventType ?? null,
+      task_id: changes.taskId ?? null,
+      area_id: changes.areaId ?? null,
+      location: changes.location ?? null,
+    },
+  });
+}
+
+export function calendarEventDeleteOccurrence(
+  id: string,
+  occurrenceStart: string,
+  scope: OccurrenceScope
+) {
+  return invoke<void>("calendar_event_delete_occurrence", { id, occurrenceStart, scope });
+}
+
+export function calendarBlockFromTask(taskId: string, startAt: string, endAt?: string) {
+  return invoke<string>("calendar_block_from_task", {
+    taskId,
+    startAt,
+    endAt: endAt ?? null,
+  });
+}
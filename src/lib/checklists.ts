@@ -0,0 +1,37 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type ChecklistItem = {
+  id: string;
+  task_id: string;
+  text: string;
+  done: boolean;
+  position: number;
+  created_at: string;
+  completed_at: string | null;
+};
+
+export function checklistList(taskId: string) {
+  return invoke<ChecklistItem[]>("checklist_list", { taskId });
+}
+
+export function checklistAdd(taskId: string, text: string) {
+  return invoke<ChecklistItem>("checklist_add", { taskId, text });
+}
+
+// Omitted fields are left unchanged.
+export function checklistUpdate(id: string, change: { text?: string; done?: boolean }) {
+  return invoke<ChecklistItem>("checklist_update", {
+    id,
+    text: change.text ?? null,
+    done: change.done ?? null,
+  });
+}
+
+export function checklistDelete(id: string) {
+  return invoke<void>("checklist_delete", { id });
+}
+
+// Items not named keep their relative order after the named ones.
+export function checklistReorder(taskId: string, ids: string[]) {
+  return invoke<ChecklistItem[]>("checklist_reorder", { taskId, ids });
+}
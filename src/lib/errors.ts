@@ -0,0 +1,42 @@
+// Commands reject with { code, message, rule? } (see src-tauri/src/error.rs).
+export type ErrorCode =
+  | "NotFound"
+  | "Validation"
+  | "RuleViolation"
+  | "Db"
+  | "Ai"
+  | "Io";
+
+export type Rule = "next_action_required" | "open_subtasks";
+
+export type AuralisError = {
+  code: ErrorCode;
+  message: string;
+  rule?: Rule;
+};
+
+export function isAuralisError(e: unknown): e is AuralisError {
+  return (
+    typeof e === "object" &&
+    e !== null &&
+    "code" in e &&
+    "message" in e &&
+    typeof (e as AuralisError).message === "string"
+  );
+}
+
+export function isRuleViolation(e: unknown, rule: Rule) {
+  return isAuralisError(e) && e.code === "RuleViolation" && e.rule === rule;
+}
+
+// Text for an error banner. Rule violations get a hint on how to fix them.
+export function errorMessage(e: unknown): string {
+  if (isRuleViolation(e, "next_action_required")) {
+    return "This project needs a next action: add an open task first, or pause the project.";
+  }
+  if (isRuleViolation(e, "open_subtasks")) {
+    return "This task still has open subtasks: finish them first, or complete them together with it.";
+  }
+  if (isAuralisError(e)) return e.message;
+  return String(e);
+}
@@ -0,0 +1,82 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+// Omitted settings take the defaults: 25/5/15 minutes, a long break every 4 rounds, 4 rounds.
+export type FocusConfig = {
+  work_minutes: number;
+  short_break_minutes: number;
+  long_break_minutes: number;
+  long_break_every: number;
+  rounds: number;
+};
+
+export type FocusPhase = "work" | "short_break" | "long_break";
+
+export type FocusStatus = {
+  session_id: string;
+  task_id: string;
+  task_title: string;
+  config: FocusConfig;
+  phase: FocusPhase;
+  round: number; // 1-based; during a break, the round just finished
+  phase_started_at: string;
+  phase_ends_at: string; // pushed back by pauses
+  remaining_seconds: number;
+  paused: boolean;
+  rounds_completed: number;
+  focused_seconds: number;
+};
+
+export type PhaseChange = {
+  session_id: string;
+  from: FocusPhase;
+  to: FocusPhase;
+  round: number;
+  at: string;
+};
+
+export type FocusSummary = {
+  session_id: string;
+  task_id: string;
+  rounds_completed: number;
+  focused_seconds: number;
+  stopped_early: boolean;
+};
+
+// Work is logged as time entries on the task as each stretch ends.
+export function focusStart(taskId: string, config?: Partial<FocusConfig>) {
+  return invoke<FocusStatus>("focus_start", { taskId, config: config ?? null });
+}
+
+export function focusStatus() {
+  return invoke<FocusStatus | null>("focus_status");
+}
+
+export function focusPause() {
+  return invoke<FocusStatus>("focus_pause");
+}
+
+export function focusResume() {
+  return invoke<FocusStatus>("focus_resume");
+}
+
+export function focusSkip() {
+  return invoke<FocusStatus>("focus_skip");
+}
+
+export function focusStop() {
+  return invoke<FocusStatus>("focus_stop");
+}
+
+// Fired once a second while a session runs.
+export function onFocusTick(handler: (status: FocusStatus) => void) {
+  return listen<FocusStatus>("focus:tick", (e) => handler(e.payload));
+}
+
+export function onFocusPhaseChanged(handler: (change: PhaseChange) => void) {
+  return listen<PhaseChange>("focus:phase_changed", (e) => handler(e.payload));
+}
+
+export function onFocusCompleted(handler: (summary: FocusSummary) => void) {
+  return listen<FocusSummary>("focus:completed", (e) => handler(e.payload));
+}
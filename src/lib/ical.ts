@@ -0,0 +1,23 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type IcalImportSummary = {
+  events_created: number;
+  events_updated: number;
+  tasks_created: number;
+  tasks_updated: number;
+  warnings: string[];
+};
+
+export type IcalExportSummary = {
+  path: string;
+  events: number;
+  tasks: number;
+};
+
+export function icalImport(path: string, areaId?: string | null) {
+  return invoke<IcalImportSummary>("ical_import", { path, areaId: areaId ?? null });
+}
+
+export function icalExport(path: string) {
+  return invoke<IcalExportSummary>("ical_export", { path });
+}
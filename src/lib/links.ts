@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Backlink } from "./notes";
+
+export type LinkKind = "note" | "task" | "project";
+
+export type OutgoingLink = {
+  position: number; // offset of the link in the note's content
+  kind: LinkKind | null; // the prefix as written, e.g. [[task:...]]
+  target_text: string;
+  heading: string | null;
+  label: string | null;
+  target_kind: LinkKind | null; // null while the link matches nothing
+  target_id: string | null;
+  target_title: string | null;
+};
+
+export type EntityLinks = {
+  outgoing: OutgoingLink[]; // only notes have any
+  backlinks: Backlink[];
+};
+
+export function entityLinks(kind: LinkKind, id: string) {
+  return invoke<EntityLinks>("entity_links", { kind, id });
+}
@@ -26,10 +26,11 @@ export function noteAdd(
   });
 }
 
-export function noteList(areaId?: string | null, projectId?: string | null) {
+export function noteList(areaId?: string | null, projectId?: string | null, tag?: string) {
   return invoke<NoteItem[]>("note_list", {
     areaId: areaId ?? null,
     projectId: projectId ?? null,
+    tag: tag ?? null,
   });
 }
 
@@ -57,3 +58,50 @@ export function noteGet(id: string) {
   return invoke<NoteItem>("note_get", { id });
 }
 
+
+export type NoteRevision = {
+  id: number;
+  note_id: string;
+  title: string;
+  content: string;
+  area_id: string | null;
+  project_id: string | null;
+  note_created_at: string;
+  edited_at: string; // when this version was written
+  reason: "update" | "delete"; // what replaced it
+  created_at: string; // when it was replaced
+};
+
+export type NoteRevisionDiff = {
+  from: { revision_id: number | null; title: string; edited_at: string };
+  to: { revision_id: number | null; title: string; edited_at: string };
+  lines: { op: "equal" | "delete" | "insert"; text: string }[];
+};
+
+export function noteHistory(id: string) {
+  return invoke<NoteRevision[]>("note_history", { id });
+}
+
+export function noteDeletedList() {
+  return invoke<NoteRevision[]>("note_deleted_list");
+}
+
+// Omit a or b (null) to compare against the current version.
+export function noteRevisionDiff(id: string, a: number | null, b: number | null) {
+  return invoke<NoteRevisionDiff>("note_revision_diff", { id, a, b });
+}
+
+export function noteRestore(id: string, revisionId: number) {
+  return invoke<NoteItem>("note_restore", { id, revisionId });
+}
+
+export type Backlink = {
+  note_id: string;
+  title: string;
+  updated_at: string;
+  contexts: string[]; // the lines the links are on
+};
+
+export function noteBacklinks(id: string) {
+  return invoke<Backlink[]>("note_backlinks", { id });
+}
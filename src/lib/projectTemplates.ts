@@ -0,0 +1,71 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ProjectItem, ProjectPriority } from "./projects";
+import type { TaskItem } from "./tasks";
+
+export type TemplateTask = {
+  id: string;
+  position: number;
+  title: string;
+  priority: ProjectPriority;
+  due_offset_days: number | null; // days from the anchor date; negative for before it
+  estimate_minutes: number | null;
+  notes: string | null;
+};
+
+export type ProjectTemplate = {
+  id: string;
+  name: string;
+  note: string | null; // becomes the project's starter note
+  tasks: TemplateTask[];
+  created_at: string;
+  updated_at: string;
+};
+
+export type TemplateTaskInput = {
+  title: string;
+  priority?: ProjectPriority; // normal when omitted
+  due_offset_days?: number | null;
+  estimate_minutes?: number | null;
+  notes?: string | null;
+};
+
+// Saving replaces the whole template, tasks included; at least one task is required.
+export type ProjectTemplateInput = {
+  name: string;
+  note?: string | null;
+  tasks: TemplateTaskInput[];
+};
+
+export type TemplateInstance = {
+  project: ProjectItem;
+  tasks: TaskItem[];
+  note_id: string | null;
+};
+
+export function projectTemplateList() {
+  return invoke<ProjectTemplate[]>("project_template_list");
+}
+
+export function projectTemplateGet(id: string) {
+  return invoke<ProjectTemplate>("project_template_get", { id });
+}
+
+// Creates a template without id, otherwise replaces it.
+export function projectTemplateSave(template: ProjectTemplateInput, id?: string) {
+  return invoke<ProjectTemplate>("project_template_save", { id: id ?? null, template });
+}
+
+export function projectTemplateDelete(id: string) {
+  return invoke<void>("project_template_delete", { id });
+}
+
+// anchorDate is YYYY-MM-DD. The project is created active, named after the
+// template unless a name is given.
+export function projectFromTemplate(templateId: string, anchorDate: string, areaId?: string, name?: string) {
+  return invoke<TemplateInstance>("project_from_template", {
+    templateId,
+    areaId: areaId ?? null,
+    name: name ?? null,
+    anchorDate,
+  });
+}
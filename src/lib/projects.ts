@@ -2,12 +2,27 @@ import { invoke } from "@tauri-apps/api/core";
 
 export type ProjectStatus = "paused" | "active" | "completed";
 
+export type ProjectPriority = "low" | "normal" | "high";
+
 export type ProjectItem = {
     id: string;
     area_id: string;
     name: string;
     status: ProjectStatus;
+    goal: string | null;
+    deadline_at: string | null;
+    priority: ProjectPriority | null;
     created_at: string;
+    completed_at: string | null;
+};
+
+// Omitted fields are left unchanged; null clears goal, deadline_at and priority.
+export type ProjectPatch = {
+    name?: string;
+    area_id?: string;
+    goal?: string | null;
+    deadline_at?: string | null;
+    priority?: ProjectPriority | null;
 };
 
 export function projectAdd(name: string, areaId?: string) {
@@ -17,8 +32,8 @@ export function projectAdd(name: string, areaId?: string) {
     });
 }
 
-export function projectList(status?: ProjectStatus) {
-    return invoke<ProjectItem[]>("project_list", { status: status ?? null });
+export function projectList(status?: ProjectStatus, tag?: string) {
+    return invoke<ProjectItem[]>("project_list", { status: status ?? null, tag: tag ?? null });
 }
 
 export function projectSetStatus(id: string, status: ProjectStatus) {
@@ -27,4 +42,40 @@ export function projectSetStatus(id: string, status: ProjectStatus) {
 
 export function projectGet(id: string) {
   return invoke<ProjectItem>("project_get", { id });
-}
\ No newline at end of file
+}
+// [[links]] to the project in notes are rewritten to the new name.
+export function projectRename(id: string, name: string) {
+  return invoke<void>("project_rename", { id, name });
+}
+
+// Names are unique within an area. moveTasks takes the tasks along to the new area.
+export function projectUpdate(id: string, patch: ProjectPatch, moveTasks = false) {
+  return invoke<ProjectItem>("project_update", { id, patch, moveTasks });
+}
+
+// done/paused follow the project status; otherwise at_risk (overdue tasks or a
+// deadline under 7 days away), stalled (nothing completed for 14 days) or on_track.
+export type ProjectHealth = "on_track" | "at_risk" | "stalled" | "paused" | "done";
+
+export type ProjectStats = {
+  project_id: string;
+  open_tasks: number; // todo or doing
+  done_tasks: number;
+  deferred_tasks: number;
+  percent_complete: number; // of all tasks, deferred included
+  overdue_tasks: number;
+  last_completed_at: string | null;
+  days_since_last_completion: number | null;
+  deadline_at: string | null;
+  days_to_deadline: number | null; // negative once passed
+  health: ProjectHealth;
+};
+
+export function projectStats(id: string) {
+  return invoke<ProjectStats>("project_stats", { id });
+}
+
+// One call for a whole list, in the order given.
+export function projectStatsBatch(ids: string[]) {
+  return invoke<ProjectStats[]>("project_stats_batch", { ids });
+}
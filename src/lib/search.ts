@@ -0,0 +1,20 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type SearchKind = "note" | "task" | "project" | "inbox";
+
+export type SearchHit = {
+  kind: SearchKind;
+  id: string;
+  // HTML-escaped; matches are wrapped in <mark>
+  title: string;
+  snippet: string;
+  rank: number;
+};
+
+export function search(query: string, kinds?: SearchKind[], limit?: number) {
+  return invoke<SearchHit[]>("search", {
+    query,
+    kinds: kinds ?? null,
+    limit: limit ?? null,
+  });
+}
@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { TaskItem } from "./tasks";
+
+export type SmartList = {
+  id: string;
+  name: string;
+  query: string; // see taskQuery
+  position: number;
+  created_at: string;
+  updated_at: string;
+};
+
+export function smartListList() {
+  return invoke<SmartList[]>("smart_list_list");
+}
+
+// Creates a list when id is omitted.
+export function smartListSave(name: string, query: string, id?: string) {
+  return invoke<SmartList>("smart_list_save", { id: id ?? null, name, query });
+}
+
+export function smartListReorder(ids: string[]) {
+  return invoke<SmartList[]>("smart_list_reorder", { ids });
+}
+
+export function smartListDelete(id: string) {
+  return invoke<void>("smart_list_delete", { id });
+}
+
+export function smartListRun(id: string) {
+  return invoke<TaskItem[]>("smart_list_run", { id });
+}
@@ -0,0 +1,38 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type TagTarget = "task" | "note" | "project" | "inbox";
+
+export type TagItem = {
+  id: string;
+  name: string;
+  created_at: string;
+  usage_count: number;
+};
+
+export function tagList() {
+  return invoke<TagItem[]>("tag_list");
+}
+
+export function tagListFor(kind: TagTarget, id: string) {
+  return invoke<TagItem[]>("tag_list_for", { kind, id });
+}
+
+export function tagAdd(kind: TagTarget, entityId: string, name: string) {
+  return invoke<TagItem>("tag_add", { kind, entityId, name });
+}
+
+export function tagRemove(kind: TagTarget, entityId: string, tagId: string) {
+  return invoke<void>("tag_remove", { kind, entityId, tagId });
+}
+
+export function tagRename(id: string, name: string) {
+  return invoke<TagItem>("tag_rename", { id, name });
+}
+
+export function tagMerge(sourceId: string, targetId: string) {
+  return invoke<TagItem>("tag_merge", { sourceId, targetId });
+}
+
+export function tagDelete(id: string) {
+  return invoke<void>("tag_delete", { id });
+}
@@ -2,17 +2,26 @@ import { invoke } from "@tauri-apps/api/core";
 
 export type TaskStatus = "todo" | "doing" | "done" | "deferred";
 
+export type TaskPriority = "low" | "normal" | "high";
+
 export type TaskItem = {
   id: string;
   area_id: string;
   project_id: string | null;
   title: string;
   status: TaskStatus;
-  priority: "low" | "normal" | "high";
+  priority: TaskPriority;
   due_at: string | null;
   scheduled_at: string | null;
+  estimate_minutes: number | null;
+  notes: string | null;
+  recurrence: string | null;
   created_at: string;
   completed_at: string | null;
+  parent_task_id: string | null;
+  // Waiting on a task that is not done yet.
+  blocked: boolean;
+  updated_at: string; // last edit to the task itself
 };
 
 export function taskAdd(title: string, areaId?: string, projectId?: string) {
@@ -23,12 +32,69 @@ export function taskAdd(title: string, areaId?: string, projectId?: string) {
   });
 }
 
-export function taskList(status?: TaskStatus) {
-  return invoke<TaskItem[]>("task_list", { status: status ?? null });
+export function taskList(status?: TaskStatus, tag?: string) {
+  return invoke<TaskItem[]>("task_list", { status: status ?? null, tag: tag ?? null });
+}
+
+// e.g. `status:todo,doing due<+7d tag:@phone sort:due`
+export function taskQuery(query: string) {
+  return invoke<TaskItem[]>("task_query", { query });
+}
+
+export type StatusChange = {
+  // The next instance when a repeating task is completed.
+  next_id: string | null;
+  // Tasks that were waiting on this one and are now free to start.
+  unblocked: TaskItem[];
+};
+
+// A task with open subtasks is only completed with `includeSubtasks`, which closes them too.
+export function taskSetStatus(id: string, status: TaskStatus, includeSubtasks = false) {
+  return invoke<StatusChange>("task_set_status", { id, status, includeSubtasks });
+}
+
+export function taskAddSubtask(parentId: string, title: string) {
+  return invoke<string>("task_add_subtask", { parentId, title });
+}
+
+// null moves the task back to the top level.
+export function taskSetParent(id: string, parentId: string | null) {
+  return invoke<TaskItem>("task_set_parent", { id, parentId });
 }
 
-export function taskSetStatus(id: string, status: TaskStatus) {
-  return invoke<void>("task_set_status", { id, status });
+export type Progress = {
+  done: number;
+  total: number;
+};
+
+// `checklist` counts this task's own checklist; `progress` adds every subtask below it.
+export type TaskNode = TaskItem & {
+  checklist: Progress;
+  progress: Progress;
+  children: TaskNode[];
+};
+
+// Without a project, the tasks that are in no project.
+export function taskTree(projectId: string | null) {
+  return invoke<TaskNode[]>("task_tree", { projectId });
+}
+
+export type Dependencies = {
+  depends_on: TaskItem[];
+  dependents: TaskItem[];
+};
+
+export function taskDependencies(id: string) {
+  return invoke<Dependencies>("task_dependencies", { id });
+}
+
+// `id` can't start until `dependsOnId` is done.
+export function taskAddDependency(id: string, dependsOnId: string) {
+  return invoke<Dependencies>("task_add_dependency", { id, dependsOnId });
+}
+
+export function taskRemoveDependency(id: string, dependsOnId: string) {
+  return invoke<Dependencies>("task_remove_dependency", { id, dependsOnId });
 }
 
 export function taskListByProject(projectId: string) {
@@ -40,4 +106,51 @@ export function taskSetProject(id: string, projectId: string | null) {
     id,
     projectId,
   });
-}
\ No newline at end of file
+}
+
+export function taskSetRecurrence(id: string, recurrence: string | null) {
+  return invoke<void>("task_set_recurrence", { id, recurrence });
+}
+
+// Omitted fields are left unchanged; null clears a nullable field.
+export type TaskPatch = {
+  title?: string;
+  area_id?: string;
+  project_id?: string | null;
+  priority?: TaskPriority;
+  due_at?: string | null;
+  scheduled_at?: string | null;
+  estimate_minutes?: number | null;
+  notes?: string | null;
+  recurrence?: string | null;
+};
+
+export function taskUpdate(id: string, patch: TaskPatch) {
+  return invoke<TaskItem>("task_update", { id, patch });
+}
+
+// What quick-add read from the text; dates are UTC ISO strings.
+export type QuickAdd = {
+  title: string;
+  due_at: string | null;
+  scheduled_at: string | null;
+  priority: TaskPriority | null;
+  area_id: string | null;
+  project_id: string | null;
+  estimate_minutes: number | null;
+  warnings: string[];
+};
+
+export type QuickAddResult = {
+  id: string;
+  parsed: QuickAdd;
+};
+
+export function taskQuickAddPreview(text: string) {
+  return invoke<QuickAdd>("task_quick_add_preview", { text });
+}
+
+// e.g. "Call dentist tomorrow 3pm !high #health +Q4Launch ~30m"
+export function taskQuickAdd(text: string) {
+  return invoke<QuickAddResult>("task_quick_add", { text });
+}
@@ -0,0 +1,91 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type TimeEntry = {
+  id: string;
+  task_id: string;
+  task_title: string;
+  started_at: string;
+  ended_at: string | null; // null while the timer runs
+  note: string | null;
+  // Set on a timer left running when the app went away: when it was last seen.
+  // Stop it there (taskStopTimer(interrupted_at)) or resume to count the gap.
+  interrupted_at: string | null;
+  seconds: number;
+  created_at: string;
+};
+
+export type TimeEntryInput = {
+  task_id: string;
+  started_at: string;
+  ended_at: string;
+  note?: string | null;
+};
+
+// Omitted fields are left unchanged; setting ended_at on the running timer stops it.
+export type TimeEntryPatch = {
+  task_id?: string;
+  started_at?: string;
+  ended_at?: string;
+  note?: string | null;
+};
+
+export type ReportGroup = "task" | "project" | "area" | "day";
+
+export type ReportRow = {
+  key: string | null; // null for time on tasks in no project
+  label: string;
+  actual_seconds: number;
+  estimate_minutes: number | null;
+  task_count: number;
+};
+
+export type TimeReport = {
+  time_zone: string | null;
+  group_by: ReportGroup;
+  start_at: string;
+  end_at: string;
+  rows: ReportRow[];
+  total_seconds: number;
+};
+
+// Stops a timer running on another task first.
+export function taskStartTimer(taskId: string) {
+  return invoke<TimeEntry>("task_start_timer", { taskId });
+}
+
+export function taskStopTimer(endedAt?: string) {
+  return invoke<TimeEntry>("task_stop_timer", { endedAt: endedAt ?? null });
+}
+
+export function taskRunningTimer() {
+  return invoke<TimeEntry | null>("task_running_timer");
+}
+
+export function taskResumeTimer() {
+  return invoke<TimeEntry>("task_resume_timer");
+}
+
+export function timeEntryList(filter: { taskId?: string; from?: string; to?: string } = {}) {
+  return invoke<TimeEntry[]>("time_entry_list", {
+    taskId: filter.taskId ?? null,
+    from: filter.from ?? null,
+    to: filter.to ?? null,
+  });
+}
+
+export function timeEntryAdd(input: TimeEntryInput) {
+  return invoke<TimeEntry>("time_entry_add", { input });
+}
+
+export function timeEntryUpdate(id: string, patch: TimeEntryPatch) {
+  return invoke<TimeEntry>("time_entry_update", { id, patch });
+}
+
+export function timeEntryDelete(id: string) {
+  return invoke<void>("time_entry_delete", { id });
+}
+
+// Dates are YYYY-MM-DD, both days included; timeZone defaults to the system one.
+export function timeReport(start: string, end: string | null, groupBy: ReportGroup, timeZone?: string) {
+  return invoke<TimeReport>("time_report", { start, end, groupBy, timeZone: timeZone ?? null });
+}
@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export type VaultSyncSummary = {
+  files_written: number;
+  notes_imported: number;
+  files_deleted: number;
+  notes_deleted: number;
+  conflicts: string[]; // conflict copies written, relative to the vault
+  warnings: string[];
+};
+
+export function vaultGetDir() {
+  return invoke<string | null>("vault_get_dir");
+}
+
+// Pass null to turn vault sync off. Setting a folder syncs it straight away.
+export function vaultSetDir(dir: string | null) {
+  return invoke<VaultSyncSummary | null>("vault_set_dir", { dir });
+}
+
+export function vaultSync() {
+  return invoke<VaultSyncSummary | null>("vault_sync");
+}
+
+// Fired after a background sync pass that changed something.
+export function onVaultSynced(handler: (summary: VaultSyncSummary) => void) {
+  return listen<VaultSyncSummary>("vault:synced", (e) => handler(e.payload));
+}
+
+export function onVaultError(handler: (message: string) => void) {
+  return listen<string>("vault:error", (e) => handler(e.payload));
+}
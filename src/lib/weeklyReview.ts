@@ -0,0 +1,57 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { CalendarOccurrence } from "./calendar";
+import type { InboxItem } from "./inbox";
+import type { ProjectItem, ProjectStats } from "./projects";
+import type { TaskItem } from "./tasks";
+
+// In order; a review is "done" once the last step is left.
+export type ReviewStep = "inbox" | "projects" | "stale_tasks" | "deferred_tasks" | "calendar" | "done";
+
+export type WeeklyReview = {
+  id: string;
+  step: ReviewStep;
+  stale_days: number; // open tasks not edited for this long count as stale
+  started_at: string;
+  completed_at: string | null;
+  reviewed_items: number;
+};
+
+// Inbox items are cleared by processing them; everything else is ticked off.
+// Ticking an occurrence of a recurring event ticks the whole series.
+export type ReviewItem =
+  | { kind: "inbox"; item: InboxItem }
+  | { kind: "project"; project: ProjectItem; stats: ProjectStats; reviewed: boolean }
+  | { kind: "task"; task: TaskItem; reviewed: boolean }
+  | { kind: "event"; occurrence: CalendarOccurrence; reviewed: boolean };
+
+export type ReviewState = {
+  review: WeeklyReview;
+  items: ReviewItem[]; // what the current step lists, live
+  remaining: number;
+};
+
+// Resumes the review in progress if there is one.
+export function weeklyReviewStart(staleDays?: number) {
+  return invoke<ReviewState>("weekly_review_start", { staleDays: staleDays ?? null });
+}
+
+export function weeklyReviewCurrent() {
+  return invoke<ReviewState | null>("weekly_review_current");
+}
+
+export function weeklyReviewMark(entityId: string, reviewed = true) {
+  return invoke<ReviewState>("weekly_review_mark", { entityId, reviewed });
+}
+
+// Fails while the current step still has items left.
+export function weeklyReviewAdvance() {
+  return invoke<ReviewState>("weekly_review_advance");
+}
+
+export function weeklyReviewAbandon() {
+  return invoke<void>("weekly_review_abandon");
+}
+
+export function weeklyReviewHistory(limit?: number) {
+  return invoke<WeeklyReview[]>("weekly_review_history", { limit: limit ?? null });
+}
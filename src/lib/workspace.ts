@@ -0,0 +1,22 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type ImportMode = "replace" | "merge";
+
+export type WorkspaceExportSummary = {
+  path: string;
+  tables: Record<string, number>; // rows written per table
+};
+
+export type WorkspaceImportSummary = {
+  tables: Record<string, { created: number; updated: number }>;
+  warnings: string[];
+};
+
+export function workspaceExport(path: string) {
+  return invoke<WorkspaceExportSummary>("workspace_export", { path });
+}
+
+// "replace" wipes the current workspace first; "merge" upserts by id.
+export function workspaceImport(path: string, mode: ImportMode) {
+  return invoke<WorkspaceImportSummary>("workspace_import", { path, mode });
+}
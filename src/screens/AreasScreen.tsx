@@ -1,5 +1,6 @@
 import { useEffect, useState } from "react";
 import { areaAdd, areaList, areaSetActive, type AreaItem } from "../lib/areas";
+import { errorMessage } from "../lib/errors";
 
 export default function AreasScreen() {
   const [err, setErr] = useState<string | null>(null);
@@ -14,7 +15,7 @@ export default function AreasScreen() {
       const res = await areaList(nextOnlyActive ? true : undefined);
       setItems(res);
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -34,7 +35,7 @@ export default function AreasScreen() {
       setName("");
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setLoading(false);
     }
@@ -46,7 +47,7 @@ export default function AreasScreen() {
       await areaSetActive(id, active);
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -8,6 +8,7 @@ import {
   type InboxState,
 } from "../lib/inbox";
 import { taskList, type TaskItem } from "../lib/tasks"; // only used to refresh tasks count if you want later
+import { errorMessage } from "../lib/errors";
 
 type InboxFilter = InboxState | "all";
 
@@ -25,7 +26,7 @@ export default function InboxScreen() {
         nextFilter === "all" ? await inboxList() : await inboxList(nextFilter as InboxState);
       setItems(res);
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -46,7 +47,7 @@ export default function InboxScreen() {
       setFilter("unprocessed");
       await refresh("unprocessed");
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setLoading(false);
     }
@@ -58,7 +59,7 @@ export default function InboxScreen() {
       await inboxSetState(id, state);
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -70,7 +71,7 @@ export default function InboxScreen() {
       // optional: could refresh tasks screen via shared state later
       await taskList("todo").catch(() => {});
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -8,6 +8,7 @@ import {
 } from "../lib/notes";
 import { projectList, type ProjectItem } from "../lib/projects";
 import { aiSummariseNote } from "../lib/ai";
+import { errorMessage } from "../lib/errors";
 
 export default function NotesScreen() {
   const [err, setErr] = useState<string | null>(null);
@@ -38,7 +39,7 @@ export default function NotesScreen() {
         setSelectedId(null);
       }
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -65,7 +66,7 @@ export default function NotesScreen() {
       await refresh();
       setSelectedId(id);
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setSaving(false);
     }
@@ -85,7 +86,7 @@ export default function NotesScreen() {
       );
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setSaving(false);
     }
@@ -100,7 +101,7 @@ export default function NotesScreen() {
       setSelectedId(null);
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setSaving(false);
     }
@@ -131,7 +132,7 @@ export default function NotesScreen() {
 
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setSaving(false);
     }
@@ -151,7 +152,7 @@ export default function NotesScreen() {
       );
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setSaving(false);
     }
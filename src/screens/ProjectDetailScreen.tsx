@@ -14,6 +14,7 @@ import {
   type TaskStatus,
 } from "../lib/tasks";
 import { noteList, noteAdd, type NoteItem } from "../lib/notes";
+import { errorMessage } from "../lib/errors";
 
 export default function ProjectDetailScreen() {
   const { id } = useParams<{ id: string }>();
@@ -45,7 +46,7 @@ export default function ProjectDetailScreen() {
       const ns = await noteList(null, projectId);
       setNotes(ns);
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -66,7 +67,7 @@ export default function ProjectDetailScreen() {
       setTaskText("");
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setLoading(false);
     }
@@ -80,8 +81,8 @@ export default function ProjectDetailScreen() {
       await projectSetStatus(project.id, status);
       await refresh();
     } catch (e: any) {
-      // DB trigger errors (e.g. activate without open task) show here
-      setErr(String(e));
+      // Trigger errors (e.g. activate without open task) come back as rule violations
+      setErr(errorMessage(e));
     }
   }
 
@@ -91,7 +92,7 @@ export default function ProjectDetailScreen() {
       await taskSetStatus(id, status);
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -107,7 +108,7 @@ export default function ProjectDetailScreen() {
       setNoteTitle("");
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setLoading(false);
     }
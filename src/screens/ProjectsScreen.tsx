@@ -7,6 +7,7 @@ import {
   type ProjectItem,
   type ProjectStatus,
 } from "../lib/projects";
+import { errorMessage } from "../lib/errors";
 
 type Filter = ProjectStatus | "all";
 
@@ -24,7 +25,7 @@ export default function ProjectsScreen() {
         next === "all" ? await projectList() : await projectList(next);
       setItems(res);
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -45,7 +46,7 @@ export default function ProjectsScreen() {
       setFilter("paused");
       await refresh("paused");
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setLoading(false);
     }
@@ -57,8 +58,8 @@ export default function ProjectsScreen() {
       await projectSetStatus(id, status);
       await refresh();
     } catch (e: any) {
-      // DB trigger errors (e.g. activating without open task) land here
-      setErr(String(e));
+      // Trigger errors (e.g. activating without open task) come back as rule violations
+      setErr(errorMessage(e));
     }
   }
 
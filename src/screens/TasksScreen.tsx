@@ -1,6 +1,6 @@
 import { useEffect, useState } from "react";
 import {
-  taskAdd,
+  taskQuickAdd,
   taskList,
   taskSetStatus,
   taskSetProject,
@@ -8,6 +8,7 @@ import {
   type TaskStatus,
 } from "../lib/tasks";
 import { projectList, type ProjectItem } from "../lib/projects";
+import { errorMessage } from "../lib/errors";
 
 type TaskFilter = TaskStatus | "all";
 
@@ -32,7 +33,7 @@ export default function TasksScreen() {
       const ps = await projectList();
       setProjects(ps);
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -48,12 +49,12 @@ export default function TasksScreen() {
     setLoading(true);
     setErr(null);
     try {
-      await taskAdd(title);
+      await taskQuickAdd(title);
       setText("");
       setFilter("todo");
       await refresh("todo");
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     } finally {
       setLoading(false);
     }
@@ -65,7 +66,7 @@ export default function TasksScreen() {
       await taskSetStatus(id, status);
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -75,7 +76,7 @@ export default function TasksScreen() {
       await taskSetProject(id, projectId);
       await refresh();
     } catch (e: any) {
-      setErr(String(e));
+      setErr(errorMessage(e));
     }
   }
 
@@ -89,7 +90,7 @@ export default function TasksScreen() {
         <input
           value={text}
           onChange={(e) => setText(e.target.value)}
-          placeholder="Add task…"
+          placeholder="Add task… (e.g. Call dentist tomorrow 3pm !high #health ~30m)"
           style={{
             flex: 1,
             padding: 10,